@@ -0,0 +1,56 @@
+//! Derive macros for bevy_tileset_map
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields};
+
+/// Derive `MapId` for a fieldless enum
+///
+/// Each variant is converted to its discriminant, so variants can be given explicit IDs. The
+/// enum must also derive (or implement) `Clone`, `Copy`, `PartialEq`, `Eq`, and `Hash`.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(MapId, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Maps {
+///   Overworld,
+///   Dungeon = 10,
+/// }
+/// ```
+#[proc_macro_derive(MapId)]
+pub fn derive_map_id(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+
+	let data = match &input.data {
+		Data::Enum(data) => data,
+		_ => {
+			return Error::new_spanned(&input.ident, "MapId can only be derived for enums")
+				.to_compile_error()
+				.into()
+		},
+	};
+
+	if let Some(variant) = data
+		.variants
+		.iter()
+		.find(|variant| !matches!(variant.fields, Fields::Unit))
+	{
+		return Error::new_spanned(variant, "MapId can only be derived for fieldless enums")
+			.to_compile_error()
+			.into();
+	}
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	TokenStream::from(quote! {
+		impl #impl_generics ::core::convert::From<#ident #ty_generics> for u16 #where_clause {
+			fn from(value: #ident #ty_generics) -> Self {
+				value as u16
+			}
+		}
+
+		impl #impl_generics ::bevy_tileset_map::prelude::MapId for #ident #ty_generics #where_clause {}
+	})
+}
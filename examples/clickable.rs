@@ -30,7 +30,7 @@ fn main() {
 		// Adds the `bevy_tileset` plugin
 		.add_plugin(TilesetPlugin::default())
 		// Adds this crate's plugn
-		.add_plugin(TilesetMapPlugin)
+		.add_plugin(TilesetMapPlugin::default())
 		// /== Required === //
 		// === Exmaple-Specific === //
 		.init_resource::<MyTileset>()
@@ -188,7 +188,8 @@ fn on_tile_click(
 fn on_keypress(
 	keys: Res<Input<KeyCode>>,
 	mut build_mode: ResMut<BuildMode>,
-	mut serializer: TilemapSerializer,
+	serializer: TilemapSerializer,
+	mut deserializer: TilemapDeserializer,
 	mut saved: ResMut<SavedMap>,
 ) {
 	if keys.just_pressed(KeyCode::W) {
@@ -217,13 +218,10 @@ fn on_keypress(
 		build_mode.active_layer = 2u16;
 	} else if keys.just_pressed(KeyCode::Comma) {
 		saved.map = serializer.save_maps();
-		println!(
-			"{}",
-			serde_json::to_string(&saved.map.as_ref().unwrap()).unwrap()
-		);
+		println!("{}", saved.map.as_ref().unwrap().to_json().unwrap());
 	} else if keys.just_pressed(KeyCode::Period) {
 		if let Some(map) = &saved.map {
-			serializer.load_maps(map);
+			deserializer.load_maps(map).unwrap();
 		}
 	}
 }
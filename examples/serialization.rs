@@ -1,4 +1,4 @@
-//! This example shows what loading a serialized tilemap might look like using the `TilemapSerializer`
+//! This example shows what loading a serialized tilemap might look like using the `TilemapDeserializer`
 //! system parameter.
 
 use std::fs;
@@ -28,7 +28,7 @@ fn main() {
 		// Adds the `bevy_tileset` plugin
 		.add_plugin(TilesetPlugin::default())
 		// Adds this crate's plugn
-		.add_plugin(TilesetMapPlugin)
+		.add_plugin(TilesetMapPlugin::default())
 		// /== Required === //
 		// === Exmaple-Specific === //
 		.add_state(MapState::LoadingTileset)
@@ -116,12 +116,12 @@ fn can_load_maps(mut can_load: Local<bool>, mut state: ResMut<State<MapState>>)
 
 /// A system used to load the saved tilemaps from disk
 ///
-/// The `TilemapSerializer` is a special system param that allows for entire tilemaps to be saved and loaded. Here,
+/// The `TilemapDeserializer` is a special system param that allows for entire tilemaps to be loaded. Here,
 /// we are using it to load a JSON file containing our already saved data.
-fn load_maps(mut serializer: TilemapSerializer) {
+fn load_maps(mut deserializer: TilemapDeserializer) {
 	let path = FileAssetIo::get_root_path().join("assets/map.json");
 	let data = fs::read_to_string(path).unwrap();
-	let maps = serde_json::from_str::<SerializableTilemap>(&data).unwrap();
+	let maps = SerializableTilemap::from_json(&data).unwrap();
 
-	serializer.load_maps(&maps);
+	deserializer.load_maps(&maps).unwrap();
 }
@@ -0,0 +1,165 @@
+//! Tools for taking over tilemaps spawned by other plugins
+
+use bevy::prelude::Entity;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileId, TilesetId};
+
+use crate::manager::{TilemapLayer, TilemapManager};
+use crate::placement::{MapId, TilePlacementError};
+
+/// A mapping from the texture indices of a foreign tilemap to the tiles of a tileset
+///
+/// This is used by [`TilemapManager::adopt_map`] to find the tile each existing texture index
+/// stands for. Every mapped tile belongs to the mapping's tileset.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{TextureIndexMapping, TileId};
+/// # let (grass, dirt, wall) = (TileId::new(0, 0), TileId::new(1, 0), TileId::new(2, 0));
+/// // The texture indices used by an LDtk tileset
+/// let mapping = TextureIndexMapping::new(0)
+///   .with_tile(0, grass)
+///   .with_tile(1, dirt)
+///   .with_tile(12, wall);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureIndexMapping {
+	tileset_id: TilesetId,
+	tiles: HashMap<u32, TileId>,
+}
+
+impl TextureIndexMapping {
+	/// Create an empty mapping to the given tileset
+	pub fn new(tileset_id: TilesetId) -> Self {
+		Self {
+			tileset_id,
+			tiles: HashMap::default(),
+		}
+	}
+
+	/// The tileset the texture indices are mapped to
+	pub fn tileset_id(&self) -> TilesetId {
+		self.tileset_id
+	}
+
+	/// Map the given texture index to a tile
+	pub fn insert<Id: Into<TileId>>(&mut self, texture_index: u32, tile_id: Id) -> &mut Self {
+		let tile_id = TileId {
+			tileset_id: self.tileset_id,
+			..tile_id.into()
+		};
+		self.tiles.insert(texture_index, tile_id);
+		self
+	}
+
+	/// Map the given texture index to a tile
+	pub fn with_tile<Id: Into<TileId>>(mut self, texture_index: u32, tile_id: Id) -> Self {
+		self.insert(texture_index, tile_id);
+		self
+	}
+
+	/// Get the tile mapped to the given texture index
+	pub fn get(&self, texture_index: u32) -> Option<&TileId> {
+		self.tiles.get(&texture_index)
+	}
+}
+
+impl<'w, 's> TilemapManager<'w, 's> {
+	/// Take over an existing tilemap (such as one spawned by `bevy_ecs_ldtk`) as the given layer
+	///
+	/// Each tile whose texture index is in the mapping is given the components this crate
+	/// expects, so the [`TilePlacer`] and the serializer can work with it just like any other
+	/// tile: its texture index is replaced by the index of its tile in the tileset, animated tiles
+	/// are animated, and auto tiles are marked as such. The tilemap itself is marked as the given
+	/// layer and switched to the tileset's texture.
+	///
+	/// Tiles missing from the mapping would be drawn with the wrong part of the new texture, so
+	/// if the tilemap doesn't already use the tileset's texture, every tile has to be mapped.
+	/// Otherwise, this fails with [`TilePlacementError::UnmappedTiles`] without modifying the
+	/// tilemap. Tilemaps already using the tileset's texture keep their unmapped tiles as they are.
+	///
+	/// Returns the number of adopted tiles.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::prelude::{Added, Entity, Query, Res};
+	/// # use bevy_ecs_tilemap::prelude::TileStorage;
+	/// # use bevy_tileset_map::prelude::{TextureIndexMapping, TilemapManager};
+	/// # #[derive(bevy::prelude::Resource)]
+	/// # struct LevelMapping(TextureIndexMapping);
+	/// fn adopt_levels(
+	///   mut manager: TilemapManager,
+	///   tilemaps: Query<Entity, Added<TileStorage>>,
+	///   mapping: Res<LevelMapping>,
+	/// ) {
+	///   for (layer_id, tilemap) in tilemaps.iter().enumerate() {
+	///     manager.adopt_map(tilemap, 0u16, layer_id as u16, &mapping.0).unwrap();
+	///   }
+	/// }
+	/// ```
+	///
+	/// [`TilePlacer`]: crate::prelude::TilePlacer
+	pub fn adopt_map<MId: MapId>(
+		&mut self,
+		tilemap: Entity,
+		map_id: MId,
+		layer_id: u16,
+		mapping: &TextureIndexMapping,
+	) -> Result<usize, TilePlacementError> {
+		let map_id = map_id.into();
+		let (storage, texture) = self
+			.storages
+			.get(tilemap)
+			.map_err(|_| TilePlacementError::InvalidTilemap(tilemap))?;
+		let tileset = self
+			.tilesets
+			.get_by_id(&mapping.tileset_id)
+			.ok_or(TilePlacementError::InvalidTileset(mapping.tileset_id))?;
+
+		let tileset_texture = TilemapTexture::Single(tileset.texture().clone());
+
+		// === Resolve === //
+		let mut adopted = Vec::new();
+		let mut unmapped = 0;
+		for entity in storage.iter().flatten() {
+			let tile_id = match self
+				.tiles
+				.get(*entity)
+				.ok()
+				.and_then(|(_, index)| mapping.get(index.0))
+			{
+				Some(tile_id) => *tile_id,
+				None => {
+					unmapped += 1;
+					continue;
+				},
+			};
+			let index = tileset
+				.get_tile_index_by_id(tile_id)
+				.ok_or(TilePlacementError::InvalidTile(tile_id))?;
+			adopted.push((*entity, tile_id, index));
+		}
+		if unmapped > 0 && texture != Some(&tileset_texture) {
+			return Err(TilePlacementError::UnmappedTiles {
+				tilemap,
+				count: unmapped,
+			});
+		}
+
+		// === Adopt === //
+		let count = adopted.len();
+		for (entity, tile_id, index) in adopted {
+			let mut cmds = self.commands.entity(entity);
+			crate::builder::insert_tile(&mut cmds, tileset, tile_id, index);
+			cmds.insert(TileParent { map_id, layer_id });
+		}
+
+		self.commands
+			.entity(tilemap)
+			.insert((TilemapLayer { map_id, layer_id }, tileset_texture));
+		Ok(count)
+	}
+}
@@ -0,0 +1,236 @@
+//! Tools for controlling the animation of placed tiles
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Changed, Commands, Component, DetectChanges, Entity, Query, Ref};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileIndex, TilesetParent, Tilesets};
+
+use crate::manager::TilemapLayer;
+use crate::placement::MapId;
+
+/// Marks an animated tile as paused
+///
+/// Contains the speed the animation will return to once resumed.
+///
+/// Animations are paused by setting their speed to zero. This component is removed whenever the
+/// tile's texture changes (such as when it is replaced or its auto tile state changes), since the
+/// new tile starts with the animation defined by its tileset.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub struct PausedAnimation {
+	/// The speed of the animation before it was paused
+	pub speed: f32,
+}
+
+/// A system param used to control the animation of placed animated tiles
+///
+/// Tiles can be addressed by entity or by position. Methods return `false` if the tile could not
+/// be found or is not animated.
+///
+/// Replacing a tile always resets its animation to the one defined by its tileset, discarding
+/// any paused state or custom speed.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::TileAnimator;
+/// fn freeze_water(mut animator: TileAnimator) {
+///   animator.pause_at(TilePos { x: 3, y: 4 }, 0u16, 0);
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct TileAnimator<'w, 's> {
+	tiles: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+			&'static mut AnimatedTile,
+			Option<&'static PausedAnimation>,
+		),
+	>,
+	/// Used to find the tile at a position
+	storages: Query<'w, 's, (&'static TilemapLayer, &'static TileStorage)>,
+	tilesets: Tilesets<'w, 's>,
+	commands: Commands<'w, 's>,
+}
+
+impl<'w, 's> TileAnimator<'w, 's> {
+	/// Pause the animation of the given tile
+	///
+	/// Pausing an already paused tile does nothing. Since the pause is only recorded once the
+	/// system's commands are applied, tiles with a speed of zero (such as ones paused earlier in
+	/// the same system) are treated as already paused.
+	pub fn pause(&mut self, entity: Entity) -> bool {
+		let (.., mut anim, paused) = match self.tiles.get_mut(entity) {
+			Ok(tile) => tile,
+			Err(..) => return false,
+		};
+
+		if paused.is_none() && anim.speed != 0.0 {
+			self.commands
+				.entity(entity)
+				.insert(PausedAnimation { speed: anim.speed });
+			anim.speed = 0.0;
+		}
+		true
+	}
+
+	/// Resume the animation of the given tile
+	///
+	/// Resuming a tile that isn't paused does nothing.
+	pub fn resume(&mut self, entity: Entity) -> bool {
+		let (.., mut anim, paused) = match self.tiles.get_mut(entity) {
+			Ok(tile) => tile,
+			Err(..) => return false,
+		};
+
+		if let Some(paused) = paused {
+			anim.speed = paused.speed;
+			self.commands.entity(entity).remove::<PausedAnimation>();
+		}
+		true
+	}
+
+	/// Returns true if the animation of the given tile is paused
+	pub fn is_paused(&self, entity: Entity) -> bool {
+		matches!(self.tiles.get(entity), Ok((.., Some(_))))
+	}
+
+	/// Set the animation speed of the given tile
+	///
+	/// If the tile is paused, the new speed is used once it is resumed.
+	pub fn set_speed(&mut self, entity: Entity, speed: f32) -> bool {
+		let (.., mut anim, paused) = match self.tiles.get_mut(entity) {
+			Ok(tile) => tile,
+			Err(..) => return false,
+		};
+
+		if paused.is_some() {
+			self.commands
+				.entity(entity)
+				.insert(PausedAnimation { speed });
+		} else {
+			anim.speed = speed;
+		}
+		true
+	}
+
+	/// Reset the animation of the given tile to the one defined by its tileset
+	///
+	/// This resumes the tile if it was paused.
+	pub fn reset(&mut self, entity: Entity) -> bool {
+		let (_, index, tileset, mut anim, paused) = match self.tiles.get_mut(entity) {
+			Ok(tile) => tile,
+			Err(..) => return false,
+		};
+
+		let tile_index = self.tilesets.get_by_id(&tileset.0).and_then(|tileset| {
+			let id = tileset.get_tile_id(&(index.0 as usize))?;
+			tileset.get_tile_index_by_id(*id)
+		});
+		let (start, end, speed) = match tile_index {
+			Some(TileIndex::Animated(start, end, speed)) => (start, end, speed),
+			_ => return false,
+		};
+
+		anim.start = start as u32;
+		anim.end = end as u32;
+		anim.speed = speed;
+		if paused.is_some() {
+			self.commands.entity(entity).remove::<PausedAnimation>();
+		}
+		true
+	}
+
+	/// Pause the animation of the tile at the given position
+	pub fn pause_at<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> bool {
+		match self.get_entity(pos, map_id, layer_id) {
+			Some(entity) => self.pause(entity),
+			None => false,
+		}
+	}
+
+	/// Resume the animation of the tile at the given position
+	pub fn resume_at<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> bool {
+		match self.get_entity(pos, map_id, layer_id) {
+			Some(entity) => self.resume(entity),
+			None => false,
+		}
+	}
+
+	/// Set the animation speed of the tile at the given position
+	pub fn set_speed_at<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		speed: f32,
+	) -> bool {
+		match self.get_entity(pos, map_id, layer_id) {
+			Some(entity) => self.set_speed(entity, speed),
+			None => false,
+		}
+	}
+
+	/// Reset the animation of the tile at the given position
+	pub fn reset_at<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> bool {
+		match self.get_entity(pos, map_id, layer_id) {
+			Some(entity) => self.reset(entity),
+			None => false,
+		}
+	}
+
+	/// Get the animated tile entity at the given position
+	fn get_entity<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<Entity> {
+		let map_id = map_id.into();
+		let (_, storage) = self
+			.storages
+			.iter()
+			.find(|(layer, _)| layer.map_id == map_id && layer.layer_id == layer_id)?;
+		let entity = storage.checked_get(&pos.into())?;
+		self.tiles.contains(entity).then_some(entity)
+	}
+}
+
+/// __\[SYSTEM\]__ Clears the paused state of tiles whose texture has changed
+///
+/// This ensures replaced tiles always start with the animation defined by their tileset. Tiles
+/// that kept their [`AnimatedTile`] (such as auto tiles changing their texture) get their saved
+/// speed back, while tiles paused since this last ran are left paused.
+pub(crate) fn reset_replaced_animations(
+	mut commands: Commands,
+	mut query: Query<(Entity, &mut AnimatedTile, Ref<PausedAnimation>), Changed<TileTextureIndex>>,
+) {
+	for (entity, mut anim, paused) in query.iter_mut() {
+		if paused.is_changed() {
+			continue;
+		}
+		if !anim.is_added() {
+			anim.speed = paused.speed;
+		}
+		commands.entity(entity).remove::<PausedAnimation>();
+	}
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::Resource;
+use bevy::utils::{HashMap, HashSet};
+use bevy_tileset::auto::AutoTileId;
+use bevy_tileset::prelude::{TileGroupId, TilesetId};
+
+/// A resource used to connect auto tiles of different groups
+///
+/// By default, auto tiles only consider tiles of their own group as matching neighbors. Any group
+/// connected to an auto tile group here is also considered a match, allowing different terrain to
+/// join seamlessly (such as a dirt path connecting to a stone path).
+///
+/// Connections are one-way: connecting dirt to stone makes dirt tiles treat stone tiles as
+/// neighbors, but not the other way around. Use [`connect_both`](Self::connect_both) to
+/// connect both groups to each other.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{AutoTileConnections, TileId};
+/// # let (dirt, stone) = (TileId::new(0, 0), TileId::new(1, 0));
+/// let mut connections = AutoTileConnections::default();
+/// connections.connect_both(dirt.tileset_id, dirt.group_id, stone.group_id);
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AutoTileConnections {
+	connections: HashMap<(TilesetId, TileGroupId), HashSet<TileGroupId>>,
+}
+
+impl AutoTileConnections {
+	/// Make the auto tiles of group `from` treat the auto tiles of group `to` as neighbors
+	pub fn connect(
+		&mut self,
+		tileset_id: TilesetId,
+		from: TileGroupId,
+		to: TileGroupId,
+	) -> &mut Self {
+		if from != to {
+			self.connections
+				.entry((tileset_id, from))
+				.or_insert_with(HashSet::default)
+				.insert(to);
+		}
+		self
+	}
+
+	/// Make the auto tiles of groups `a` and `b` treat each other as neighbors
+	pub fn connect_both(
+		&mut self,
+		tileset_id: TilesetId,
+		a: TileGroupId,
+		b: TileGroupId,
+	) -> &mut Self {
+		self.connect(tileset_id, a, b).connect(tileset_id, b, a)
+	}
+
+	/// Remove the connection from group `from` to group `to`
+	pub fn disconnect(&mut self, tileset_id: TilesetId, from: TileGroupId, to: TileGroupId) {
+		if let Some(groups) = self.connections.get_mut(&(tileset_id, from)) {
+			groups.remove(&to);
+		}
+	}
+
+	/// Returns true if the given auto tile treats the other auto tile as a neighbor
+	///
+	/// Auto tiles of the same group are always connected.
+	pub fn is_connected(&self, auto_id: &AutoTileId, other: &AutoTileId) -> bool {
+		if auto_id.tileset_id != other.tileset_id {
+			return false;
+		}
+
+		auto_id.group_id == other.group_id
+			|| self
+				.connections
+				.get(&(auto_id.tileset_id, auto_id.group_id))
+				.map_or(false, |groups| groups.contains(&other.group_id))
+	}
+
+	/// Returns true if no groups are connected
+	pub fn is_empty(&self) -> bool {
+		self.connections.values().all(HashSet::is_empty)
+	}
+}
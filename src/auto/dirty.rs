@@ -0,0 +1,156 @@
+use crate::auto::connections::AutoTileConnections;
+use crate::auto::layers::{apply_connected_layers, connected_tiles, AutoTileLayers};
+use crate::auto::traits::{directions_mut, neighbor_coords, TileInfo, TilemapCache};
+use crate::coord::TileCoord;
+use crate::grid::{MapGrid, MapGridLookup};
+use bevy::prelude::{Component, Entity, Resource};
+use bevy::tasks::{ComputeTaskPool, ParallelSlice};
+use bevy::utils::{HashMap, HashSet};
+use bevy_tileset::auto::{AutoTile, AutoTileRequest, AutoTilemap};
+use bevy_tileset::prelude::AutoTileRule;
+
+/// The number of affected tiles above which their rules are generated in parallel
+const PARALLEL_THRESHOLD: usize = 1024;
+
+/// Marks an auto tile whose texture was already resolved when it was placed
+///
+/// Changes to these tiles aren't marked as dirty, so they keep their texture until one of their
+/// neighbors changes. The marker is removed the first time the tile is skipped.
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub(crate) struct ResolvedAutoTile;
+
+/// A resource containing the coordinates that changed since auto tiles were last updated
+///
+/// Both placement and removal of auto tiles mark their coordinates as dirty. Once per frame,
+/// every auto tile at or around a dirty coordinate is resolved exactly once, no matter how
+/// many of its neighbors changed.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct DirtyAutoTiles {
+	coords: HashSet<TileCoord>,
+	/// The number of tiles resolved since this was last taken
+	resolved: usize,
+}
+
+impl DirtyAutoTiles {
+	/// Mark the given coordinates as dirty
+	pub fn mark(&mut self, coord: TileCoord) {
+		self.coords.insert(coord);
+	}
+
+	/// Returns true if nothing has been marked as dirty
+	pub fn is_empty(&self) -> bool {
+		self.coords.is_empty()
+	}
+
+	/// Create a request for every auto tile affected by the dirty coordinates, clearing them
+	///
+	/// Each affected tile appears in the returned requests exactly once.
+	pub(super) fn resolve(
+		&mut self,
+		lookup: &TilemapCache,
+		layers: &AutoTileLayers,
+		connections: &AutoTileConnections,
+		grids: &MapGridLookup,
+	) -> Vec<AutoTileRequest<TileInfo>> {
+		let mut affected: HashMap<Entity, TileInfo> = HashMap::default();
+		for coord in self.coords.drain() {
+			let grid = grids.get(coord.map_id, coord.layer_id);
+			let tiles = std::iter::once(Some(coord))
+				.chain(neighbor_coords(&coord, &grid, coord.layer_id))
+				.flatten()
+				.filter_map(|coord| lookup.get_tile_at(&coord))
+				.chain(connected_tiles(lookup, layers, grids, &coord));
+
+			for tile in tiles {
+				affected.entry(tile.entity).or_insert(tile);
+			}
+		}
+
+		let tiles = affected.into_values().collect::<Vec<_>>();
+		let mut requests = if tiles.len() < PARALLEL_THRESHOLD {
+			tiles
+				.into_iter()
+				.map(|tile| AutoTileRequest {
+					rule: generate_rule(
+						&tile,
+						&grids.get(tile.coord.map_id, tile.coord.layer_id),
+						connections,
+						|coord| lookup.get_tile_at(coord),
+					),
+					tile,
+				})
+				.collect::<Vec<_>>()
+		} else {
+			resolve_parallel(&tiles, lookup, connections, grids)
+		};
+		apply_connected_layers(&mut requests, lookup, layers, grids);
+		self.resolved += requests.len();
+		requests
+	}
+
+	/// Take the number of tiles resolved since this was last called
+	pub fn take_resolved(&mut self) -> usize {
+		std::mem::take(&mut self.resolved)
+	}
+}
+
+/// Generate the rules for the given tiles in parallel
+///
+/// Since the tilemap can't be queried from multiple threads, this first takes a snapshot of
+/// every auto tile, which each thread then reads the neighbors of its tiles from.
+fn resolve_parallel(
+	tiles: &[TileInfo],
+	lookup: &TilemapCache,
+	connections: &AutoTileConnections,
+	grids: &MapGridLookup,
+) -> Vec<AutoTileRequest<TileInfo>> {
+	let snapshot = lookup.tiles_query.snapshot();
+	let task_pool = ComputeTaskPool::get();
+	let chunk_size = (tiles.len() / task_pool.thread_num().max(1)).max(1);
+
+	tiles
+		.par_chunk_map(task_pool, chunk_size, |chunk| {
+			chunk
+				.iter()
+				.map(|tile| AutoTileRequest {
+					rule: generate_rule(
+						tile,
+						&grids.get(tile.coord.map_id, tile.coord.layer_id),
+						connections,
+						|coord| snapshot.get(coord).copied(),
+					),
+					tile: *tile,
+				})
+				.collect::<Vec<_>>()
+		})
+		.into_iter()
+		.flatten()
+		.collect()
+}
+
+/// Generate the rule describing the matching neighbors of the given tile
+///
+/// Neighboring tiles are found using `get_tile_at`, and match if they're of the same group or a
+/// group connected to it in the [`AutoTileConnections`].
+fn generate_rule<F: Fn(&TileCoord) -> Option<TileInfo>>(
+	tile: &TileInfo,
+	grid: &MapGrid,
+	connections: &AutoTileConnections,
+	get_tile_at: F,
+) -> AutoTileRule {
+	let mut rule = AutoTileRule::default();
+	let neighbors = neighbor_coords(&tile.coord, grid, tile.coord.layer_id);
+	for (neighbor, dir) in neighbors.iter().zip(directions_mut(&mut rule)) {
+		let is_match = neighbor
+			.and_then(|coord| get_tile_at(&coord))
+			.map(|neighbor| {
+				tile.can_match(&neighbor)
+					|| connections.is_connected(&tile.auto_tile, &neighbor.auto_tile)
+			})
+			.unwrap_or_default();
+		if is_match {
+			*dir = Some(true);
+		}
+	}
+	rule
+}
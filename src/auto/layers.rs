@@ -0,0 +1,125 @@
+use crate::auto::traits::{directions_mut, neighbor_coords, TileInfo, TilemapCache};
+use crate::coord::TileCoord;
+use crate::grid::MapGridLookup;
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use bevy_tileset::auto::{AutoTileId, AutoTileRequest, AutoTilemap};
+use bevy_tileset::prelude::{TileGroupId, TilesetId};
+
+/// A resource used to connect auto tiles to tiles on other layers
+///
+/// By default, auto tiles only consider matching tiles on their own layer as neighbors. Any layer
+/// connected to an auto tile group here is also considered: a tile on a connected layer counts as
+/// a neighbor regardless of its type. This allows terrain spanning multiple layers (such as a
+/// cliff layer placed over a ground layer) to connect seamlessly.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AutoTileLayers {
+	connections: HashMap<(TilesetId, TileGroupId, u16), Vec<u16>>,
+}
+
+impl AutoTileLayers {
+	/// Connect the given auto tile group on `layer_id` to the tiles on `neighbor_layers`
+	pub fn connect<I: IntoIterator<Item = u16>>(
+		&mut self,
+		tileset_id: TilesetId,
+		group_id: TileGroupId,
+		layer_id: u16,
+		neighbor_layers: I,
+	) -> &mut Self {
+		let layers = self
+			.connections
+			.entry((tileset_id, group_id, layer_id))
+			.or_insert_with(Vec::new);
+		for layer in neighbor_layers {
+			if layer != layer_id && !layers.contains(&layer) {
+				layers.push(layer);
+			}
+		}
+		self
+	}
+
+	/// Remove all layer connections for the given auto tile group on `layer_id`
+	pub fn disconnect(&mut self, tileset_id: TilesetId, group_id: TileGroupId, layer_id: u16) {
+		self.connections.remove(&(tileset_id, group_id, layer_id));
+	}
+
+	/// Get the layers connected to the given auto tile group on `layer_id`
+	pub fn neighbor_layers(&self, auto_id: &AutoTileId, layer_id: u16) -> &[u16] {
+		self.connections
+			.get(&(auto_id.tileset_id, auto_id.group_id, layer_id))
+			.map(|layers| layers.as_slice())
+			.unwrap_or_default()
+	}
+
+	/// Returns true if no layers are connected
+	pub fn is_empty(&self) -> bool {
+		self.connections.is_empty()
+	}
+
+	/// Get the auto tile groups (and their layer) that are connected to the given layer
+	fn dependents(
+		&self,
+		layer_id: u16,
+	) -> impl Iterator<Item = (TilesetId, TileGroupId, u16)> + '_ {
+		self.connections
+			.iter()
+			.filter(move |(_, layers)| layers.contains(&layer_id))
+			.map(|(key, _)| *key)
+	}
+}
+
+/// Get the auto tiles on other layers that are connected to the tile at the given coordinates
+pub(super) fn connected_tiles(
+	lookup: &TilemapCache,
+	layers: &AutoTileLayers,
+	grids: &MapGridLookup,
+	coord: &TileCoord,
+) -> Vec<TileInfo> {
+	let grid = grids.get(coord.map_id, coord.layer_id);
+	let mut tiles = Vec::new();
+	for (tileset_id, group_id, layer_id) in layers.dependents(coord.layer_id) {
+		for neighbor in neighbor_coords(coord, &grid, layer_id)
+			.into_iter()
+			.flatten()
+		{
+			if let Some(neighbor) = lookup.get_tile_at(&neighbor) {
+				if neighbor.auto_tile.tileset_id == tileset_id
+					&& neighbor.auto_tile.group_id == group_id
+				{
+					tiles.push(neighbor);
+				}
+			}
+		}
+	}
+	tiles
+}
+
+/// Mark the neighbors found on connected layers in each request's rule
+pub(super) fn apply_connected_layers(
+	requests: &mut [AutoTileRequest<TileInfo>],
+	lookup: &TilemapCache,
+	layers: &AutoTileLayers,
+	grids: &MapGridLookup,
+) {
+	for request in requests.iter_mut() {
+		let TileInfo {
+			coord, auto_tile, ..
+		} = request.tile;
+		let grid = grids.get(coord.map_id, coord.layer_id);
+		for layer_id in layers.neighbor_layers(&auto_tile, coord.layer_id) {
+			let neighbors = neighbor_coords(&coord, &grid, *layer_id);
+			for (neighbor, dir) in neighbors.iter().zip(directions_mut(&mut request.rule)) {
+				if dir.unwrap_or_default() {
+					continue;
+				}
+
+				let exists = neighbor
+					.map(|neighbor| lookup.contains_tile(&neighbor))
+					.unwrap_or_default();
+				if exists {
+					*dir = Some(true);
+				}
+			}
+		}
+	}
+}
@@ -1,5 +1,16 @@
+mod connections;
+mod dirty;
+mod layers;
+mod rules;
 mod systems;
 mod traits;
 
+pub use connections::AutoTileConnections;
+pub(crate) use dirty::{DirtyAutoTiles, ResolvedAutoTile};
+pub use layers::AutoTileLayers;
+pub use rules::{
+	AddAutoTileRules, AutoTileRuleSet, AutoTileRules, CardinalRuleSet, CornerRuleSet,
+	HexOrientation, HexRuleSet, NeighborGroups, WangCornerRuleSet,
+};
 pub use systems::RemoveAutoTileEvent;
 pub(crate) use systems::{on_change_auto_tile, on_remove_auto_tile};
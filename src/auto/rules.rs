@@ -0,0 +1,487 @@
+use bevy::prelude::{App, Resource};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::HexCoordSystem;
+use bevy_tileset::auto::AutoTileId;
+use bevy_tileset::prelude::{AutoTileRule, TileGroupId, TileIndex, TilesetId};
+
+use crate::transform::TileTransform;
+
+/// A set of adjacency rules used to select the texture of an auto tile
+///
+/// By default, auto tiles use the rules defined in their tileset. Registering a rule set for an
+/// auto tile group in the [`AutoTileRules`] resource overrides this, allowing for tiling schemes
+/// that can't be expressed by the tileset's rules.
+///
+/// Any `Fn(&AutoTileRule) -> Option<TileIndex>` closure can be used as a rule set.
+///
+/// # Neighborhoods
+///
+/// The directions of an [`AutoTileRule`] depend on the map type, for both rule sets and the
+/// rules defined in a tileset:
+///
+/// * __Square__ and __isometric__ maps have all eight neighbors. For isometric maps, directions
+///   are in grid space (so `north` of a diamond map points up and to the right on screen).
+/// * __Row__-oriented hexagonal maps have six neighbors: `north`, `north_east`, `south_east`,
+///   `south`, `south_west`, and `north_west` (`east` and `west` are always unset).
+/// * __Column__-oriented hexagonal maps have six neighbors: `north_east`, `east`, `south_east`,
+///   `south_west`, `west`, and `north_west` (`north` and `south` are always unset).
+///
+/// See [`HexRuleSet`] for a rule set covering every combination of hexagonal neighbors.
+pub trait AutoTileRuleSet: Send + Sync + 'static {
+	/// Select the texture for an auto tile, given the state of its neighbors
+	///
+	/// Every direction of `neighbors` is set, with `true` meaning a matching auto tile exists
+	/// in that direction. Returning `None` falls back to the rules defined in the tileset.
+	fn select(&self, neighbors: &AutoTileRule) -> Option<TileIndex>;
+
+	/// Select the flip and rotation for an auto tile, given the state of its neighbors
+	///
+	/// Returning `None` leaves the tile's current flip untouched. By default, rule sets don't
+	/// specify a transform.
+	fn transform(&self, neighbors: &AutoTileRule) -> Option<TileTransform> {
+		let _ = neighbors;
+		None
+	}
+
+	/// Select the texture for an auto tile, given the groups of its neighbors
+	///
+	/// This is checked before [`select`](Self::select), allowing rule sets to tell apart
+	/// neighbors of different groups. Returning `None` falls back to `select`. By default, rule
+	/// sets only consider which neighbors match.
+	fn select_groups(&self, groups: &NeighborGroups) -> Option<TileIndex> {
+		let _ = groups;
+		None
+	}
+
+	/// Select the flip and rotation for an auto tile, given the groups of its neighbors
+	///
+	/// This is checked before [`transform`](Self::transform). Returning `None` falls back to
+	/// `transform`.
+	fn transform_groups(&self, groups: &NeighborGroups) -> Option<TileTransform> {
+		let _ = groups;
+		None
+	}
+}
+
+/// The auto tile groups surrounding an auto tile
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NeighborGroups {
+	/// The group of the tile itself
+	pub group: TileGroupId,
+	/// The group of the auto tile in each direction
+	///
+	/// These are ordered `north`, `east`, `south`, `west`, `north_east`, `north_west`,
+	/// `south_east`, and `south_west`. Only auto tiles of the same tileset and layer are
+	/// included: any other neighbor is `None`.
+	pub neighbors: [Option<TileGroupId>; 8],
+}
+
+impl<F: Fn(&AutoTileRule) -> Option<TileIndex> + Send + Sync + 'static> AutoTileRuleSet for F {
+	fn select(&self, neighbors: &AutoTileRule) -> Option<TileIndex> {
+		self(neighbors)
+	}
+}
+
+/// A 16-tile rule set that only considers the four cardinal neighbors
+///
+/// This covers both "16-tile" terrain sets and pipe-style tiles (where each tile connects to
+/// its matching cardinal neighbors). The tile used is chosen by a bitmask of the connected
+/// neighbors: `north = 1`, `east = 2`, `south = 4`, and `west = 8`.
+#[derive(Debug, Copy, Clone)]
+pub struct CardinalRuleSet {
+	tiles: [TileIndex; 16],
+	transforms: Option<[TileTransform; 16]>,
+}
+
+impl CardinalRuleSet {
+	/// Create a rule set from the tiles for each bitmask value
+	pub fn new(tiles: [TileIndex; 16]) -> Self {
+		Self {
+			tiles,
+			transforms: None,
+		}
+	}
+
+	/// Set the flip and rotation applied to the tile for each bitmask value
+	///
+	/// This allows a single texture to be reused for several bitmask values (such as using one
+	/// rotated corner texture for all four corners).
+	pub fn with_transforms(mut self, transforms: [TileTransform; 16]) -> Self {
+		self.transforms = Some(transforms);
+		self
+	}
+
+	/// Get the bitmask of the connected cardinal neighbors
+	pub fn mask(neighbors: &AutoTileRule) -> usize {
+		[
+			neighbors.north,
+			neighbors.east,
+			neighbors.south,
+			neighbors.west,
+		]
+		.iter()
+		.enumerate()
+		.filter(|(_, dir)| dir.unwrap_or_default())
+		.fold(0, |mask, (bit, _)| mask | (1 << bit))
+	}
+}
+
+impl AutoTileRuleSet for CardinalRuleSet {
+	fn select(&self, neighbors: &AutoTileRule) -> Option<TileIndex> {
+		Some(self.tiles[Self::mask(neighbors)])
+	}
+
+	fn transform(&self, neighbors: &AutoTileRule) -> Option<TileTransform> {
+		Some(self.transforms?[Self::mask(neighbors)])
+	}
+}
+
+/// A 16-tile rule set for Wang 2-corner terrain blending
+///
+/// Rather than matching edges, this selects tiles based on the terrain of the four corners of a
+/// tile. A corner belongs to this tile's terrain group if all three neighbors sharing that corner
+/// also belong to it. Otherwise the corner belongs to the terrain beneath it, allowing this
+/// group's tiles to blend into the neighboring terrain.
+///
+/// The tile used is chosen by a bitmask of the corners belonging to this group:
+/// `north_east = 1`, `south_east = 2`, `south_west = 4`, and `north_west = 8`.
+///
+/// Each corner is either this group's terrain or the terrain beneath it, so this blends exactly
+/// two terrains. To blend several terrains, use a [`WangCornerRuleSet`] instead.
+#[derive(Debug, Copy, Clone)]
+pub struct CornerRuleSet {
+	tiles: [TileIndex; 16],
+	transforms: Option<[TileTransform; 16]>,
+}
+
+impl CornerRuleSet {
+	/// Create a rule set from the tiles for each bitmask value
+	pub fn new(tiles: [TileIndex; 16]) -> Self {
+		Self {
+			tiles,
+			transforms: None,
+		}
+	}
+
+	/// Set the flip and rotation applied to the tile for each bitmask value
+	///
+	/// This allows a single texture to be reused for several bitmask values (such as using one
+	/// rotated corner texture for all four corners).
+	pub fn with_transforms(mut self, transforms: [TileTransform; 16]) -> Self {
+		self.transforms = Some(transforms);
+		self
+	}
+
+	/// Get the bitmask of the corners belonging to this tile's terrain group
+	pub fn mask(neighbors: &AutoTileRule) -> usize {
+		let is_set = |dir: Option<bool>| dir.unwrap_or_default();
+		let corner = |a, b, c| is_set(a) && is_set(b) && is_set(c);
+		[
+			corner(neighbors.north, neighbors.north_east, neighbors.east),
+			corner(neighbors.south, neighbors.south_east, neighbors.east),
+			corner(neighbors.south, neighbors.south_west, neighbors.west),
+			corner(neighbors.north, neighbors.north_west, neighbors.west),
+		]
+		.iter()
+		.enumerate()
+		.filter(|(_, is_corner)| **is_corner)
+		.fold(0, |mask, (bit, _)| mask | (1 << bit))
+	}
+}
+
+impl AutoTileRuleSet for CornerRuleSet {
+	fn select(&self, neighbors: &AutoTileRule) -> Option<TileIndex> {
+		Some(self.tiles[Self::mask(neighbors)])
+	}
+
+	fn transform(&self, neighbors: &AutoTileRule) -> Option<TileTransform> {
+		Some(self.transforms?[Self::mask(neighbors)])
+	}
+}
+
+/// A rule set for Wang corner terrain blending between several terrain groups
+///
+/// Each corner of a tile takes the lowest terrain among the tiles sharing it (this tile and the
+/// three neighbors around that corner), where terrains are ordered from bottom to top. Neighbors
+/// that aren't of one of the terrain groups are ignored. The tile used is then chosen by the
+/// terrains of its four corners, ordered `north_east`, `south_east`, `south_west`, and
+/// `north_west`.
+///
+/// Register the same rule set for every terrain group it blends, so that each tile sees the
+/// terrains around it.
+#[derive(Debug, Clone)]
+pub struct WangCornerRuleSet {
+	terrains: Vec<TileGroupId>,
+	tiles: HashMap<[TileGroupId; 4], (TileIndex, Option<TileTransform>)>,
+}
+
+impl WangCornerRuleSet {
+	/// Create a rule set blending the given terrain groups, ordered from bottom to top
+	pub fn new<I: IntoIterator<Item = TileGroupId>>(terrains: I) -> Self {
+		Self {
+			terrains: terrains.into_iter().collect(),
+			tiles: HashMap::default(),
+		}
+	}
+
+	/// Set the tile used for the given corner terrains
+	pub fn with_tile(self, corners: [TileGroupId; 4], index: TileIndex) -> Self {
+		self.insert_tile(corners, index, None)
+	}
+
+	/// Set the tile used for the given corner terrains, along with its flip and rotation
+	///
+	/// This allows a single texture to be reused for several corner combinations.
+	pub fn with_transformed_tile(
+		self,
+		corners: [TileGroupId; 4],
+		index: TileIndex,
+		transform: TileTransform,
+	) -> Self {
+		self.insert_tile(corners, index, Some(transform))
+	}
+
+	/// Get the terrain of each corner of a tile
+	///
+	/// Returns `None` if the tile itself isn't of one of the terrain groups
+	pub fn corners(&self, groups: &NeighborGroups) -> Option<[TileGroupId; 4]> {
+		let rank = |group: &TileGroupId| self.terrains.iter().position(|terrain| terrain == group);
+		let center = rank(&groups.group)?;
+		let [north, east, south, west, north_east, north_west, south_east, south_west] =
+			groups.neighbors;
+		let corner = |sharing: [Option<TileGroupId>; 3]| {
+			sharing
+				.iter()
+				.flatten()
+				.filter_map(rank)
+				.fold(center, usize::min)
+		};
+		Some(
+			[
+				corner([north, north_east, east]),
+				corner([south, south_east, east]),
+				corner([south, south_west, west]),
+				corner([north, north_west, west]),
+			]
+			.map(|rank| self.terrains[rank]),
+		)
+	}
+
+	fn insert_tile(
+		mut self,
+		corners: [TileGroupId; 4],
+		index: TileIndex,
+		transform: Option<TileTransform>,
+	) -> Self {
+		self.tiles.insert(corners, (index, transform));
+		self
+	}
+}
+
+impl AutoTileRuleSet for WangCornerRuleSet {
+	fn select(&self, _neighbors: &AutoTileRule) -> Option<TileIndex> {
+		None
+	}
+
+	fn select_groups(&self, groups: &NeighborGroups) -> Option<TileIndex> {
+		let (index, _) = self.tiles.get(&self.corners(groups)?)?;
+		Some(*index)
+	}
+
+	fn transform_groups(&self, groups: &NeighborGroups) -> Option<TileTransform> {
+		self.tiles.get(&self.corners(groups)?)?.1
+	}
+}
+
+/// The orientation of a hexagonal map, determining which six neighbors its tiles have
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HexOrientation {
+	/// Hexagons are laid out in rows (pointy-topped)
+	Row,
+	/// Hexagons are laid out in columns (flat-topped)
+	Column,
+}
+
+impl From<HexCoordSystem> for HexOrientation {
+	fn from(coord_sys: HexCoordSystem) -> Self {
+		match coord_sys {
+			HexCoordSystem::Row | HexCoordSystem::RowEven | HexCoordSystem::RowOdd => Self::Row,
+			HexCoordSystem::Column | HexCoordSystem::ColumnEven | HexCoordSystem::ColumnOdd => {
+				Self::Column
+			},
+		}
+	}
+}
+
+/// A 64-tile rule set for hexagonal maps, considering all six neighbors
+///
+/// The tile used is chosen by a bitmask of the connected neighbors, going clockwise from the
+/// top. For [`HexOrientation::Row`]: `north = 1`, `north_east = 2`, `south_east = 4`,
+/// `south = 8`, `south_west = 16`, and `north_west = 32`. For [`HexOrientation::Column`]:
+/// `north_east = 1`, `east = 2`, `south_east = 4`, `south_west = 8`, `west = 16`, and
+/// `north_west = 32`.
+#[derive(Debug, Copy, Clone)]
+pub struct HexRuleSet {
+	orientation: HexOrientation,
+	tiles: [TileIndex; 64],
+	transforms: Option<[TileTransform; 64]>,
+}
+
+impl HexRuleSet {
+	/// Create a rule set from the tiles for each bitmask value
+	pub fn new<O: Into<HexOrientation>>(orientation: O, tiles: [TileIndex; 64]) -> Self {
+		Self {
+			orientation: orientation.into(),
+			tiles,
+			transforms: None,
+		}
+	}
+
+	/// Set the flip and rotation applied to the tile for each bitmask value
+	///
+	/// This allows a single texture to be reused for several bitmask values (such as using one
+	/// rotated edge texture for all six edges).
+	pub fn with_transforms(mut self, transforms: [TileTransform; 64]) -> Self {
+		self.transforms = Some(transforms);
+		self
+	}
+
+	/// Get the bitmask of the connected neighbors for the given orientation
+	pub fn mask(orientation: HexOrientation, neighbors: &AutoTileRule) -> usize {
+		match orientation {
+			HexOrientation::Row => [
+				neighbors.north,
+				neighbors.north_east,
+				neighbors.south_east,
+				neighbors.south,
+				neighbors.south_west,
+				neighbors.north_west,
+			],
+			HexOrientation::Column => [
+				neighbors.north_east,
+				neighbors.east,
+				neighbors.south_east,
+				neighbors.south_west,
+				neighbors.west,
+				neighbors.north_west,
+			],
+		}
+		.iter()
+		.enumerate()
+		.filter(|(_, dir)| dir.unwrap_or_default())
+		.fold(0, |mask, (bit, _)| mask | (1 << bit))
+	}
+}
+
+impl AutoTileRuleSet for HexRuleSet {
+	fn select(&self, neighbors: &AutoTileRule) -> Option<TileIndex> {
+		Some(self.tiles[Self::mask(self.orientation, neighbors)])
+	}
+
+	fn transform(&self, neighbors: &AutoTileRule) -> Option<TileTransform> {
+		Some(self.transforms?[Self::mask(self.orientation, neighbors)])
+	}
+}
+
+/// A resource containing the custom [`AutoTileRuleSet`]s for auto tile groups
+#[derive(Resource, Default)]
+pub struct AutoTileRules {
+	rules: HashMap<(TilesetId, TileGroupId), Box<dyn AutoTileRuleSet>>,
+}
+
+impl AutoTileRules {
+	/// Register a rule set for the given auto tile group
+	///
+	/// This replaces any rule set previously registered for the group.
+	pub fn insert<R: AutoTileRuleSet>(
+		&mut self,
+		tileset_id: TilesetId,
+		group_id: TileGroupId,
+		rule_set: R,
+	) -> &mut Self {
+		self.rules
+			.insert((tileset_id, group_id), Box::new(rule_set));
+		self
+	}
+
+	/// Remove the rule set for the given auto tile group
+	///
+	/// Returns true if a rule set was removed
+	pub fn remove(&mut self, tileset_id: &TilesetId, group_id: &TileGroupId) -> bool {
+		self.rules.remove(&(*tileset_id, *group_id)).is_some()
+	}
+
+	/// Returns true if a rule set is registered for the given auto tile group
+	pub fn contains(&self, auto_id: &AutoTileId) -> bool {
+		self.rules
+			.contains_key(&(auto_id.tileset_id, auto_id.group_id))
+	}
+
+	/// Select the texture for the given auto tile using its registered rule set (if any)
+	pub fn select(&self, auto_id: &AutoTileId, neighbors: &AutoTileRule) -> Option<TileIndex> {
+		self.rules
+			.get(&(auto_id.tileset_id, auto_id.group_id))?
+			.select(neighbors)
+	}
+
+	/// Select the flip and rotation for the given auto tile using its registered rule set (if any)
+	pub fn transform(
+		&self,
+		auto_id: &AutoTileId,
+		neighbors: &AutoTileRule,
+	) -> Option<TileTransform> {
+		self.rules
+			.get(&(auto_id.tileset_id, auto_id.group_id))?
+			.transform(neighbors)
+	}
+
+	/// Select the texture for the given auto tile from the groups of its neighbors, using its
+	/// registered rule set (if any)
+	pub fn select_groups(
+		&self,
+		auto_id: &AutoTileId,
+		groups: &NeighborGroups,
+	) -> Option<TileIndex> {
+		self.rules
+			.get(&(auto_id.tileset_id, auto_id.group_id))?
+			.select_groups(groups)
+	}
+
+	/// Select the flip and rotation for the given auto tile from the groups of its neighbors,
+	/// using its registered rule set (if any)
+	pub fn transform_groups(
+		&self,
+		auto_id: &AutoTileId,
+		groups: &NeighborGroups,
+	) -> Option<TileTransform> {
+		self.rules
+			.get(&(auto_id.tileset_id, auto_id.group_id))?
+			.transform_groups(groups)
+	}
+}
+
+/// An extension trait for registering [`AutoTileRuleSet`]s on an [`App`]
+pub trait AddAutoTileRules {
+	/// Register a rule set with the [`AutoTileRules`] resource
+	fn add_auto_tile_rules<R: AutoTileRuleSet>(
+		&mut self,
+		tileset_id: TilesetId,
+		group_id: TileGroupId,
+		rule_set: R,
+	) -> &mut Self;
+}
+
+impl AddAutoTileRules for App {
+	fn add_auto_tile_rules<R: AutoTileRuleSet>(
+		&mut self,
+		tileset_id: TilesetId,
+		group_id: TileGroupId,
+		rule_set: R,
+	) -> &mut Self {
+		self.init_resource::<AutoTileRules>();
+		self.world
+			.resource_mut::<AutoTileRules>()
+			.insert(tileset_id, group_id, rule_set);
+		self
+	}
+}
@@ -1,7 +1,15 @@
-use crate::auto::traits::{TileInfo, TilemapCache};
-use bevy::prelude::{Changed, Commands, Entity, EventReader, Query, With};
+use crate::auto::connections::AutoTileConnections;
+use crate::auto::dirty::{DirtyAutoTiles, ResolvedAutoTile};
+use crate::auto::layers::AutoTileLayers;
+use crate::auto::rules::{AutoTileRules, NeighborGroups};
+use crate::auto::traits::{neighbor_coords, TileInfo, TilemapCache};
+use crate::coord::TileCoord;
+use crate::grid::{MapGridLookup, MapGrids};
+use bevy::prelude::{Changed, Commands, Entity, EventReader, Query, Res, ResMut, With};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TileFlip;
 use bevy_ecs_tilemap::{GPUAnimated, MapQuery, Tile, TileParent, TilePos};
-use bevy_tileset::auto::{AutoTileId, AutoTileRequest, AutoTiler};
+use bevy_tileset::auto::{AutoTileId, AutoTileRequest, AutoTilemap};
 use bevy_tileset::prelude::{TileIndex, Tilesets};
 use std::cell::RefCell;
 
@@ -15,12 +23,13 @@ pub struct RemoveAutoTileEvent {
 
 /// __\[SYSTEM\]__ Handles the creation/modification of an auto tile
 ///
-/// This system chooses the appropriate texture based on its surrounding neighbors,
-/// and updates any neighbors of the same type in a similar manner
+/// This system marks changed auto tiles as dirty and then resolves every dirty region: each
+/// auto tile at or around a dirty coordinate chooses the appropriate texture based on its
+/// surrounding neighbors. Every affected tile is only resolved once per frame.
 pub(crate) fn on_change_auto_tile(
 	mut commands: Commands,
 	changed_tiles: Query<
-		(Entity, &TilePos, &TileParent, &AutoTileId),
+		(Entity, &TilePos, &TileParent, Option<&ResolvedAutoTile>),
 		(Changed<AutoTileId>, With<Tile>),
 	>,
 	all_tiles: Query<(Entity, &TilePos, &TileParent, &AutoTileId), With<Tile>>,
@@ -33,29 +42,55 @@ pub(crate) fn on_change_auto_tile(
 		Option<&mut GPUAnimated>,
 	)>,
 	tilesets: Tilesets,
+	rules: Res<AutoTileRules>,
+	layers: Res<AutoTileLayers>,
+	connections: Res<AutoTileConnections>,
+	mut dirty: ResMut<DirtyAutoTiles>,
+	grids: MapGrids,
 	map_query: MapQuery,
 ) {
+	for (entity, pos, parent, resolved) in changed_tiles.iter() {
+		if resolved.is_some() {
+			// Already resolved (such as when loaded with its resolved index) -> skip it once
+			commands.entity(entity).remove::<ResolvedAutoTile>();
+			continue;
+		}
+
+		dirty.mark(TileCoord {
+			pos: *pos,
+			map_id: parent.map_id,
+			layer_id: parent.layer_id,
+		});
+	}
+
 	// Ensure a change happened
-	if changed_tiles.iter().count() < 1 {
+	if dirty.is_empty() {
 		return;
 	}
 
 	let mut map_query_cell = RefCell::new(map_query);
-	let mut cache = TilemapCache {
+	let lookup = TilemapCache {
 		tiles_query: &all_tiles,
 		map_query: &map_query_cell,
 	};
-	let mut tiler = AutoTiler::new(&mut cache);
+	let grids = grids.collect();
+	let requests = dirty.resolve(&lookup, &layers, &connections, &grids);
 
-	for (entity, pos, parent, auto_tile) in changed_tiles.iter() {
-		tiler.add_tile(TileInfo::new(entity, pos, parent, auto_tile), true);
-	}
-
-	let requests = tiler.finish();
+	// Only tiles with a custom rule set can make use of the groups of their neighbors
+	let groups = requests
+		.iter()
+		.filter(|request| rules.contains(&request.tile.auto_tile))
+		.map(|request| {
+			let groups = neighbor_groups(&request.tile, &lookup, &grids);
+			(request.tile.entity, groups)
+		})
+		.collect::<HashMap<_, _>>();
 
 	apply_requests(
 		&requests,
+		&groups,
 		&tilesets,
+		&rules,
 		&mut working_tiles,
 		&mut commands,
 		map_query_cell.get_mut(),
@@ -64,57 +99,47 @@ pub(crate) fn on_change_auto_tile(
 
 /// __\[SYSTEM\]__ Handles the removal of auto tiles
 ///
-/// Specifically, notifies the surrounding auto tiles of the change
-/// This method needs to be called after the removal but within the same frame, otherwise
-/// the query will be empty
+/// Specifically, marks the coordinates of removed auto tiles as dirty so that the surrounding
+/// auto tiles are updated by [`on_change_auto_tile`] within the same frame
 pub(crate) fn on_remove_auto_tile(
 	mut event: EventReader<RemoveAutoTileEvent>,
-	// All tiles (used for the tilemap cache)
-	all_tiles: Query<(Entity, &TilePos, &TileParent, &AutoTileId), With<Tile>>,
-	mut working_tiles: Query<(
-		Entity,
-		&TilePos,
-		&mut Tile,
-		&TileParent,
-		&AutoTileId,
-		Option<&mut GPUAnimated>,
-	)>,
-	tilesets: Tilesets,
-	map_query: MapQuery,
-	mut commands: Commands,
+	mut dirty: ResMut<DirtyAutoTiles>,
 ) {
-	let mut map_query_cell = RefCell::new(map_query);
-	let mut cache = TilemapCache {
-		tiles_query: &all_tiles,
-		map_query: &map_query_cell,
-	};
-	let mut tiler = AutoTiler::new(&mut cache);
-
-	for ref evt in event.iter() {
-		let RemoveAutoTileEvent {
-			entity,
-			pos,
-			parent,
-			auto_id,
-		} = evt;
-		tiler.add_tile(TileInfo::new(*entity, pos, parent, auto_id), true);
+	for evt in event.iter() {
+		dirty.mark(TileCoord {
+			pos: evt.pos,
+			map_id: evt.parent.map_id,
+			layer_id: evt.parent.layer_id,
+		});
 	}
+}
 
-	let requests = tiler.finish();
-
-	apply_requests(
-		&requests,
-		&tilesets,
-		&mut working_tiles,
-		&mut commands,
-		map_query_cell.get_mut(),
-	);
+/// Get the groups of the auto tiles surrounding the given tile
+///
+/// Only auto tiles of the same tileset on the same layer are included.
+fn neighbor_groups(
+	tile: &TileInfo,
+	lookup: &TilemapCache,
+	grids: &MapGridLookup,
+) -> NeighborGroups {
+	let grid = grids.get(tile.coord.map_id, tile.coord.layer_id);
+	let neighbors = neighbor_coords(&tile.coord, &grid, tile.coord.layer_id).map(|coord| {
+		let neighbor = lookup.get_tile_at(&coord?)?;
+		(neighbor.auto_tile.tileset_id == tile.auto_tile.tileset_id)
+			.then_some(neighbor.auto_tile.group_id)
+	});
+	NeighborGroups {
+		group: tile.auto_tile.group_id,
+		neighbors,
+	}
 }
 
 /// Applies the given rule requests
 fn apply_requests(
 	requests: &[AutoTileRequest<TileInfo>],
+	groups: &HashMap<Entity, NeighborGroups>,
 	tilesets: &Tilesets,
+	rules: &AutoTileRules,
 	query: &mut Query<(
 		Entity,
 		&TilePos,
@@ -133,15 +158,25 @@ fn apply_requests(
 		{
 			if let Some(tileset) = tilesets.get_by_id(&auto_tile.tileset_id) {
 				if let Some(tile_name) = tileset.get_tile_name(&auto_tile.group_id) {
+					// --- Check Custom Rules --- //
+					let groups = groups.get(&entity);
+					let custom_index = groups
+						.and_then(|groups| rules.select_groups(auto_tile, groups))
+						.or_else(|| rules.select(auto_tile, &rule));
+
 					// --- Check If Variant --- //
 					let texture_index = tile.texture_index as usize;
-					if tileset.is_auto_variant(tile_name, &texture_index, &rule) {
+					if custom_index.is_none()
+						&& tileset.is_auto_variant(tile_name, &texture_index, &rule)
+					{
 						// The request index is just a variant of the correct state -> skip it
 						continue;
 					}
 
 					// --- Apply Rule --- //
-					if let Some(index) = tileset.get_auto_index(tile_name, rule) {
+					if let Some(index) =
+						custom_index.or_else(|| tileset.get_auto_index(tile_name, rule))
+					{
 						match index {
 							TileIndex::Standard(idx) => {
 								tile.texture_index = idx as u16;
@@ -170,6 +205,17 @@ fn apply_requests(
 							},
 						}
 
+						// --- Apply Transform --- //
+						let transform = groups
+							.and_then(|groups| rules.transform_groups(auto_tile, groups))
+							.or_else(|| rules.transform(auto_tile, &rule));
+						if let Some(transform) = transform {
+							let flip = TileFlip::from(transform);
+							tile.flip_x = flip.x;
+							tile.flip_y = flip.y;
+							tile.flip_d = flip.d;
+						}
+
 						// --- Notify Chunk --- //
 						map_query.notify_chunk_for_tile(*pos, parent.map_id, parent.layer_id);
 					}
@@ -1,8 +1,11 @@
 use crate::coord::TileCoord;
+use crate::grid::MapGrid;
 use bevy::math::IVec2;
 use bevy::prelude::{Entity, Query, With};
+use bevy::utils::HashMap;
 use bevy_ecs_tilemap::{MapQuery, Tile, TileParent, TilePos};
 use bevy_tileset::auto::{AutoTile, AutoTileId};
+use bevy_tileset::prelude::AutoTileRule;
 use std::cell::RefCell;
 
 /// A trait over `Query<'w, 's, (Entity, &TilePos, &TileParent, &AutoTile), With<Tile>>` to prevent errors with
@@ -10,6 +13,8 @@ use std::cell::RefCell;
 pub(super) trait TileQuery {
 	fn find_tile(&self, entity: Entity) -> Option<TileInfo>;
 	fn count(&self) -> usize;
+	/// Collect every auto tile, keyed by its coordinates
+	fn snapshot(&self) -> HashMap<TileCoord, TileInfo>;
 }
 
 impl<'w, 's> TileQuery for Query<'w, 's, (Entity, &TilePos, &TileParent, &AutoTileId), With<Tile>> {
@@ -24,6 +29,15 @@ impl<'w, 's> TileQuery for Query<'w, 's, (Entity, &TilePos, &TileParent, &AutoTi
 	fn count(&self) -> usize {
 		self.iter().count()
 	}
+
+	fn snapshot(&self) -> HashMap<TileCoord, TileInfo> {
+		self.iter()
+			.map(|(entity, pos, parent, auto_tile)| {
+				let tile = TileInfo::new(entity, pos, parent, auto_tile);
+				(tile.coord, tile)
+			})
+			.collect()
+	}
 }
 
 /// Defines a tile
@@ -71,6 +85,16 @@ impl bevy_tileset::auto::AutoTile for TileInfo {
 	}
 }
 
+impl<'a, 'w, 's> TilemapCache<'a, 'w, 's> {
+	/// Returns true if any tile (auto or not) exists at the given coordinates
+	pub fn contains_tile(&self, coords: &TileCoord) -> bool {
+		self.map_query
+			.borrow_mut()
+			.get_tile_entity(coords.pos, coords.map_id, coords.layer_id)
+			.is_ok()
+	}
+}
+
 impl<'a, 'w, 's> bevy_tileset::auto::AutoTilemap for TilemapCache<'a, 'w, 's> {
 	type Tile = TileInfo;
 
@@ -102,3 +126,37 @@ impl<'a, 'w, 's> bevy_tileset::auto::AutoTilemap for TilemapCache<'a, 'w, 's> {
 		self.tiles_query.count()
 	}
 }
+
+/// Get the coordinates of each neighbor of the given tile on the given layer
+///
+/// These are in the same order as [`directions_mut`].
+pub(super) fn neighbor_coords(
+	coord: &TileCoord,
+	grid: &MapGrid,
+	layer_id: u16,
+) -> [Option<TileCoord>; 8] {
+	grid.neighbors(&coord.pos).map(|pos| {
+		pos.map(|pos| TileCoord {
+			pos,
+			map_id: coord.map_id,
+			layer_id,
+		})
+	})
+}
+
+/// Get each direction of the given rule (in the same order as [`MapGrid::neighbors`])
+pub(super) fn directions_mut(rule: &mut AutoTileRule) -> [&mut Option<bool>; 8] {
+	let AutoTileRule {
+		north,
+		east,
+		south,
+		west,
+		north_east,
+		north_west,
+		south_east,
+		south_west,
+	} = rule;
+	[
+		north, east, south, west, north_east, north_west, south_east, south_west,
+	]
+}
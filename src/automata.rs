@@ -0,0 +1,381 @@
+//! Tools for simulating tiles as cellular automata
+
+use std::time::Duration;
+
+use bevy::log::warn;
+use bevy::prelude::{App, Entity, Res, ResMut, Resource, Time, Timer, TimerMode};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// The contents of a cell and its neighbors, as seen by a [`TileRule`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CellNeighbors {
+	/// The tile currently in the cell
+	pub tile: Option<TileId>,
+	/// The tiles in the neighboring cells
+	///
+	/// Neighbors are ordered: north, east, south, west, north-east, north-west, south-east, and
+	/// south-west. Directions that don't exist for the map type or lie outside the map are
+	/// `None`, just like empty cells.
+	pub neighbors: [Option<TileId>; 8],
+}
+
+impl CellNeighbors {
+	/// The tile in the cell to the north
+	pub fn north(&self) -> Option<TileId> {
+		self.neighbors[0]
+	}
+
+	/// The tile in the cell to the east
+	pub fn east(&self) -> Option<TileId> {
+		self.neighbors[1]
+	}
+
+	/// The tile in the cell to the south
+	pub fn south(&self) -> Option<TileId> {
+		self.neighbors[2]
+	}
+
+	/// The tile in the cell to the west
+	pub fn west(&self) -> Option<TileId> {
+		self.neighbors[3]
+	}
+
+	/// Count the neighboring tiles matching the given predicate
+	pub fn count<F: Fn(&TileId) -> bool>(&self, predicate: F) -> usize {
+		self.neighbors
+			.iter()
+			.flatten()
+			.filter(|tile| predicate(tile))
+			.count()
+	}
+}
+
+/// A rule deciding the next tile of each cell simulated by a [`TileAutomaton`]
+///
+/// The rule returns the tile the cell should contain after the step (or `None` to empty it), so
+/// returning [`CellNeighbors::tile`] leaves the cell unchanged. Every cell of a step sees the
+/// layer as it was before the step, regardless of the order cells are stepped in.
+///
+/// Any `Fn(TilePos, &CellNeighbors) -> Option<TileId>` closure can be used as a rule.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{CellNeighbors, TileId, TileRule};
+/// /// Makes water fall down until it lands on something
+/// struct FallingWater(TileId);
+///
+/// impl TileRule for FallingWater {
+///   fn step(&self, pos: TilePos, cell: &CellNeighbors) -> Option<TileId> {
+///     let water = Some(self.0);
+///     if cell.tile.is_none() && cell.north() == water {
+///       // Filled from above
+///       water
+///     } else if cell.tile == water && cell.south().is_none() && pos.y > 0 {
+///       // Fell below
+///       None
+///     } else {
+///       cell.tile
+///     }
+///   }
+/// }
+/// ```
+pub trait TileRule: Send + Sync + 'static {
+	/// Get the tile of the given cell after this step
+	fn step(&self, pos: TilePos, neighbors: &CellNeighbors) -> Option<TileId>;
+}
+
+impl<F: Fn(TilePos, &CellNeighbors) -> Option<TileId> + Send + Sync + 'static> TileRule for F {
+	fn step(&self, pos: TilePos, neighbors: &CellNeighbors) -> Option<TileId> {
+		self(pos, neighbors)
+	}
+}
+
+/// A [`TileRule`] applied to a single layer at a fixed timestep
+///
+/// Only active cells are stepped: every cell that changed during the previous step, along with
+/// its neighbors. All cells of the layer are active during the first step, which is the only one
+/// reading the whole layer (later steps only look up the cells they touch), and the automaton
+/// sleeps once a step changes nothing. Tiles placed by other means don't wake it up, so use
+/// [`activate`](Self::activate) after editing a simulated layer.
+///
+/// Each frame, all due steps are simulated in memory and only the final difference is applied
+/// to the layer: new tiles are placed with a single [`TileStamp`] and emptied cells are removed
+/// in bulk.
+pub struct TileAutomaton {
+	/// The map being simulated
+	pub map_id: u16,
+	/// The layer being simulated
+	pub layer_id: u16,
+	/// Whether the simulation is paused
+	pub paused: bool,
+	rule: Box<dyn TileRule>,
+	timer: Timer,
+	/// The cells that changed during the last step
+	active: HashSet<TilePos>,
+	/// Whether the first step (which activates every cell) has run
+	started: bool,
+}
+
+impl TileAutomaton {
+	/// Simulate the given layer, stepping once every `timestep`
+	pub fn new<MId: MapId, R: TileRule>(
+		map_id: MId,
+		layer_id: u16,
+		timestep: Duration,
+		rule: R,
+	) -> Self {
+		Self {
+			map_id: map_id.into(),
+			layer_id,
+			paused: false,
+			rule: Box::new(rule),
+			timer: Timer::new(timestep, TimerMode::Repeating),
+			active: HashSet::default(),
+			started: false,
+		}
+	}
+
+	/// Wake up the given cell (and its neighbors) for the next step
+	pub fn activate<Pos: Into<TilePos>>(&mut self, pos: Pos) {
+		self.active.insert(pos.into());
+	}
+
+	/// Wake up every cell of the layer for the next step
+	pub fn activate_all(&mut self) {
+		self.started = false;
+	}
+
+	/// Returns true if no cells will be stepped until woken up
+	pub fn is_sleeping(&self) -> bool {
+		self.started && self.active.is_empty()
+	}
+
+	/// The duration between steps
+	pub fn timestep(&self) -> Duration {
+		self.timer.duration()
+	}
+
+	/// Set the duration between steps
+	pub fn set_timestep(&mut self, timestep: Duration) {
+		self.timer.set_duration(timestep);
+	}
+}
+
+/// A resource containing every registered [`TileAutomaton`]
+#[derive(Resource, Default)]
+pub struct TileAutomata {
+	automata: Vec<TileAutomaton>,
+}
+
+impl TileAutomata {
+	/// Register an automaton
+	pub fn add(&mut self, automaton: TileAutomaton) -> &mut Self {
+		self.automata.push(automaton);
+		self
+	}
+
+	/// Get the automaton simulating the given layer
+	pub fn get_mut<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<&mut TileAutomaton> {
+		let map_id = map_id.into();
+		self.automata
+			.iter_mut()
+			.find(|automaton| automaton.map_id == map_id && automaton.layer_id == layer_id)
+	}
+
+	/// Remove every automaton simulating the given layer
+	pub fn remove<MId: MapId>(&mut self, map_id: MId, layer_id: u16) {
+		let map_id = map_id.into();
+		self.automata
+			.retain(|automaton| automaton.map_id != map_id || automaton.layer_id != layer_id);
+	}
+
+	/// Iterate over the registered automata
+	pub fn iter(&self) -> impl Iterator<Item = &TileAutomaton> {
+		self.automata.iter()
+	}
+
+	/// Iterate mutably over the registered automata
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut TileAutomaton> {
+		self.automata.iter_mut()
+	}
+}
+
+/// An extension trait for registering [`TileAutomaton`]s on an [`App`]
+pub trait AddTileAutomaton {
+	/// Register an automaton with the [`TileAutomata`] resource
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use std::time::Duration;
+	/// # use bevy::prelude::App;
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{AddTileAutomaton, CellNeighbors, TileAutomaton, TileId};
+	/// # let mut app = App::new();
+	/// # let fire = TileId::new(0, 0);
+	/// // Fire burns out after a single step
+	/// app.add_tile_automaton(TileAutomaton::new(
+	///   0u16,
+	///   1,
+	///   Duration::from_millis(250),
+	///   move |_: TilePos, cell: &CellNeighbors| cell.tile.filter(|tile| *tile != fire),
+	/// ));
+	/// ```
+	fn add_tile_automaton(&mut self, automaton: TileAutomaton) -> &mut Self;
+}
+
+impl AddTileAutomaton for App {
+	fn add_tile_automaton(&mut self, automaton: TileAutomaton) -> &mut Self {
+		self.init_resource::<TileAutomata>();
+		self.world.resource_mut::<TileAutomata>().add(automaton);
+		self
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Run the given number of steps of an automaton, then apply the result to its layer
+	pub(crate) fn step_automaton(
+		&mut self,
+		automaton: &mut TileAutomaton,
+		steps: u32,
+	) -> Result<(), TilePlacementError> {
+		if automaton.is_sleeping() {
+			return Ok(());
+		}
+
+		let (map_id, layer_id) = (automaton.map_id, automaton.layer_id);
+
+		// The entity and tile of each cell read so far, as they were before this call
+		let mut original: HashMap<TilePos, Option<(Entity, Option<TileId>)>> = HashMap::default();
+		// Only the first step reads the whole layer, later ones look up the cells they touch
+		let scanned = !automaton.started;
+		if scanned {
+			for (entity, pos, tile_id) in self.layer_tiles(map_id, layer_id) {
+				original.insert(pos, Some((entity, tile_id)));
+				automaton.active.insert(pos);
+			}
+			automaton.started = true;
+		}
+		// The cells changed by the simulated steps
+		let mut cells: HashMap<TilePos, Option<TileId>> = HashMap::default();
+
+		// === Simulate === //
+		let grid = self.grids.get(map_id, layer_id);
+		for _ in 0..steps {
+			let mut stepped = HashSet::default();
+			for pos in automaton.active.drain() {
+				stepped.insert(pos);
+				stepped.extend(grid.neighbors(&pos).into_iter().flatten());
+			}
+
+			if !scanned {
+				let read = stepped
+					.iter()
+					.flat_map(|pos| grid.neighbors(pos).into_iter().flatten().chain([*pos]));
+				for pos in read {
+					original.entry(pos).or_insert_with(|| {
+						let entity = self.find_entity(pos, map_id, layer_id)?;
+						Some((entity, self.tile_id_of(entity)))
+					});
+				}
+			}
+			let current = |pos: &TilePos| match cells.get(pos) {
+				Some(tile) => *tile,
+				None => original
+					.get(pos)
+					.copied()
+					.flatten()
+					.and_then(|(_, tile)| tile),
+			};
+
+			// Read from the current cells and write to the next ones
+			let mut next = Vec::new();
+			for pos in stepped {
+				let neighbors = CellNeighbors {
+					tile: current(&pos),
+					neighbors: grid
+						.neighbors(&pos)
+						.map(|neighbor| neighbor.and_then(|neighbor| current(&neighbor))),
+				};
+				let tile = automaton.rule.step(pos, &neighbors);
+				if tile != neighbors.tile {
+					next.push((pos, tile));
+				}
+			}
+
+			if next.is_empty() {
+				break;
+			}
+			for (pos, tile) in next {
+				cells.insert(pos, tile);
+				automaton.active.insert(pos);
+			}
+		}
+
+		// === Apply === //
+		let mut removed = Vec::new();
+		let mut placed = Vec::new();
+		for (pos, tile) in cells {
+			let old = original.get(&pos).copied().flatten();
+			match (tile, old) {
+				(None, Some((entity, Some(old_tile)))) => {
+					removed.push((entity, pos, Some(old_tile)))
+				},
+				(Some(tile), old) if old.and_then(|(_, old_tile)| old_tile) != Some(tile) => {
+					placed.push((pos, tile))
+				},
+				_ => {},
+			}
+		}
+		self.remove_batch(removed, map_id, layer_id);
+
+		if let Some((origin, stamp)) = TileStamp::fit(placed) {
+			self.place_stamp(&stamp, origin, map_id, layer_id)?;
+		}
+		Ok(())
+	}
+}
+
+/// __\[SYSTEM\]__ Steps each [`TileAutomaton`] once its timestep elapses
+pub(crate) fn step_tile_automata(
+	automata: Option<ResMut<TileAutomata>>,
+	mut placer: TilePlacer,
+	time: Res<Time>,
+) {
+	let mut automata = match automata {
+		Some(automata) => automata,
+		None => return,
+	};
+
+	for automaton in automata.iter_mut() {
+		if automaton.paused {
+			continue;
+		}
+
+		let steps = automaton
+			.timer
+			.tick(time.delta())
+			.times_finished_this_tick();
+		if steps == 0 {
+			continue;
+		}
+
+		if let Err(err) = placer.step_automaton(automaton, steps) {
+			warn!(
+				"Failed to step the tile automaton of layer {} (map {}): {}",
+				automaton.layer_id, automaton.map_id, err
+			);
+		}
+	}
+}
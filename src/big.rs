@@ -0,0 +1,249 @@
+//! Tools for placing tiles spanning multiple cells
+
+use bevy::prelude::{App, Entity, Query, RemovedComponents, ResMut, Resource, With};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::{TileParent, TilePos};
+use bevy_tileset::prelude::{TileGroupId, TileId, TilesetId};
+
+use crate::coord::TileCoord;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// A resource containing the tiles whose footprint is larger than a single cell
+///
+/// Each big tile is made up of the parts in a [`TileStamp`], one tile per covered cell, and is
+/// placed with its bottom-left corner at the given position using [`TilePlacer::place_big`].
+/// Once placed, the whole object acts as a single tile: every covered cell is reserved so no
+/// other tile can be placed over it, and removing any of its cells removes the entire object.
+/// Parts removed by other means (such as [`TilePlacer::remove_batch`] or by despawning them)
+/// release the object's cells once the [`TilesetMapStage`] runs.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{AddBigTile, TileId, TileStamp};
+/// # let mut app = App::new();
+/// # let tree = TileId::new(0, 0);
+/// # let (bottom, middle, top) = (TileId::new(1, 0), TileId::new(2, 0), TileId::new(3, 0));
+/// let parts = TileStamp::new(1, 3)
+///   .with_tile(bottom, TilePos { x: 0, y: 0 }, 0)
+///   .with_tile(middle, TilePos { x: 0, y: 1 }, 0)
+///   .with_tile(top, TilePos { x: 0, y: 2 }, 0);
+/// app.add_big_tile(tree, parts);
+/// ```
+///
+/// [`TilesetMapStage`]: crate::prelude::TilesetMapStage
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BigTiles {
+	/// The parts of each big tile
+	tiles: HashMap<(TilesetId, TileGroupId), TileStamp>,
+	/// The bottom-left cell of the big tile covering each cell
+	covered: HashMap<TileCoord, TileCoord>,
+	/// Each placed big tile, keyed by its bottom-left cell
+	placed: HashMap<TileCoord, PlacedBigTile>,
+	/// The bottom-left cell of the big tile each placed part belongs to
+	parts: HashMap<Entity, TileCoord>,
+}
+
+/// The cells and part entities of a placed big tile
+#[derive(Debug, Clone, Default)]
+struct PlacedBigTile {
+	cells: Vec<TileCoord>,
+	entities: Vec<Entity>,
+}
+
+impl BigTiles {
+	/// Register the parts of the given big tile
+	pub fn add<Id: Into<TileId>>(&mut self, tile_id: Id, parts: TileStamp) -> &mut Self {
+		let id = tile_id.into();
+		self.tiles.insert((id.tileset_id, id.group_id), parts);
+		self
+	}
+
+	/// Get the parts of the given big tile
+	pub fn get(&self, tile_id: &TileId) -> Option<&TileStamp> {
+		self.tiles.get(&(tile_id.tileset_id, tile_id.group_id))
+	}
+
+	/// Get the bottom-left cell of the placed big tile covering the given cell (if any)
+	pub fn origin_of(&self, coord: &TileCoord) -> Option<TileCoord> {
+		self.covered.get(coord).copied()
+	}
+
+	/// Stop tracking the placed big tile covering the given cell, returning all of its cells
+	pub(crate) fn take_placed(&mut self, coord: &TileCoord) -> Option<Vec<TileCoord>> {
+		let origin = *self.covered.get(coord)?;
+		self.release(&origin)
+	}
+
+	/// Stop tracking the placed big tile with the given bottom-left cell, returning all of its
+	/// cells
+	fn release(&mut self, origin: &TileCoord) -> Option<Vec<TileCoord>> {
+		let placed = self.placed.remove(origin)?;
+		for cell in &placed.cells {
+			self.covered.remove(cell);
+		}
+		for entity in &placed.entities {
+			self.parts.remove(entity);
+		}
+		Some(placed.cells)
+	}
+}
+
+/// A helper trait for registering big tiles
+pub trait AddBigTile {
+	/// Register a big tile with the [`BigTiles`] resource
+	fn add_big_tile<Id: Into<TileId>>(&mut self, tile_id: Id, parts: TileStamp) -> &mut Self;
+}
+
+impl AddBigTile for App {
+	fn add_big_tile<Id: Into<TileId>>(&mut self, tile_id: Id, parts: TileStamp) -> &mut Self {
+		self.init_resource::<BigTiles>();
+		self.world.resource_mut::<BigTiles>().add(tile_id, parts);
+		self
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Place a big tile (registered in the [`BigTiles`] resource) with its bottom-left corner at
+	/// the given position
+	///
+	/// Fails with [`TilePlacementError::Overlap`] if any of the covered cells is already filled,
+	/// without modifying the map.
+	///
+	/// Returns the results of each placed part.
+	pub fn place_big<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let id = tile_id.into();
+		let origin = pos.into();
+		let map_id = map_id.into();
+		let parts = self
+			.big_tiles
+			.get(&id)
+			.cloned()
+			.ok_or(TilePlacementError::InvalidTile(id))?;
+
+		let cells = parts
+			.iter()
+			.map(|(pos, layer, _)| TileCoord {
+				pos: TilePos {
+					x: origin.x + pos.x,
+					y: origin.y + pos.y,
+				},
+				map_id,
+				layer_id: layer_id + layer,
+			})
+			.collect::<Vec<_>>();
+		for cell in &cells {
+			if self
+				.occupancy
+				.is_occupied(cell.pos, cell.map_id, cell.layer_id)
+			{
+				return Err(TilePlacementError::Overlap {
+					pos: cell.pos,
+					layer_id: cell.layer_id,
+				});
+			}
+		}
+
+		let placed = self.place_stamp(&parts, origin, map_id, layer_id)?;
+
+		let key = TileCoord {
+			pos: origin,
+			map_id,
+			layer_id,
+		};
+		let entities = placed
+			.iter()
+			.filter_map(|placed| match placed {
+				PlacedTile::Added {
+					new_tile: (entity, _),
+					..
+				} => Some(*entity),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		for cell in &cells {
+			self.big_tiles.covered.insert(*cell, key);
+		}
+		for entity in &entities {
+			self.big_tiles.parts.insert(*entity, key);
+		}
+		self.big_tiles
+			.placed
+			.insert(key, PlacedBigTile { cells, entities });
+		Ok(placed)
+	}
+
+	/// Fail if the given cell is covered by a placed big tile
+	pub(crate) fn check_big_tile_overlap(
+		&self,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<(), TilePlacementError> {
+		let coord = TileCoord {
+			pos,
+			map_id,
+			layer_id,
+		};
+		match self.big_tiles.origin_of(&coord) {
+			Some(_) => Err(TilePlacementError::Overlap { pos, layer_id }),
+			None => Ok(()),
+		}
+	}
+
+	/// Remove the whole big tile covering the given cell (if any)
+	///
+	/// Returns false if the cell isn't covered by a big tile.
+	pub(crate) fn remove_big_tile(
+		&mut self,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<bool, TilePlacementError> {
+		let coord = TileCoord {
+			pos,
+			map_id,
+			layer_id,
+		};
+		let cells = match self.big_tiles.take_placed(&coord) {
+			Some(cells) => cells,
+			None => return Ok(false),
+		};
+
+		for cell in cells {
+			self.remove(cell.pos, cell.map_id, cell.layer_id)?;
+		}
+		Ok(true)
+	}
+}
+
+/// __\[SYSTEM\]__ Releases the cells of big tiles whose parts were removed without going through
+/// [`TilePlacer::remove`]
+///
+/// This covers tiles removed in bulk, cleared layers, culled chunks, and tiles despawned by other
+/// means. The remaining parts of such a big tile are left in place, but no longer reserve their
+/// cells.
+pub(crate) fn release_big_tiles(
+	mut removed: RemovedComponents<TileParent>,
+	tiles: Query<(), With<TileParent>>,
+	mut big_tiles: ResMut<BigTiles>,
+) {
+	for entity in removed.iter() {
+		// The entity may have been reused (such as by the tile pool) since it was removed
+		if tiles.contains(entity) {
+			continue;
+		}
+		if let Some(origin) = big_tiles.parts.get(&entity).copied() {
+			big_tiles.release(&origin);
+		}
+	}
+}
@@ -0,0 +1,112 @@
+//! Tools for handling tiles placed outside the bounds of a map
+
+use bevy::prelude::Resource;
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::placement::{TilePlacementError, TilePlacer};
+
+/// The amount (in tiles) a map's size is rounded up to when it grows
+const GROW_STEP: u32 = 32;
+
+/// A resource determining what happens when a tile is placed outside the bounds of its tilemap
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutOfBoundsPolicy {
+	/// Fail with [`TilePlacementError::OutOfBounds`]
+	Error,
+	/// Move the tile to the nearest position within the map
+	Clamp,
+	/// Expand the tilemap (and its [`TileStorage`]) to fit the tile
+	///
+	/// Maps grow in steps of 32 tiles to avoid reallocating their storage on every placement.
+	/// Since tile positions are unsigned, maps can only grow up and to the right.
+	Grow,
+}
+
+impl Default for OutOfBoundsPolicy {
+	fn default() -> Self {
+		Self::Error
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Resolve the position of a tile about to be placed according to the [`OutOfBoundsPolicy`]
+	///
+	/// Positions within the map (or in a layer without a tilemap) are returned as-is. This never
+	/// modifies the map: under [`OutOfBoundsPolicy::Grow`], the position is returned unchanged and
+	/// the tilemap is only grown by [`grow_to_fit`](Self::grow_to_fit) once the tile passes every
+	/// other check.
+	pub(crate) fn resolve_bounds(
+		&self,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<TilePos, TilePlacementError> {
+		let size = match self.tile_storage(map_id, layer_id) {
+			Some(storage) => storage.size,
+			None => return Ok(pos),
+		};
+		if pos.within_map_bounds(&size) {
+			return Ok(pos);
+		}
+
+		match *self.bounds_policy {
+			OutOfBoundsPolicy::Error => Err(TilePlacementError::OutOfBounds { pos, size }),
+			OutOfBoundsPolicy::Clamp => Ok(TilePos {
+				x: pos.x.min(size.x.saturating_sub(1)),
+				y: pos.y.min(size.y.saturating_sub(1)),
+			}),
+			OutOfBoundsPolicy::Grow => Ok(pos),
+		}
+	}
+
+	/// Expand the tilemap (and its [`TileStorage`]) of the given layer to fit the given position
+	///
+	/// Does nothing if the position already lies within the map or the [`OutOfBoundsPolicy`] isn't
+	/// [`Grow`](OutOfBoundsPolicy::Grow).
+	pub(crate) fn grow_to_fit(&mut self, pos: TilePos, map_id: u16, layer_id: u16) {
+		if *self.bounds_policy != OutOfBoundsPolicy::Grow {
+			return;
+		}
+
+		let tilemap = match self
+			.tilemaps
+			.iter()
+			.find(|(_, layer)| layer.map_id == map_id && layer.layer_id == layer_id)
+		{
+			Some((tilemap, _)) => tilemap,
+			None => return,
+		};
+		let mut storage = match self.map_query.get_mut(tilemap) {
+			Ok(storage) => storage,
+			Err(_) => return,
+		};
+
+		let size = storage.size;
+		if pos.within_map_bounds(&size) {
+			return;
+		}
+
+		let grow = |current: u32, required: u32| {
+			let required = required.saturating_add(GROW_STEP) / GROW_STEP * GROW_STEP;
+			current.max(required)
+		};
+		let new_size = TilemapSize {
+			x: grow(size.x, pos.x),
+			y: grow(size.y, pos.y),
+		};
+
+		// Copy every tile into the larger storage
+		let mut grown = TileStorage::empty(new_size);
+		for y in 0..size.y {
+			for x in 0..size.x {
+				let tile_pos = TilePos { x, y };
+				if let Some(entity) = storage.get(&tile_pos) {
+					grown.set(&tile_pos, entity);
+				}
+			}
+		}
+		*storage = grown;
+
+		self.commands.entity(tilemap).insert(new_size);
+	}
+}
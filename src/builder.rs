@@ -0,0 +1,287 @@
+//! Tools for spawning entire tilemaps at once
+
+use bevy::asset::Handle;
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::{Commands, Entity, Transform};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileId, TileIndex, Tileset, TilesetParent, Tilesets};
+
+use crate::generator::TileGenerator;
+use crate::layers::MapLayers;
+use crate::manager::TilemapLayer;
+use crate::placement::{MapId, TilePlacementError};
+use crate::stamp::TileStamp;
+
+/// A builder used to spawn a complete map (with all of its layers) in one go
+///
+/// Each layer is spawned as its own tilemap entity using the tileset's texture, with every tile
+/// configured for use with this crate: animated tiles are animated, auto tiles are resolved
+/// once spawned, and named layers are registered in the [`MapLayers`] resource.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{Commands, Handle, Res, ResMut};
+/// # use bevy_ecs_tilemap::prelude::TilemapSize;
+/// # use bevy_tileset_map::prelude::{MapLayers, TileId, TilemapBuilder, Tileset, Tilesets};
+/// fn spawn_map(
+///   mut commands: Commands,
+///   tilesets: Tilesets,
+///   mut map_layers: ResMut<MapLayers>,
+///   # tileset: Handle<Tileset>,
+///   # grass: TileId,
+///   # tree: TileId,
+/// ) {
+///   TilemapBuilder::new(0u16, TilemapSize { x: 32, y: 32 }, tileset)
+///     .with_layer(0, Some("ground"), |_| Some(grass))
+///     .with_layer(1, Some("decor"), |pos| (pos.x % 4 == 0).then(|| tree))
+///     .build(&mut commands, &tilesets, &mut map_layers)
+///     .unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TilemapBuilder {
+	map_id: u16,
+	size: TilemapSize,
+	tileset: Handle<Tileset>,
+	tile_size: Option<TilemapTileSize>,
+	map_type: TilemapType,
+	transform: Transform,
+	layers: Vec<BuilderLayer>,
+}
+
+/// A layer to be spawned by the [`TilemapBuilder`]
+#[derive(Debug, Clone)]
+struct BuilderLayer {
+	layer_id: u16,
+	name: Option<String>,
+	tiles: TileStamp,
+}
+
+impl TilemapBuilder {
+	/// Create a builder for a map of the given size using the given tileset
+	pub fn new<MId: MapId>(map_id: MId, size: TilemapSize, tileset: Handle<Tileset>) -> Self {
+		Self {
+			map_id: map_id.into(),
+			size,
+			tileset,
+			tile_size: None,
+			map_type: TilemapType::default(),
+			transform: Transform::default(),
+			layers: Vec::new(),
+		}
+	}
+
+	/// Set the size of each tile (defaults to the tile size of the tileset)
+	pub fn with_tile_size(mut self, tile_size: TilemapTileSize) -> Self {
+		self.tile_size = Some(tile_size);
+		self
+	}
+
+	/// Set the type of the map (defaults to a square grid)
+	pub fn with_map_type(mut self, map_type: TilemapType) -> Self {
+		self.map_type = map_type;
+		self
+	}
+
+	/// Set the transform of the lowest layer
+	///
+	/// Every other layer is offset from it by one unit on the z-axis per layer.
+	pub fn with_transform(mut self, transform: Transform) -> Self {
+		self.transform = transform;
+		self
+	}
+
+	/// Add a layer filled using the given [`TileGenerator`] (or closure)
+	///
+	/// If `name` is given, the layer is registered under it in the [`MapLayers`] resource.
+	pub fn with_layer<G: TileGenerator>(
+		mut self,
+		layer_id: u16,
+		name: Option<&str>,
+		generator: G,
+	) -> Self {
+		let mut tiles = TileStamp::new(self.size.x, self.size.y);
+		for y in 0..self.size.y {
+			for x in 0..self.size.x {
+				let pos = TilePos { x, y };
+				tiles.set(pos, 0, generator.tile_at(pos));
+			}
+		}
+		self.push_layer(layer_id, name, tiles)
+	}
+
+	/// Add a layer filled from rows of tiles
+	///
+	/// Rows are given top-down (so that they read the same as they appear on screen), meaning
+	/// the first row is placed at the top of the map. Tiles outside the map are ignored.
+	///
+	/// If `name` is given, the layer is registered under it in the [`MapLayers`] resource.
+	pub fn with_layer_tiles(
+		mut self,
+		layer_id: u16,
+		name: Option<&str>,
+		rows: &[Vec<Option<TileId>>],
+	) -> Self {
+		let mut tiles = TileStamp::new(self.size.x, self.size.y);
+		for (row, row_tiles) in rows.iter().enumerate().take(self.size.y as usize) {
+			let y = self.size.y - 1 - row as u32;
+			for (x, tile) in row_tiles.iter().enumerate() {
+				tiles.set(TilePos { x: x as u32, y }, 0, *tile);
+			}
+		}
+		self.push_layer(layer_id, name, tiles)
+	}
+
+	/// Spawn the map
+	///
+	/// Every tile is validated before anything is spawned, so an invalid tile results in an error
+	/// without spawning anything.
+	///
+	/// Returns the tilemap entity of each layer, in the order they were added.
+	pub fn build(
+		self,
+		commands: &mut Commands,
+		tilesets: &Tilesets,
+		map_layers: &mut MapLayers,
+	) -> Result<Vec<Entity>, TilePlacementError> {
+		let tileset = tilesets
+			.get(&self.tileset)
+			.ok_or(TilePlacementError::UnloadedTileset)?;
+
+		// === Validate === //
+		let mut layers = Vec::with_capacity(self.layers.len());
+		for layer in &self.layers {
+			let mut tiles = Vec::new();
+			for (pos, _, tile_id) in layer.tiles.iter() {
+				let tile_id = TileId {
+					tileset_id: *tileset.id(),
+					..*tile_id
+				};
+				let index = tileset
+					.get_tile_index_by_id(tile_id)
+					.ok_or(TilePlacementError::InvalidTile(tile_id))?;
+				tiles.push((pos, tile_id, index));
+			}
+			layers.push((layer, tiles));
+		}
+
+		// === Spawn === //
+		let tile_size = self.tile_size.unwrap_or_else(|| {
+			let size = tileset.tile_size();
+			TilemapTileSize {
+				x: size.x,
+				y: size.y,
+			}
+		});
+		let texture = TilemapTexture::Single(tileset.texture().clone());
+
+		let mut entities = Vec::with_capacity(layers.len());
+		for (layer, tiles) in layers {
+			let tilemap = commands.spawn_empty().id();
+			let mut storage = TileStorage::empty(self.size);
+
+			for (pos, tile_id, index) in tiles {
+				let tile = spawn_tile(commands, tileset, tilemap, pos, tile_id, index);
+				commands.entity(tile).insert(TileParent {
+					map_id: self.map_id,
+					layer_id: layer.layer_id,
+				});
+				storage.set(&pos, tile);
+			}
+
+			let mut transform = self.transform;
+			transform.translation.z += layer.layer_id as f32;
+			commands.entity(tilemap).insert((
+				TilemapBundle {
+					grid_size: tile_size.into(),
+					map_type: self.map_type,
+					size: self.size,
+					storage,
+					texture: texture.clone(),
+					tile_size,
+					transform,
+					..Default::default()
+				},
+				TilemapLayer {
+					map_id: self.map_id,
+					layer_id: layer.layer_id,
+				},
+			));
+
+			if let Some(name) = &layer.name {
+				map_layers.insert(self.map_id, name, layer.layer_id);
+			}
+
+			entities.push(tilemap);
+		}
+
+		Ok(entities)
+	}
+
+	/// Add a layer, replacing any existing layer with the same ID
+	fn push_layer(mut self, layer_id: u16, name: Option<&str>, tiles: TileStamp) -> Self {
+		self.layers.retain(|layer| layer.layer_id != layer_id);
+		self.layers.push(BuilderLayer {
+			layer_id,
+			name: name.map(String::from),
+			tiles,
+		});
+		self
+	}
+}
+
+/// Spawn a single tile entity belonging to the given tilemap
+pub(crate) fn spawn_tile(
+	commands: &mut Commands,
+	tileset: &Tileset,
+	tilemap: Entity,
+	pos: TilePos,
+	tile_id: TileId,
+	index: TileIndex,
+) -> Entity {
+	let mut cmds = commands.spawn(TileBundle {
+		position: pos,
+		tilemap_id: TilemapId(tilemap),
+		..Default::default()
+	});
+	insert_tile(&mut cmds, tileset, tile_id, index);
+	cmds.id()
+}
+
+/// Insert the components identifying an entity as the given tile of a tileset
+///
+/// This sets the tile's texture and tileset, animates animated tiles, and marks auto tiles as
+/// such.
+pub(crate) fn insert_tile(
+	cmds: &mut EntityCommands,
+	tileset: &Tileset,
+	tile_id: TileId,
+	index: TileIndex,
+) {
+	cmds.insert((
+		TileTextureIndex(*index.base_index() as u32),
+		TilesetParent(*tileset.id()),
+	));
+
+	if let TileIndex::Animated(start, end, speed) = index {
+		cmds.insert(AnimatedTile {
+			start: start as u32,
+			end: end as u32,
+			speed,
+		});
+	}
+
+	#[cfg(feature = "auto-tile")]
+	{
+		let is_auto = tileset
+			.get_tile_name(&tile_id.group_id)
+			.and_then(|name| tileset.get_tile_data(name))
+			.map_or(false, |data| data.is_auto());
+		if is_auto {
+			cmds.insert(bevy_tileset::auto::AutoTileId::from(tile_id));
+		}
+	}
+	#[cfg(not(feature = "auto-tile"))]
+	let _ = tile_id;
+}
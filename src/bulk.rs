@@ -0,0 +1,201 @@
+//! Bulk operations on the tiles of a layer
+
+use bevy::math::UVec2;
+use bevy::prelude::Entity;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::coord::TileCoord;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::region_pos;
+use crate::validation::TileOverlay;
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Remove every tile in the given layer belonging to the same tile group as `tile_id`
+	///
+	/// The layer is only scanned once, and like [`remove_rect`](Self::remove_rect), the tiles are
+	/// despawned in bulk.
+	///
+	/// Returns the results of each removed tile.
+	pub fn remove_group<Id: Into<TileId>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let id = tile_id.into();
+		let tiles = self
+			.layer_tiles(map_id, layer_id)
+			.into_iter()
+			.filter(|(.., existing)| existing.map_or(false, |existing| existing.eq_tile_group(&id)))
+			.collect();
+		Ok(self.remove_batch(tiles, map_id, layer_id))
+	}
+
+	/// Replace every tile in the given layer belonging to the same tile group as `old_id` with
+	/// `new_id`
+	///
+	/// Every replacement goes through the same checks as [`place`](Self::place) before any tiles
+	/// are replaced, so an invalid tile (or a replacement rejected by the layer's validation)
+	/// results in an error without modifying the map. Validators see the layer as it will be once
+	/// every tile is replaced. Like [`remove_group`](Self::remove_group), the layer is only scanned
+	/// once and auto tiles are only updated once.
+	///
+	/// Returns the results of each placed tile.
+	pub fn replace_all<OldId: Into<TileId>, NewId: Into<TileId>, MId: MapId>(
+		&mut self,
+		old_id: OldId,
+		new_id: NewId,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let old_id = old_id.into();
+		let new_id = new_id.into();
+		let map_id = map_id.into();
+		let targets = self
+			.layer_tiles(map_id, layer_id)
+			.into_iter()
+			.filter(|(.., existing)| {
+				existing.map_or(false, |existing| existing.eq_tile_group(&old_id))
+			})
+			.map(|(_, pos, _)| pos)
+			.collect::<Vec<_>>();
+
+		// === Validate === //
+		let overlay: TileOverlay = targets
+			.iter()
+			.map(|pos| {
+				let coord = TileCoord {
+					pos: *pos,
+					map_id,
+					layer_id,
+				};
+				(coord, Some(new_id))
+			})
+			.collect();
+		let mut resolved = Vec::with_capacity(targets.len());
+		for pos in targets {
+			if let Some(pos) = self.can_place(&new_id, pos, map_id, layer_id, Some(&overlay))? {
+				resolved.push(pos);
+			}
+		}
+
+		// === Place === //
+		let mut placed = Vec::with_capacity(resolved.len());
+		for pos in resolved {
+			placed.push(self.place_unchecked(new_id, pos, map_id, layer_id)?);
+		}
+
+		Ok(placed)
+	}
+
+	/// Remove every tile of the given layer within a region
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. Tiles are
+	/// despawned in bulk: each tilemap's storage is only updated once and the surrounding auto
+	/// tiles are notified with a single batch of events.
+	///
+	/// Returns the results of each removed tile.
+	pub fn remove_rect<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) -> Vec<PlacedTile> {
+		let origin = origin.into();
+		let tiles = self
+			.layer_tiles(map_id, layer_id)
+			.into_iter()
+			.filter(|(_, pos, _)| region_pos(pos, &origin, size).is_some())
+			.collect();
+		self.remove_batch(tiles, map_id, layer_id)
+	}
+
+	/// Remove every tile of the given layer
+	///
+	/// Like [`remove_rect`](Self::remove_rect), tiles are despawned in bulk.
+	///
+	/// Returns the results of each removed tile.
+	pub fn clear_layer<MId: MapId>(&mut self, map_id: MId, layer_id: u16) -> Vec<PlacedTile> {
+		let tiles = self.layer_tiles(map_id, layer_id);
+		self.remove_batch(tiles, map_id, layer_id)
+	}
+
+	/// Despawn the given tiles of a layer at once
+	///
+	/// Tiles outside the layer's [`PlacementMask`](crate::prelude::PlacementMask) are skipped.
+	/// Each result is at the same index as its tile.
+	pub(crate) fn remove_batch<MId: MapId>(
+		&mut self,
+		tiles: Vec<(Entity, TilePos, Option<TileId>)>,
+		map_id: MId,
+		layer_id: u16,
+	) -> Vec<PlacedTile> {
+		let map_id = map_id.into();
+		let mut results = vec![PlacedTile::Skipped; tiles.len()];
+		let tiles = tiles
+			.into_iter()
+			.enumerate()
+			.filter(|(_, (_, pos, _))| self.placement_masks.allows(*pos, map_id, layer_id))
+			.collect::<Vec<_>>();
+		if tiles.is_empty() {
+			return results;
+		}
+
+		// === Auto Tiles === //
+		#[cfg(feature = "auto-tile")]
+		{
+			let mut events = tiles
+				.iter()
+				.filter_map(|(_, (entity, ..))| {
+					let (pos, parent, auto_id) = self.auto_query.get(*entity).ok()?;
+					Some(crate::auto::RemoveAutoTileEvent {
+						entity: *entity,
+						pos: *pos,
+						parent: *parent,
+						auto_id: *auto_id,
+					})
+				})
+				.collect::<Vec<_>>();
+			events.retain(|event| self.mark_auto_tile_removed(event.entity));
+			self.event_writer.send_batch(events);
+		}
+
+		// === Storage === //
+		if let Some(mut storage) = self.tile_storage_mut(map_id, layer_id) {
+			for (_, (entity, pos, _)) in &tiles {
+				if storage.checked_get(pos) == Some(*entity) {
+					storage.remove(pos);
+				}
+			}
+		}
+
+		// === Despawn === //
+		for (index, (entity, pos, tile_id)) in tiles {
+			self.push_change(pos, map_id, layer_id, tile_id, None);
+			self.release_cell(pos, map_id, layer_id);
+			self.despawn_pooled(entity);
+			results[index] = PlacedTile::Removed {
+				old_tile: Some((entity, tile_id)),
+			};
+		}
+		results
+	}
+
+	/// Get every tile in the given layer, along with its [`TileId`] (if it can be resolved)
+	pub(crate) fn layer_tiles<MId: MapId>(
+		&self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Vec<(Entity, TilePos, Option<TileId>)> {
+		let map_id = map_id.into();
+		self.region_query
+			.iter()
+			.filter(|(_, _, parent, ..)| parent.map_id == map_id && parent.layer_id == layer_id)
+			.map(|(entity, pos, _, index, tileset)| {
+				(entity, *pos, self.resolve_tile_id(index, tileset))
+			})
+			.collect()
+	}
+}
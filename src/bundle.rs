@@ -0,0 +1,77 @@
+//! Tools for attaching custom components to placed tiles
+
+use bevy::prelude::Bundle;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, TilePlacementResult, TilePlacer};
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Same as [`place`](Self::place), but also inserting the given bundle onto the placed tile
+	///
+	/// The bundle is inserted within the same command batch that creates the tile, so its
+	/// components exist from the moment the tile does. Nothing is inserted if placement fails.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::prelude::Component;
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+	/// #[derive(Component)]
+	/// struct Health(u32);
+	///
+	/// fn place_crate(mut placer: TilePlacer) {
+	///   # let wooden_crate = TileId::new(0, 0);
+	///   placer
+	///     .place_with(wooden_crate, TilePos { x: 3, y: 4 }, 0u16, 1, Health(10))
+	///     .unwrap();
+	/// }
+	/// ```
+	pub fn place_with<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId, B: Bundle>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		bundle: B,
+	) -> TilePlacementResult {
+		let result = self.place(tile_id, pos, map_id, layer_id)?;
+		self.insert_on_added(&result, bundle);
+		Ok(result)
+	}
+
+	/// Same as [`try_place`](Self::try_place), but also inserting the given bundle onto the placed
+	/// tile
+	///
+	/// See [`place_with`](Self::place_with) for details.
+	pub fn try_place_with<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId, B: Bundle>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		bundle: B,
+	) -> TilePlacementResult {
+		let result = self.try_place(tile_id, pos, map_id, layer_id)?;
+		self.insert_on_added(&result, bundle);
+		Ok(result)
+	}
+
+	/// Same as [`replace`](Self::replace), but also inserting the given bundle onto the placed
+	/// tile
+	///
+	/// See [`place_with`](Self::place_with) for details.
+	pub fn replace_with<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId, B: Bundle>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		bundle: B,
+	) -> TilePlacementResult {
+		let result = self.replace(tile_id, pos, map_id, layer_id)?;
+		self.insert_on_added(&result, bundle);
+		Ok(result)
+	}
+}
@@ -0,0 +1,191 @@
+//! Tools for recording the tile edits made through this crate
+
+use bevy::prelude::Resource;
+use bevy::reflect::Reflect;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::coord::TileCoord;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+
+/// A single tile edit recorded in the [`TileChangeLog`]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect_value(Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serialization",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TileChange {
+	/// The coordinate of the edited tile
+	pub coord: TileCoord,
+	/// The tile before the edit (`None` if the cell was empty)
+	pub old: Option<TileId>,
+	/// The tile after the edit (`None` if the tile was removed)
+	pub new: Option<TileId>,
+	/// The change tick of the system that made the edit
+	pub tick: u32,
+}
+
+impl TileChange {
+	/// Get the change that undoes this one
+	pub fn inverse(&self) -> Self {
+		Self {
+			old: self.new,
+			new: self.old,
+			..*self
+		}
+	}
+}
+
+/// An opt-in resource recording every tile edit made through the [`TilePlacer`]
+///
+/// Recording only happens while this resource exists, so insert it to start recording:
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy_tileset_map::prelude::TileChangeLog;
+/// # let mut app = App::new();
+/// app.init_resource::<TileChangeLog>();
+/// ```
+///
+/// The recorded changes can then be drained (for example, to be saved or sent over the network)
+/// and later applied with [`TilePlacer::apply_changes`].
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TileChangeLog {
+	changes: Vec<TileChange>,
+	/// The number of times the log was drained or cleared
+	generation: u64,
+}
+
+impl TileChangeLog {
+	/// Record a change
+	pub fn record(&mut self, change: TileChange) {
+		self.changes.push(change);
+	}
+
+	/// The number of recorded changes
+	pub fn len(&self) -> usize {
+		self.changes.len()
+	}
+
+	/// Returns true if no changes have been recorded
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Iterate over the recorded changes, oldest first
+	pub fn iter(&self) -> impl Iterator<Item = &TileChange> {
+		self.changes.iter()
+	}
+
+	/// The number of times the log was drained or cleared
+	///
+	/// Readers keeping track of how many changes they already handled can compare this to find
+	/// out whether those changes are still in the log.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Take all recorded changes, leaving the log empty
+	pub fn drain(&mut self) -> Vec<TileChange> {
+		self.generation += 1;
+		std::mem::take(&mut self.changes)
+	}
+
+	/// Discard all recorded changes
+	pub fn clear(&mut self) {
+		self.generation += 1;
+		self.changes.clear();
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Apply the given changes, in order
+	///
+	/// Changes placing a tile use [`place`](Self::place), while changes removing a tile use
+	/// [`remove`](Self::remove). Note that applied changes are themselves recorded if a
+	/// [`TileChangeLog`] exists.
+	///
+	/// Returns the results of each applied change.
+	pub fn apply_changes(
+		&mut self,
+		changes: &[TileChange],
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let mut placed = Vec::with_capacity(changes.len());
+		for change in changes {
+			let TileCoord {
+				pos,
+				map_id,
+				layer_id,
+			} = change.coord;
+			match change.new {
+				Some(tile_id) => placed.push(self.place(tile_id, pos, map_id, layer_id)?),
+				None => {
+					let old_tile = self
+						.find_entity(pos, map_id, layer_id)
+						.map(|entity| (entity, change.old));
+					if old_tile.is_some() {
+						self.remove(pos, map_id, layer_id)?;
+					}
+					placed.push(PlacedTile::Removed { old_tile });
+				},
+			}
+		}
+		Ok(placed)
+	}
+
+	/// Undo the given changes by applying their inverses in reverse order
+	pub fn revert_changes(
+		&mut self,
+		changes: &[TileChange],
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let inverse = changes
+			.iter()
+			.rev()
+			.map(TileChange::inverse)
+			.collect::<Vec<_>>();
+		self.apply_changes(&inverse)
+	}
+
+	/// Record a change to the given cell in the [`TileChangeLog`] (if it exists)
+	pub(crate) fn record_change<MId: MapId>(
+		&mut self,
+		pos: TilePos,
+		map_id: MId,
+		layer_id: u16,
+		new: Option<TileId>,
+	) {
+		if self.change_log.is_none() {
+			return;
+		}
+
+		let old = self
+			.find_entity(pos, map_id, layer_id)
+			.and_then(|entity| self.tile_id_of(entity));
+		self.push_change(pos, map_id, layer_id, old, new);
+	}
+
+	/// Record a change with a known previous tile in the [`TileChangeLog`] (if it exists)
+	pub(crate) fn push_change<MId: MapId>(
+		&mut self,
+		pos: TilePos,
+		map_id: MId,
+		layer_id: u16,
+		old: Option<TileId>,
+		new: Option<TileId>,
+	) {
+		let tick = self.change_tick.change_tick();
+		if let Some(log) = self.change_log.as_mut() {
+			log.record(TileChange {
+				coord: TileCoord {
+					pos,
+					map_id: map_id.into(),
+					layer_id,
+				},
+				old,
+				new,
+				tick,
+			});
+		}
+	}
+}
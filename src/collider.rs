@@ -0,0 +1,244 @@
+//! Tools for generating physics colliders from tile metadata
+
+use bevy::ecs::system::EntityCommands;
+use bevy::math::{UVec2, Vec2};
+use bevy::prelude::{
+	BuildChildren, Changed, Commands, Component, DespawnRecursiveExt, DetectChanges, Entity, Local,
+	Or, Query, RemovedComponents, Res, Transform, TransformBundle,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TilesetParent, Tilesets};
+
+use crate::coord::TileCoord;
+use crate::manager::TilemapLayer;
+use crate::metadata::TileMetadata;
+
+/// The [`TileMetadata`] property marking a tile as solid
+///
+/// Tiles with this property set to `true` generate colliders.
+pub const COLLISION_PROPERTY: &str = "collision";
+
+/// The size (in tiles) of the square regions colliders are merged within
+const COLLIDER_CHUNK_SIZE: u32 = 16;
+
+/// A component added to tilemaps containing their generated collider entities
+///
+/// Colliders are spawned as children of the tilemap. Adjacent solid tiles within each 16x16
+/// region of the tilemap are merged into as few rectangular colliders as possible, so only the
+/// regions containing changed tiles have to be regenerated.
+#[derive(Component, Debug, Default, Clone)]
+pub struct TileColliders {
+	/// The collider entities of each region, keyed by the region's coordinate
+	chunks: HashMap<UVec2, Vec<Entity>>,
+	/// Whether each tile of the tilemap is solid
+	solid: Vec<bool>,
+	/// The size of the tilemap `solid` was built for
+	size: UVec2,
+}
+
+impl TileColliders {
+	/// The collider entities of this tilemap
+	pub fn colliders(&self) -> impl Iterator<Item = Entity> + '_ {
+		self.chunks.values().flatten().copied()
+	}
+}
+
+/// __\[SYSTEM\]__ Regenerates the colliders around any tiles that were placed or removed
+///
+/// Only the regions containing changed tiles are regenerated, unless the tilemap was resized or
+/// the [`TileMetadata`] changed.
+pub(crate) fn update_tile_colliders(
+	mut commands: Commands,
+	changed_tiles: Query<
+		(Entity, &TilePos, &TileParent),
+		Or<(
+			Changed<TilePos>,
+			Changed<TileParent>,
+			Changed<TileTextureIndex>,
+		)>,
+	>,
+	mut removed_tiles: RemovedComponents<TileParent>,
+	tiles: Query<(&TileTextureIndex, &TilesetParent)>,
+	mut tilemaps: Query<(
+		Entity,
+		&TilemapLayer,
+		&TileStorage,
+		&TilemapGridSize,
+		Option<&mut TileColliders>,
+	)>,
+	tilesets: Tilesets,
+	metadata: Res<TileMetadata>,
+	mut tracked: Local<HashMap<Entity, TileCoord>>,
+) {
+	let mut dirty: HashMap<(u16, u16), HashSet<TilePos>> = HashMap::default();
+	let mut mark = |coord: TileCoord| {
+		dirty
+			.entry((coord.map_id, coord.layer_id))
+			.or_default()
+			.insert(coord.pos);
+	};
+	// Removals come first, so pooled tiles that were reused are tracked again
+	for entity in removed_tiles.iter() {
+		if let Some(previous) = tracked.remove(&entity) {
+			mark(previous);
+		}
+	}
+	for (entity, pos, parent) in changed_tiles.iter() {
+		let coord = TileCoord {
+			pos: *pos,
+			map_id: parent.map_id,
+			layer_id: parent.layer_id,
+		};
+		if let Some(previous) = tracked.insert(entity, coord) {
+			mark(previous);
+		}
+		mark(coord);
+	}
+	if metadata.is_changed() {
+		tracked.values().copied().for_each(&mut mark);
+	}
+
+	if dirty.is_empty() {
+		return;
+	}
+
+	for (tilemap, layer, storage, grid_size, colliders) in tilemaps.iter_mut() {
+		let cells = match dirty.remove(&(layer.map_id, layer.layer_id)) {
+			Some(cells) => cells,
+			None => continue,
+		};
+
+		let mut added = None;
+		let colliders = match colliders {
+			Some(colliders) => colliders.into_inner(),
+			None => added.insert(TileColliders::default()),
+		};
+
+		// A resized (or new) tilemap is regenerated from scratch
+		let size = UVec2::new(storage.size.x, storage.size.y);
+		let cells = if colliders.size != size {
+			for collider in colliders.colliders() {
+				commands.entity(collider).despawn_recursive();
+			}
+			*colliders = TileColliders {
+				chunks: HashMap::default(),
+				solid: vec![false; (size.x * size.y) as usize],
+				size,
+			};
+			(0..size.y)
+				.flat_map(|y| (0..size.x).map(move |x| TilePos { x, y }))
+				.collect()
+		} else {
+			cells
+				.into_iter()
+				.filter(|pos| pos.x < size.x && pos.y < size.y)
+				.collect::<Vec<_>>()
+		};
+
+		// === Solid Tiles === //
+		let mut dirty_chunks = HashSet::default();
+		for pos in cells {
+			colliders.solid[(pos.y * size.x + pos.x) as usize] = storage
+				.checked_get(&pos)
+				.and_then(|entity| tiles.get(entity).ok())
+				.and_then(|(index, tileset)| metadata.resolve(&tilesets, index, tileset))
+				.and_then(|properties| properties.get(COLLISION_PROPERTY))
+				.and_then(|property| property.as_bool())
+				.unwrap_or_default();
+			dirty_chunks.insert(UVec2::new(pos.x, pos.y) / COLLIDER_CHUNK_SIZE);
+		}
+
+		// === Rebuild === //
+		let grid_size = Vec2::new(grid_size.x, grid_size.y);
+		for chunk in dirty_chunks {
+			for collider in colliders.chunks.remove(&chunk).into_iter().flatten() {
+				commands.entity(collider).despawn_recursive();
+			}
+
+			let min = chunk * COLLIDER_CHUNK_SIZE;
+			let chunk_size = (size - min).min(UVec2::splat(COLLIDER_CHUNK_SIZE));
+			let solid = (min.y..min.y + chunk_size.y)
+				.flat_map(|y| (min.x..min.x + chunk_size.x).map(move |x| (y * size.x + x) as usize))
+				.map(|index| colliders.solid[index])
+				.collect::<Vec<_>>();
+
+			let mut spawned = Vec::new();
+			for (rect_min, rect_size) in merge_rects(&solid, chunk_size) {
+				// Tile positions are the center of each tile
+				let center = ((min + rect_min).as_vec2() + (rect_size.as_vec2() - Vec2::ONE) / 2.0)
+					* grid_size;
+				let mut cmds = commands.spawn(TransformBundle::from_transform(
+					Transform::from_translation(center.extend(0.0)),
+				));
+				insert_collider(&mut cmds, rect_size.as_vec2() * grid_size);
+				spawned.push(cmds.id());
+			}
+
+			if !spawned.is_empty() {
+				commands.entity(tilemap).push_children(&spawned);
+				colliders.chunks.insert(chunk, spawned);
+			}
+		}
+
+		if let Some(colliders) = added {
+			commands.entity(tilemap).insert(colliders);
+		}
+	}
+}
+
+/// Merge the given grid of solid cells into rectangles (returned as the min cell and size)
+///
+/// This greedily expands each rectangle right, then up, as far as possible.
+fn merge_rects(solid: &[bool], size: UVec2) -> Vec<(UVec2, UVec2)> {
+	let index = |x: u32, y: u32| (y * size.x + x) as usize;
+	let mut used = vec![false; solid.len()];
+	let mut rects = Vec::new();
+
+	for y in 0..size.y {
+		for x in 0..size.x {
+			if !solid[index(x, y)] || used[index(x, y)] {
+				continue;
+			}
+
+			let mut width = 1;
+			while x + width < size.x && solid[index(x + width, y)] && !used[index(x + width, y)] {
+				width += 1;
+			}
+
+			let mut height = 1;
+			while y + height < size.y
+				&& (x..x + width)
+					.all(|x| solid[index(x, y + height)] && !used[index(x, y + height)])
+			{
+				height += 1;
+			}
+
+			for used_y in y..y + height {
+				for used_x in x..x + width {
+					used[index(used_x, used_y)] = true;
+				}
+			}
+
+			rects.push((UVec2::new(x, y), UVec2::new(width, height)));
+		}
+	}
+
+	rects
+}
+
+/// Insert the collider components for each enabled physics engine
+#[allow(unused_variables)]
+fn insert_collider(cmds: &mut EntityCommands, size: Vec2) {
+	#[cfg(feature = "rapier2d")]
+	cmds.insert((
+		bevy_rapier2d::prelude::RigidBody::Fixed,
+		bevy_rapier2d::prelude::Collider::cuboid(size.x / 2.0, size.y / 2.0),
+	));
+
+	#[cfg(feature = "xpbd")]
+	cmds.insert((
+		bevy_xpbd_2d::prelude::RigidBody::Static,
+		bevy_xpbd_2d::prelude::Collider::cuboid(size.x, size.y),
+	));
+}
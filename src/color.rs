@@ -0,0 +1,164 @@
+//! Tools for tinting placed tiles
+
+use bevy::prelude::{Changed, Color, Component, Entity, Or, Query, RemovedComponents};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::{TileColor, TileParent, TilePos, TileStorage};
+use bevy_tileset::prelude::TileId;
+
+use crate::manager::{LayerOpacity, TilemapLayer};
+use crate::placement::{MapId, TilePlacementResult, TilePlacer};
+
+/// A component tinting a placed tile
+///
+/// The [`TileColor`] of a tile is composed from this tint, the tints applied by lighting and fog
+/// of war, and the [`LayerOpacity`] of its layer. Set this rather than the [`TileColor`] itself,
+/// which is overwritten whenever any of them changes. Tiles without this component are untinted.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub struct TileTint(pub Color);
+
+impl Default for TileTint {
+	fn default() -> Self {
+		Self(Color::WHITE)
+	}
+}
+
+/// The tint applied to a tile by lighting
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub(crate) struct LightTint(pub Color);
+
+/// The tint applied to a tile by fog of war
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub(crate) struct FogTint(pub Color);
+
+/// Multiply two colors channel by channel
+fn multiply(a: Color, b: Color) -> Color {
+	let [ar, ag, ab, aa] = a.as_rgba_f32();
+	let [br, bg, bb, ba] = b.as_rgba_f32();
+	Color::rgba(ar * br, ag * bg, ab * bb, aa * ba)
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Same as [`place`](Self::place), but tinting the placed tile with the given color
+	pub fn place_with_color<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		color: Color,
+	) -> TilePlacementResult {
+		let result = self.place(tile_id, pos, map_id, layer_id)?;
+		self.insert_on_added(&result, TileTint(color));
+		Ok(result)
+	}
+
+	/// Same as [`replace`](Self::replace), but tinting the placed tile with the given color
+	pub fn replace_with_color<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		color: Color,
+	) -> TilePlacementResult {
+		let result = self.replace(tile_id, pos, map_id, layer_id)?;
+		self.insert_on_added(&result, TileTint(color));
+		Ok(result)
+	}
+
+	/// Tint the tile at the given position with the given color
+	///
+	/// Returns the tinted tile entity, or `None` if no tile exists at that position.
+	pub fn update_color<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		color: Color,
+	) -> Option<Entity> {
+		let entity = self.find_entity(pos.into(), map_id, layer_id)?;
+		self.commands.entity(entity).insert(TileTint(color));
+		Some(entity)
+	}
+
+	/// Remove the tint of the tile at the given position
+	///
+	/// Returns the tile entity, or `None` if no tile exists at that position.
+	pub fn reset_color<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<Entity> {
+		let entity = self.find_entity(pos.into(), map_id, layer_id)?;
+		self.commands.entity(entity).remove::<TileTint>();
+		Some(entity)
+	}
+}
+
+/// __\[SYSTEM\]__ Composes the [`TileColor`] of each tile from its [`TileTint`], lighting, fog of
+/// war, and [`LayerOpacity`]
+///
+/// This is the only system writing tile colors, and runs after lighting and fog of war are
+/// updated. Only tiles whose tints changed and the tiles of layers whose opacity changed are
+/// updated.
+pub(crate) fn compose_tile_colors(
+	changed_tiles: Query<
+		Entity,
+		Or<(
+			Changed<TileParent>,
+			Changed<TileTint>,
+			Changed<LightTint>,
+			Changed<FogTint>,
+		)>,
+	>,
+	mut removed_tints: RemovedComponents<TileTint>,
+	mut removed_light: RemovedComponents<LightTint>,
+	mut removed_fog: RemovedComponents<FogTint>,
+	changed_layers: Query<&TileStorage, Changed<LayerOpacity>>,
+	layers: Query<(&TilemapLayer, &LayerOpacity)>,
+	mut tiles: Query<(
+		&TileParent,
+		Option<&TileTint>,
+		Option<&LightTint>,
+		Option<&FogTint>,
+		&mut TileColor,
+	)>,
+) {
+	let mut dirty = changed_tiles.iter().collect::<HashSet<_>>();
+	dirty.extend(removed_tints.iter());
+	dirty.extend(removed_light.iter());
+	dirty.extend(removed_fog.iter());
+	for storage in changed_layers.iter() {
+		dirty.extend(storage.iter().flatten().copied());
+	}
+	if dirty.is_empty() {
+		return;
+	}
+
+	let opacities = layers
+		.iter()
+		.map(|(layer, opacity)| ((layer.map_id, layer.layer_id), opacity.0))
+		.collect::<HashMap<_, _>>();
+	for entity in dirty {
+		let (parent, tint, light, fog, mut color) = match tiles.get_mut(entity) {
+			Ok(tile) => tile,
+			Err(..) => continue,
+		};
+
+		let mut composed = tint.map_or(Color::WHITE, |tint| tint.0);
+		if let Some(light) = light {
+			composed = multiply(composed, light.0);
+		}
+		if let Some(fog) = fog {
+			composed = multiply(composed, fog.0);
+		}
+		if let Some(opacity) = opacities.get(&(parent.map_id, parent.layer_id)) {
+			composed.set_a(composed.a() * opacity);
+		}
+
+		if color.0 != composed {
+			color.0 = composed;
+		}
+	}
+}
@@ -0,0 +1,154 @@
+//! Commands used to place and remove tiles without a [`TilePlacer`]
+
+use bevy::ecs::system::{Command, SystemState};
+use bevy::prelude::{Commands, Events, Mut, Resource, World};
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::changes::TileChangeLog;
+use crate::events::{PlaceTileRequest, PlaceTileResult, RemoveTileRequest, RemoveTileResult};
+use crate::placement::{MapId, TilePlacer};
+use crate::replay::TileReplay;
+
+/// An extension trait for placing and removing tiles with [`Commands`]
+///
+/// Unlike the [`PlaceTileRequest`] events, which are serviced during the [`TilesetMapStage`],
+/// these are applied at the next command flush. This makes them usable from exclusive systems
+/// and anywhere else a [`TilePlacer`] can't be constructed.
+///
+/// The outcome of each command is sent as a [`PlaceTileResult`] or [`RemoveTileResult`] event.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::Commands;
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{TileId, TilesetCommandsExt};
+/// fn place_spawn_point(mut commands: Commands) {
+///   # let spawn_point = TileId::new(0, 0);
+///   commands.place_tile(spawn_point, TilePos { x: 8, y: 8 }, 0u16, 1);
+/// }
+/// ```
+///
+/// [`TilesetMapStage`]: crate::prelude::TilesetMapStage
+pub trait TilesetCommandsExt {
+	/// Queue a tile to be placed using [`TilePlacer::place`]
+	fn place_tile<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> &mut Self;
+
+	/// Queue a tile to be placed using the method given by the request's mode
+	fn place_tile_request(&mut self, request: PlaceTileRequest) -> &mut Self;
+
+	/// Queue the tile at the given position to be removed
+	fn remove_tile<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> &mut Self;
+
+	/// Start replaying the changes recorded in the given log, at `speed` steps per second
+	///
+	/// This replaces any running [`TileReplay`], which can be used to pause or seek the replay.
+	fn replay(&mut self, log: &TileChangeLog, speed: f32) -> &mut Self;
+}
+
+impl<'w, 's> TilesetCommandsExt for Commands<'w, 's> {
+	fn place_tile<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> &mut Self {
+		self.place_tile_request(PlaceTileRequest::new(tile_id, pos, map_id, layer_id))
+	}
+
+	fn place_tile_request(&mut self, request: PlaceTileRequest) -> &mut Self {
+		self.add(PlaceTileCommand(request));
+		self
+	}
+
+	fn remove_tile<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> &mut Self {
+		self.add(RemoveTileCommand(RemoveTileRequest {
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+		}));
+		self
+	}
+
+	fn replay(&mut self, log: &TileChangeLog, speed: f32) -> &mut Self {
+		self.insert_resource(TileReplay::from_log(log, speed));
+		self
+	}
+}
+
+/// A command servicing a [`PlaceTileRequest`]
+struct PlaceTileCommand(PlaceTileRequest);
+
+impl Command for PlaceTileCommand {
+	fn write(self, world: &mut World) {
+		let result = with_placer(world, |placer| self.0.apply(placer));
+		if let Some(mut events) = world.get_resource_mut::<Events<PlaceTileResult>>() {
+			events.send(PlaceTileResult {
+				request: self.0,
+				result,
+			});
+		}
+	}
+}
+
+/// A command servicing a [`RemoveTileRequest`]
+struct RemoveTileCommand(RemoveTileRequest);
+
+impl Command for RemoveTileCommand {
+	fn write(self, world: &mut World) {
+		let RemoveTileRequest {
+			pos,
+			map_id,
+			layer_id,
+		} = self.0;
+		let result = with_placer(world, |placer| placer.remove(pos, map_id, layer_id));
+		if let Some(mut events) = world.get_resource_mut::<Events<RemoveTileResult>>() {
+			events.send(RemoveTileResult {
+				request: self.0,
+				result,
+			});
+		}
+	}
+}
+
+/// The state of the [`TilePlacer`] used by the commands
+///
+/// Creating the state initializes every parameter of the placer, so it's only done by the first
+/// command and then reused. This also keeps the placer's local state between commands.
+#[derive(Resource)]
+struct CommandPlacerState(SystemState<TilePlacer<'static, 'static>>);
+
+/// Run the given function with a [`TilePlacer`] borrowed from the world
+///
+/// Any commands queued by the placer are applied before returning, so the next command sees the
+/// tiles placed by this one.
+fn with_placer<R, F: FnOnce(&mut TilePlacer) -> R>(world: &mut World, f: F) -> R {
+	if !world.contains_resource::<CommandPlacerState>() {
+		let state = SystemState::new(world);
+		world.insert_resource(CommandPlacerState(state));
+	}
+
+	world.resource_scope(|world, mut state: Mut<CommandPlacerState>| {
+		let result = f(&mut state.0.get_mut(world));
+		state.0.apply(world);
+		result
+	})
+}
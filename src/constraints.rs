@@ -0,0 +1,122 @@
+//! Tools for restricting which tiles can be placed in each layer
+
+use bevy::prelude::Resource;
+use bevy::utils::{HashMap, HashSet};
+use bevy_tileset::prelude::{TileGroupId, TileId, TilesetId};
+
+use crate::placement::{MapId, TilePlacementError, TilePlacer};
+
+/// The tiles accepted by a single constrained layer
+#[derive(Debug, Clone, Default)]
+struct LayerConstraint {
+	tilesets: HashSet<TilesetId>,
+	groups: HashSet<(TilesetId, TileGroupId)>,
+}
+
+/// A resource restricting certain layers to tiles from specific tilesets or tile groups
+///
+/// This keeps layers with a special meaning (such as a collision layer) from being filled with
+/// unrelated tiles. A constrained layer accepts any tile from one of its allowed tilesets, along
+/// with any variant of its allowed tile groups. Layers without constraints accept every tile.
+///
+/// The [`TilePlacer`] checks these constraints before every placement, returning a
+/// [`TilePlacementError::DisallowedTile`] error for any other tile.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{LayerConstraints, TileId};
+/// # let (collision_tileset, wall, grass) = (0, TileId::new(1, 1), TileId::new(0, 2));
+/// let mut constraints = LayerConstraints::default();
+/// constraints
+///   // The collision layer only accepts collision tiles...
+///   .allow_tileset(0u16, 2, collision_tileset)
+///   // ...along with walls
+///   .allow_group(0u16, 2, wall);
+///
+/// assert!(constraints.allows(0u16, 2, &wall));
+/// assert!(!constraints.allows(0u16, 2, &grass));
+/// assert!(constraints.allows(0u16, 0, &grass));
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LayerConstraints {
+	layers: HashMap<(u16, u16), LayerConstraint>,
+}
+
+impl LayerConstraints {
+	/// Allow every tile of the given tileset in the given layer
+	pub fn allow_tileset<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		tileset_id: TilesetId,
+	) -> &mut Self {
+		self.layers
+			.entry((map_id.into(), layer_id))
+			.or_default()
+			.tilesets
+			.insert(tileset_id);
+		self
+	}
+
+	/// Allow every variant of the given tile's group in the given layer
+	pub fn allow_group<Id: Into<TileId>, MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		tile_id: Id,
+	) -> &mut Self {
+		let tile_id = tile_id.into();
+		self.layers
+			.entry((map_id.into(), layer_id))
+			.or_default()
+			.groups
+			.insert((tile_id.tileset_id, tile_id.group_id));
+		self
+	}
+
+	/// Remove the constraints of the given layer, allowing every tile in it again
+	///
+	/// Returns true if the layer was constrained.
+	pub fn clear<MId: MapId>(&mut self, map_id: MId, layer_id: u16) -> bool {
+		self.layers.remove(&(map_id.into(), layer_id)).is_some()
+	}
+
+	/// Returns true if the given layer has any constraints
+	pub fn is_constrained<MId: MapId>(&self, map_id: MId, layer_id: u16) -> bool {
+		self.layers.contains_key(&(map_id.into(), layer_id))
+	}
+
+	/// Returns true if the given tile may be placed in the given layer
+	pub fn allows<MId: MapId>(&self, map_id: MId, layer_id: u16, tile_id: &TileId) -> bool {
+		match self.layers.get(&(map_id.into(), layer_id)) {
+			Some(constraint) => {
+				constraint.tilesets.contains(&tile_id.tileset_id)
+					|| constraint
+						.groups
+						.contains(&(tile_id.tileset_id, tile_id.group_id))
+			},
+			None => true,
+		}
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Check the given placement against the [`LayerConstraints`]
+	pub(crate) fn check_layer_constraints(
+		&self,
+		tile_id: &TileId,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<(), TilePlacementError> {
+		if self.layer_constraints.allows(map_id, layer_id, tile_id) {
+			Ok(())
+		} else {
+			Err(TilePlacementError::DisallowedTile {
+				tile_id: *tile_id,
+				map_id,
+				layer_id,
+			})
+		}
+	}
+}
@@ -1,9 +1,10 @@
 use bevy::math::{IVec2, UVec2};
+use bevy::reflect::{FromReflect, Reflect};
 use bevy_ecs_tilemap::tiles::TilePos;
 use bevy_tileset::tileset::coords::TileCoords;
 
 /// The coordinates of a tile, including the `map_id` and `layer_id`
-#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Reflect, FromReflect)]
 #[cfg_attr(
 	feature = "serialization",
 	derive(serde::Serialize, serde::Deserialize)
@@ -26,3 +27,20 @@ impl TileCoords for TileCoord {
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(remote = "TilePos")]
 pub(crate) struct TilePosRef(pub u32, pub u32);
+
+/// Get every position within `radius` tiles of the given position
+///
+/// The region is circular, using the distance between tile centers.
+pub(crate) fn circle(center: TilePos, radius: u32) -> impl Iterator<Item = TilePos> {
+	let radius = radius as i64;
+	(-radius..=radius).flat_map(move |dy| {
+		(-radius..=radius).filter_map(move |dx| {
+			if dx * dx + dy * dy > radius * radius {
+				return None;
+			}
+			let x = u32::try_from(center.x as i64 + dx).ok()?;
+			let y = u32::try_from(center.y as i64 + dy).ok()?;
+			Some(TilePos { x, y })
+		})
+	})
+}
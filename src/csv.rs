@@ -0,0 +1,242 @@
+//! Tools for importing and exporting layers as CSV or plain 2D grids
+
+use bevy::math::UVec2;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::{TileGroupId, TileId, Tileset, TilesetId};
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// A mapping between CSV cell values and [`TileId`]s
+///
+/// This is implemented for [`Tileset`], which maps each tile to its name, and [`CellMapping`],
+/// which maps arbitrary values (such as the integer IDs used by other engines).
+pub trait TileCellMapping {
+	/// Get the tile for the given (non-empty) cell value
+	///
+	/// Returns `None` if no tile is mapped to the value.
+	fn to_tile(&self, cell: &str) -> Option<TileId>;
+
+	/// Get the cell value for the given tile
+	///
+	/// Returns `None` if the tile isn't mapped to any value.
+	fn to_cell(&self, tile_id: &TileId) -> Option<String>;
+
+	/// Returns true if the given cell value represents an empty cell
+	fn is_empty_cell(&self, cell: &str) -> bool {
+		cell.is_empty()
+	}
+
+	/// The value written for empty cells
+	fn empty_cell(&self) -> String {
+		String::new()
+	}
+}
+
+impl TileCellMapping for Tileset {
+	fn to_tile(&self, cell: &str) -> Option<TileId> {
+		let group_id = self.get_tile_group_id(cell)?;
+		Some(TileId::new(*group_id, *self.id()))
+	}
+
+	fn to_cell(&self, tile_id: &TileId) -> Option<String> {
+		if tile_id.tileset_id != *self.id() {
+			return None;
+		}
+		self.get_tile_name(&tile_id.group_id).cloned()
+	}
+}
+
+/// A [`TileCellMapping`] between arbitrary cell values and tiles
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{CellMapping, TileId};
+/// # let (grass, water) = (TileId::new(0, 0), TileId::new(1, 0));
+/// let mut mapping = CellMapping::default();
+/// mapping.insert("1", grass).insert("2", water).with_empty("0");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CellMapping {
+	tiles: HashMap<String, TileId>,
+	cells: HashMap<(TilesetId, TileGroupId), String>,
+	empty: Option<String>,
+}
+
+impl CellMapping {
+	/// Map the given cell value to the given tile
+	///
+	/// Tiles are matched by group when exporting, so every variant of a tile is written as the
+	/// same value.
+	pub fn insert<Id: Into<TileId>>(&mut self, cell: &str, tile_id: Id) -> &mut Self {
+		let tile_id = tile_id.into();
+		self.tiles.insert(cell.to_string(), tile_id);
+		self.cells
+			.insert((tile_id.tileset_id, tile_id.group_id), cell.to_string());
+		self
+	}
+
+	/// Set the value representing an empty cell (in addition to an empty string)
+	pub fn with_empty(&mut self, cell: &str) -> &mut Self {
+		self.empty = Some(cell.to_string());
+		self
+	}
+}
+
+impl TileCellMapping for CellMapping {
+	fn to_tile(&self, cell: &str) -> Option<TileId> {
+		self.tiles.get(cell).copied()
+	}
+
+	fn to_cell(&self, tile_id: &TileId) -> Option<String> {
+		self.cells
+			.get(&(tile_id.tileset_id, tile_id.group_id))
+			.cloned()
+	}
+
+	fn is_empty_cell(&self, cell: &str) -> bool {
+		cell.is_empty() || self.empty.as_deref() == Some(cell)
+	}
+
+	fn empty_cell(&self) -> String {
+		self.empty.clone().unwrap_or_default()
+	}
+}
+
+impl TileStamp {
+	/// Create a single-layer stamp from a grid of tiles
+	///
+	/// Rows go from top to bottom, like in a spreadsheet, so the first row ends up at the top
+	/// of the stamp. The stamp is as wide as its longest row.
+	pub fn from_grid(rows: &[Vec<Option<TileId>>]) -> Self {
+		let width = rows.iter().map(Vec::len).max().unwrap_or_default() as u32;
+		let height = rows.len() as u32;
+		let mut stamp = Self::new(width, height);
+		for (row, tiles) in rows.iter().enumerate() {
+			let y = height - 1 - row as u32;
+			for (x, tile_id) in tiles.iter().enumerate() {
+				if tile_id.is_some() {
+					stamp.set(TilePos { x: x as u32, y }, 0, *tile_id);
+				}
+			}
+		}
+		stamp
+	}
+
+	/// Get the grid of tiles in the given layer of this stamp
+	///
+	/// Rows go from top to bottom, matching [`from_grid`](Self::from_grid).
+	pub fn to_grid(&self, layer_id: u16) -> Vec<Vec<Option<TileId>>> {
+		let size = self.size();
+		(0..size.y)
+			.rev()
+			.map(|y| {
+				(0..size.x)
+					.map(|x| self.get(TilePos { x, y }, layer_id).copied())
+					.collect()
+			})
+			.collect()
+	}
+
+	/// Create a single-layer stamp from CSV data, using the given mapping to find each tile
+	///
+	/// Values are separated by commas and trimmed of whitespace. Blank lines are skipped.
+	pub fn from_csv<M: TileCellMapping + ?Sized>(
+		data: &str,
+		mapping: &M,
+	) -> Result<Self, TilePlacementError> {
+		let rows = data
+			.lines()
+			.filter(|line| !line.trim().is_empty())
+			.map(|line| {
+				line.split(',')
+					.map(|cell| {
+						let cell = cell.trim();
+						if mapping.is_empty_cell(cell) {
+							return Ok(None);
+						}
+						mapping
+							.to_tile(cell)
+							.map(Some)
+							.ok_or_else(|| TilePlacementError::UnmappedCell(cell.to_string()))
+					})
+					.collect::<Result<Vec<_>, _>>()
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Self::from_grid(&rows))
+	}
+
+	/// Write the given layer of this stamp as CSV data, using the given mapping to name each tile
+	///
+	/// Fails if any tile isn't mapped to a value.
+	pub fn to_csv<M: TileCellMapping + ?Sized>(
+		&self,
+		layer_id: u16,
+		mapping: &M,
+	) -> Result<String, TilePlacementError> {
+		let mut csv = String::new();
+		for row in self.to_grid(layer_id) {
+			let cells = row
+				.iter()
+				.map(|tile_id| match tile_id {
+					Some(tile_id) => mapping
+						.to_cell(tile_id)
+						.ok_or(TilePlacementError::InvalidTile(*tile_id)),
+					None => Ok(mapping.empty_cell()),
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			csv.push_str(&cells.join(","));
+			csv.push('\n');
+		}
+		Ok(csv)
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Replace the given layer within a region with the tiles from CSV data
+	///
+	/// The first row of the data is placed at the top of the region, with the bottom-left corner
+	/// of the region at `origin`. Like [`paste`](Self::paste), existing tiles in empty cells are
+	/// removed. Nothing is placed if any cell isn't mapped to a tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TilePlacer, Tilesets};
+	/// fn load_level(mut placer: TilePlacer, tilesets: Tilesets) {
+	///   let tileset = tilesets.get_by_name("My Tileset").unwrap();
+	///   let level = "Wall,Wall,Wall\nWall,,Wall\nWall,Wall,Wall";
+	///   placer.import_csv(level, tileset, TilePos { x: 0, y: 0 }, 0u16, 0).unwrap();
+	/// }
+	/// ```
+	pub fn import_csv<M: TileCellMapping + ?Sized, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		data: &str,
+		mapping: &M,
+		origin: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let stamp = TileStamp::from_csv(data, mapping)?;
+		self.paste(&stamp, origin, map_id, layer_id)
+	}
+
+	/// Write the given layer within a region as CSV data
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. The top
+	/// row of the region is written first.
+	pub fn export_csv<M: TileCellMapping + ?Sized, Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		mapping: &M,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<String, TilePlacementError> {
+		self.copy_region(origin, size, map_id, layer_id)
+			.to_csv(0, mapping)
+	}
+}
@@ -0,0 +1,146 @@
+//! Diagnostics for measuring the cost of editing maps
+
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy::prelude::{
+	Added, App, CoreStage, Local, Plugin, Query, RemovedComponents, ResMut, StartupStage,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::TileParent;
+
+#[cfg(feature = "auto-tile")]
+use bevy::prelude::EventReader;
+
+#[cfg(feature = "auto-tile")]
+use crate::auto::{DirtyAutoTiles, RemoveAutoTileEvent};
+
+/// The number of measurements kept by each diagnostic
+const MAX_HISTORY: usize = 20;
+
+/// The base id of the per-map tile count diagnostics
+const TILE_COUNT_BASE: u128 = 0x6c3e_91a4_f2d0_4b87_a5e1_0000_0000_0000;
+
+/// Adds diagnostics for the tiles placed and removed each frame, the auto tile work done each
+/// frame, and the number of tiles in each map
+///
+/// These show up alongside bevy's own diagnostics, such as in the `LogDiagnosticsPlugin`, making
+/// performance regressions in map editing tools easy to spot.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy::diagnostic::LogDiagnosticsPlugin;
+/// # use bevy_tileset_map::prelude::{TilesetMapDiagnosticsPlugin, TilesetMapPlugin};
+/// App::new()
+///   .add_plugin(TilesetMapPlugin::default())
+///   .add_plugin(TilesetMapDiagnosticsPlugin)
+///   .add_plugin(LogDiagnosticsPlugin::default());
+/// ```
+#[derive(Default)]
+pub struct TilesetMapDiagnosticsPlugin;
+
+impl Plugin for TilesetMapDiagnosticsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_startup_system_to_stage(StartupStage::PreStartup, Self::setup_system)
+			.add_system_to_stage(CoreStage::PostUpdate, Self::tile_diagnostic_system);
+
+		#[cfg(feature = "auto-tile")]
+		app.add_system_to_stage(CoreStage::PostUpdate, Self::auto_tile_diagnostic_system);
+	}
+}
+
+impl TilesetMapDiagnosticsPlugin {
+	/// The number of tiles spawned this frame
+	pub const TILES_PLACED: DiagnosticId =
+		DiagnosticId::from_u128(0x1f4a_7c20_93d5_4e61_b8f2_6d0c_5a37_e914);
+	/// The number of tiles despawned this frame
+	pub const TILES_REMOVED: DiagnosticId =
+		DiagnosticId::from_u128(0x8b2e_05f7_4c19_4d3a_9e6b_f1a8_2c74_d053);
+	/// The number of [`RemoveAutoTileEvent`]s processed this frame
+	#[cfg(feature = "auto-tile")]
+	pub const AUTO_TILE_EVENTS: DiagnosticId =
+		DiagnosticId::from_u128(0xd93c_6a18_2f7e_4b05_a4c1_7e5d_0b96_f328);
+	/// The number of auto tiles resolved this frame
+	#[cfg(feature = "auto-tile")]
+	pub const DIRTY_TILES_RESOLVED: DiagnosticId =
+		DiagnosticId::from_u128(0x42a7_e8d1_5b3c_4f96_8d20_c9f4_13e6_7ab5);
+
+	/// Get the id of the diagnostic measuring the total number of tiles in the given map
+	///
+	/// This diagnostic is added the first time a tile is found in the map.
+	pub const fn tile_count(map_id: u16) -> DiagnosticId {
+		DiagnosticId::from_u128(TILE_COUNT_BASE | map_id as u128)
+	}
+
+	fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+		diagnostics.add(Diagnostic::new(
+			Self::TILES_PLACED,
+			"tiles_placed",
+			MAX_HISTORY,
+		));
+		diagnostics.add(Diagnostic::new(
+			Self::TILES_REMOVED,
+			"tiles_removed",
+			MAX_HISTORY,
+		));
+
+		#[cfg(feature = "auto-tile")]
+		{
+			diagnostics.add(Diagnostic::new(
+				Self::AUTO_TILE_EVENTS,
+				"auto_tile_events",
+				MAX_HISTORY,
+			));
+			diagnostics.add(Diagnostic::new(
+				Self::DIRTY_TILES_RESOLVED,
+				"dirty_tiles_resolved",
+				MAX_HISTORY,
+			));
+		}
+	}
+
+	fn tile_diagnostic_system(
+		mut diagnostics: ResMut<Diagnostics>,
+		tiles: Query<&TileParent>,
+		added: Query<(), Added<TileParent>>,
+		mut removed: RemovedComponents<TileParent>,
+		mut maps: Local<HashSet<u16>>,
+	) {
+		diagnostics.add_measurement(Self::TILES_PLACED, || added.iter().count() as f64);
+		diagnostics.add_measurement(Self::TILES_REMOVED, || removed.iter().count() as f64);
+
+		let mut counts = HashMap::<u16, usize>::default();
+		for parent in tiles.iter() {
+			*counts.entry(parent.map_id).or_default() += 1;
+		}
+
+		for map_id in counts.keys() {
+			if maps.insert(*map_id) {
+				let name = format!("tile_count/map_{}", map_id);
+				diagnostics.add(Diagnostic::new(
+					Self::tile_count(*map_id),
+					name,
+					MAX_HISTORY,
+				));
+			}
+		}
+		// Maps that lost all of their tiles still need to be measured
+		for map_id in maps.iter() {
+			let count = counts.get(map_id).copied().unwrap_or_default();
+			diagnostics.add_measurement(Self::tile_count(*map_id), || count as f64);
+		}
+	}
+
+	#[cfg(feature = "auto-tile")]
+	fn auto_tile_diagnostic_system(
+		mut diagnostics: ResMut<Diagnostics>,
+		mut events: EventReader<RemoveAutoTileEvent>,
+		mut dirty: ResMut<DirtyAutoTiles>,
+	) {
+		// Always consume the counts, even if the diagnostics are disabled
+		let processed = events.iter().count();
+		let resolved = dirty.take_resolved();
+		diagnostics.add_measurement(Self::AUTO_TILE_EVENTS, || processed as f64);
+		diagnostics.add_measurement(Self::DIRTY_TILES_RESOLVED, || resolved as f64);
+	}
+}
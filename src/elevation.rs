@@ -0,0 +1,100 @@
+//! Tools for stacking layers into elevated terrain
+
+use bevy::prelude::{Component, Resource};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::TileId;
+
+use crate::manager::TilemapLayer;
+use crate::placement::{MapId, TilePlacementResult, TilePlacer};
+
+/// A resource configuring how far apart elevated layers are drawn
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct ElevationSettings {
+	/// How far each height level is raised on the y-axis, as a fraction of the grid height
+	///
+	/// Defaults to `0.5`, which suits most isometric tiles.
+	pub step: f32,
+}
+
+impl Default for ElevationSettings {
+	fn default() -> Self {
+		Self { step: 0.5 }
+	}
+}
+
+/// A component marking a tilemap as a raised copy of a lower layer of its map
+///
+/// Elevated layers are stacked on top of their base layer (the layer `height` IDs below) and
+/// offset from it by [`ElevationSettings::step`] per height level on the y-axis and by one unit
+/// per level on the z-axis. This keeps the tiles of each layer ordered by the tilemap's usual
+/// rendering, while raised tiles are always drawn over the ones below them.
+///
+/// This is added automatically to layers placed into using [`TilePlacer::place_at_height`].
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LayerElevation(pub u16);
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Place a tile raised by the given number of height levels above `base_layer`
+	///
+	/// Each height level is its own layer: the tile is placed in layer `base_layer + height`,
+	/// which must already exist (see [`TilemapManager::create_layer`]) and is marked with a
+	/// [`LayerElevation`] so it's drawn at the right height.
+	///
+	/// [`TilemapManager::create_layer`]: crate::prelude::TilemapManager::create_layer
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+	/// fn build_hill(mut placer: TilePlacer) {
+	///   # let dirt = TileId::new(0, 0);
+	///   for height in 0..3 {
+	///     placer.place_at_height(dirt, TilePos { x: 4, y: 4 }, height, 0u16, 0).unwrap();
+	///   }
+	/// }
+	/// ```
+	pub fn place_at_height<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		height: u16,
+		map_id: MId,
+		base_layer: u16,
+	) -> TilePlacementResult {
+		let layer_id = base_layer + height;
+		if height > 0 {
+			let key = (map_id.into(), layer_id);
+			let tilemap = self
+				.tilemaps
+				.iter()
+				.find(|(_, layer)| (layer.map_id, layer.layer_id) == key)
+				.map(|(entity, _)| entity);
+			if let Some(tilemap) = tilemap {
+				self.commands.entity(tilemap).insert(LayerElevation(height));
+			}
+		}
+
+		self.place(tile_id, pos, map_id, layer_id)
+	}
+
+	/// Get the height of the topmost tile at the given position, counting up from `base_layer`
+	///
+	/// Returns `None` if no layer in the stack has a tile at this position.
+	pub fn height_at<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		base_layer: u16,
+	) -> Option<u16> {
+		let pos = pos.into();
+		let map_id = map_id.into();
+		self.tilemaps
+			.iter()
+			.filter(|(_, layer)| layer.map_id == map_id && layer.layer_id >= base_layer)
+			.map(|(_, layer)| layer.layer_id)
+			.filter(|layer_id| self.occupancy.is_occupied(pos, map_id, *layer_id))
+			.max()
+			.map(|layer_id| layer_id - base_layer)
+	}
+}
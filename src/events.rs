@@ -0,0 +1,145 @@
+//! Events used to place and remove tiles without a [`TilePlacer`]
+
+use bevy::prelude::{EventReader, EventWriter};
+use bevy::reflect::{FromReflect, Reflect};
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, TilePlacementError, TilePlacementResult, TilePlacer};
+
+/// The [`TilePlacer`] method used to service a [`PlaceTileRequest`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+#[cfg_attr(
+	feature = "serialization",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum PlaceTileMode {
+	/// Uses [`TilePlacer::place`]
+	Place,
+	/// Uses [`TilePlacer::try_place`]
+	TryPlace,
+	/// Uses [`TilePlacer::replace`]
+	Replace,
+	/// Uses [`TilePlacer::toggle`]
+	Toggle,
+	/// Uses [`TilePlacer::toggle_matching`]
+	ToggleMatching,
+}
+
+impl Default for PlaceTileMode {
+	fn default() -> Self {
+		Self::Place
+	}
+}
+
+/// An event requesting that a tile be placed
+///
+/// This is useful for systems that can't use the [`TilePlacer`] directly (such as those with
+/// conflicting queries). Requests are serviced by the [`TilesetMapPlugin`] during the
+/// [`TilesetMapStage`], with the outcome sent as a [`PlaceTileResult`] event.
+///
+/// [`TilesetMapPlugin`]: crate::prelude::TilesetMapPlugin
+/// [`TilesetMapStage`]: crate::prelude::TilesetMapStage
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PlaceTileRequest {
+	pub tile_id: TileId,
+	pub pos: TilePos,
+	pub map_id: u16,
+	pub layer_id: u16,
+	pub mode: PlaceTileMode,
+}
+
+impl PlaceTileRequest {
+	/// Create a request that places the tile using [`TilePlacer::place`]
+	pub fn new<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Self {
+		Self {
+			tile_id: tile_id.into(),
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+			mode: PlaceTileMode::default(),
+		}
+	}
+
+	/// Set the method used to place the tile
+	pub fn with_mode(mut self, mode: PlaceTileMode) -> Self {
+		self.mode = mode;
+		self
+	}
+
+	/// Service this request using the given placer
+	pub(crate) fn apply(&self, placer: &mut TilePlacer) -> TilePlacementResult {
+		let Self {
+			tile_id,
+			pos,
+			map_id,
+			layer_id,
+			mode,
+		} = *self;
+		match mode {
+			PlaceTileMode::Place => placer.place(tile_id, pos, map_id, layer_id),
+			PlaceTileMode::TryPlace => placer.try_place(tile_id, pos, map_id, layer_id),
+			PlaceTileMode::Replace => placer.replace(tile_id, pos, map_id, layer_id),
+			PlaceTileMode::Toggle => placer.toggle(tile_id, pos, map_id, layer_id),
+			PlaceTileMode::ToggleMatching => placer.toggle_matching(tile_id, pos, map_id, layer_id),
+		}
+	}
+}
+
+/// An event requesting that a tile be removed
+///
+/// Like [`PlaceTileRequest`], this is serviced by the [`TilesetMapPlugin`], with the outcome
+/// sent as a [`RemoveTileResult`] event.
+///
+/// [`TilesetMapPlugin`]: crate::prelude::TilesetMapPlugin
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RemoveTileRequest {
+	pub pos: TilePos,
+	pub map_id: u16,
+	pub layer_id: u16,
+}
+
+/// An event containing the outcome of a [`PlaceTileRequest`]
+#[derive(Debug)]
+pub struct PlaceTileResult {
+	pub request: PlaceTileRequest,
+	pub result: TilePlacementResult,
+}
+
+/// An event containing the outcome of a [`RemoveTileRequest`]
+#[derive(Debug)]
+pub struct RemoveTileResult {
+	pub request: RemoveTileRequest,
+	pub result: Result<(), TilePlacementError>,
+}
+
+/// __\[SYSTEM\]__ Services [`PlaceTileRequest`] and [`RemoveTileRequest`] events
+///
+/// Removals are serviced before placements.
+pub(crate) fn handle_tile_requests(
+	mut placer: TilePlacer,
+	mut place_requests: EventReader<PlaceTileRequest>,
+	mut remove_requests: EventReader<RemoveTileRequest>,
+	mut place_results: EventWriter<PlaceTileResult>,
+	mut remove_results: EventWriter<RemoveTileResult>,
+) {
+	for request in remove_requests.iter() {
+		let result = placer.remove(request.pos, request.map_id, request.layer_id);
+		remove_results.send(RemoveTileResult {
+			request: *request,
+			result,
+		});
+	}
+
+	for request in place_requests.iter() {
+		place_results.send(PlaceTileResult {
+			request: *request,
+			result: request.apply(&mut placer),
+		});
+	}
+}
@@ -0,0 +1,189 @@
+//! Tools for rendering a map into a single image on the CPU
+
+use std::collections::BTreeMap;
+
+use bevy::ecs::system::SystemParam;
+use bevy::math::{UVec2, Vec2};
+use bevy::prelude::{Assets, Color, Image, Query, Res, TextureAtlas};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TilesetParent, Tilesets};
+
+use crate::manager::TilemapLayer;
+use crate::placement::MapId;
+
+/// A system param used to composite the layers of a map into a single [`Image`]
+///
+/// Tiles are drawn at the native resolution of their tileset using the tileset textures, so the
+/// result matches what the map looks like in-game (minus any camera effects). This is useful for
+/// sharing screenshots of generated worlds or creating thumbnails for a save menu.
+///
+/// Maps are drawn as a square grid with layers composited from lowest to highest. Only tileset
+/// textures in the `Rgba8UnormSrgb` or `Rgba8Unorm` formats are supported.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::MapRenderer;
+/// fn save_screenshot(renderer: MapRenderer) {
+///   if let Some(image) = renderer.render_map(0u16) {
+///     image.try_into_dynamic().unwrap().save("map.png").unwrap();
+///   }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct MapRenderer<'w, 's> {
+	tiles: Query<
+		'w,
+		's,
+		(
+			&'static TilePos,
+			&'static TileParent,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+			Option<&'static TileFlip>,
+			Option<&'static TileColor>,
+		),
+	>,
+	tilemaps: Query<'w, 's, (&'static TilemapLayer, &'static TilemapSize)>,
+	tilesets: Tilesets<'w, 's>,
+	atlases: Res<'w, Assets<TextureAtlas>>,
+	images: Res<'w, Assets<Image>>,
+}
+
+/// A tile to be drawn onto the rendered image
+struct DrawnTile<'a> {
+	pos: TilePos,
+	texture: &'a Image,
+	/// The region of the texture containing the tile
+	min: UVec2,
+	size: UVec2,
+	flip: TileFlip,
+	color: Color,
+}
+
+impl<'w, 's> MapRenderer<'w, 's> {
+	/// Render every layer of the given map
+	///
+	/// Returns `None` if the map has no tiles or its tileset textures haven't been loaded.
+	pub fn render_map<MId: MapId>(&self, map_id: MId) -> Option<Image> {
+		self.render(map_id.into(), None)
+	}
+
+	/// Render a single layer of the given map
+	///
+	/// Returns `None` if the layer has no tiles or its tileset textures haven't been loaded.
+	pub fn render_layer<MId: MapId>(&self, map_id: MId, layer_id: u16) -> Option<Image> {
+		self.render(map_id.into(), Some(layer_id))
+	}
+
+	fn render(&self, map_id: u16, layer_id: Option<u16>) -> Option<Image> {
+		// === Collect Tiles === //
+		let mut layers = BTreeMap::<u16, Vec<DrawnTile>>::new();
+		let mut cell = Vec2::ZERO;
+		let mut extent = UVec2::ZERO;
+		for (layer, size) in self.tilemaps.iter() {
+			if layer.map_id == map_id && layer_id.map_or(true, |id| id == layer.layer_id) {
+				extent = extent.max(UVec2::new(size.x, size.y));
+			}
+		}
+
+		for (pos, parent, index, tileset, flip, color) in self.tiles.iter() {
+			if parent.map_id != map_id || layer_id.map_or(false, |id| id != parent.layer_id) {
+				continue;
+			}
+
+			let tileset = self.tilesets.get_by_id(&tileset.0)?;
+			let atlas = self.atlases.get(tileset.atlas())?;
+			let texture = self.images.get(tileset.texture())?;
+			if !matches!(
+				texture.texture_descriptor.format,
+				TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm
+			) {
+				return None;
+			}
+			let rect = atlas.textures.get(index.0 as usize)?;
+
+			cell = cell.max(tileset.tile_size());
+			// Layers without a tilemap are sized to fit their tiles
+			extent = extent.max(UVec2::new(pos.x + 1, pos.y + 1));
+			layers.entry(parent.layer_id).or_default().push(DrawnTile {
+				pos: *pos,
+				texture,
+				min: rect.min.as_uvec2(),
+				size: rect.size().as_uvec2(),
+				flip: flip.copied().unwrap_or_default(),
+				color: color.map_or(Color::WHITE, |color| color.0),
+			});
+		}
+
+		if layers.is_empty() {
+			return None;
+		}
+
+		// === Draw === //
+		let cell = cell.as_uvec2();
+		let size = extent * cell;
+		let mut image = Image::new_fill(
+			Extent3d {
+				width: size.x,
+				height: size.y,
+				depth_or_array_layers: 1,
+			},
+			TextureDimension::D2,
+			&[0, 0, 0, 0],
+			TextureFormat::Rgba8UnormSrgb,
+		);
+
+		for tile in layers.values().flatten() {
+			// Images go top-down while bevy_ecs_tilemap goes bottom-up
+			let left = tile.pos.x * cell.x;
+			let top = (extent.y - 1 - tile.pos.y) * cell.y + (cell.y - tile.size.y.min(cell.y));
+			let tint = tile.color.as_rgba_f32();
+			let texture_width = tile.texture.texture_descriptor.size.width;
+
+			for y in 0..tile.size.y.min(cell.y) {
+				for x in 0..tile.size.x.min(cell.x) {
+					let (mut sx, mut sy) = (x, y);
+					if tile.flip.d {
+						std::mem::swap(&mut sx, &mut sy);
+					}
+					if tile.flip.x {
+						sx = tile.size.x - 1 - sx;
+					}
+					if tile.flip.y {
+						sy = tile.size.y - 1 - sy;
+					}
+
+					let src = (((tile.min.y + sy) * texture_width + tile.min.x + sx) * 4) as usize;
+					let dst = (((top + y) * size.x + left + x) * 4) as usize;
+					let src = match tile.texture.data.get(src..src + 4) {
+						Some(src) => src,
+						None => continue,
+					};
+					blend(&mut image.data[dst..dst + 4], src, tint);
+				}
+			}
+		}
+
+		Some(image)
+	}
+}
+
+/// Blend a tinted source pixel over a destination pixel
+fn blend(dst: &mut [u8], src: &[u8], tint: [f32; 4]) {
+	let src_alpha = src[3] as f32 / 255.0 * tint[3];
+	let dst_alpha = dst[3] as f32 / 255.0;
+	let alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+	if alpha <= 0.0 {
+		return;
+	}
+
+	for channel in 0..3 {
+		let src = src[channel] as f32 * tint[channel];
+		let dst_color = dst[channel] as f32;
+		let blended = (src * src_alpha + dst_color * dst_alpha * (1.0 - src_alpha)) / alpha;
+		dst[channel] = blended.round().clamp(0.0, 255.0) as u8;
+	}
+	dst[3] = (alpha * 255.0).round() as u8;
+}
@@ -0,0 +1,196 @@
+//! A fluent interface for placing tiles
+
+use bevy::prelude::{Bundle, Color};
+use bevy_ecs_tilemap::prelude::{TileFlip, TilePos};
+use bevy_tileset::prelude::TileId;
+
+use crate::color::TileTint;
+use crate::placement::{MapId, TilePlacementError, TilePlacementResult, TilePlacer};
+use crate::policy::PlacementPolicy;
+use crate::target::DefaultMapTarget;
+use crate::transform::{TileRotation, TileTransform};
+
+/// A placement being configured, created by [`TilePlacer::at`]
+///
+/// Each optional parameter of the placement is set by its own method, and the placement is
+/// carried out once [`place`](Self::place), [`try_place`](Self::try_place),
+/// [`replace`](Self::replace), or [`remove`](Self::remove) is called. Unless
+/// [`on`](Self::on) is called, the tile is placed in the [`DefaultMapTarget`].
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{Color, Component};
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+/// # #[derive(Component)]
+/// # struct Burning;
+/// fn place_torch(mut placer: TilePlacer) {
+///   # let torch = TileId::new(0, 0);
+///   placer
+///     .at(TilePos { x: 3, y: 4 })
+///     .on(0u16, 1)
+///     .flipped_x()
+///     .tinted(Color::ORANGE)
+///     .with(Burning)
+///     .place(torch)
+///     .unwrap();
+/// }
+/// ```
+pub struct TilePlacement<'a, 'w, 's, B: Bundle = ()> {
+	placer: &'a mut TilePlacer<'w, 's>,
+	pos: TilePos,
+	target: DefaultMapTarget,
+	transform: TileTransform,
+	color: Option<Color>,
+	policy: Option<PlacementPolicy>,
+	bundle: B,
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Start configuring a placement at the given position
+	///
+	/// See [`TilePlacement`] for details.
+	pub fn at<Pos: Into<TilePos>>(&mut self, pos: Pos) -> TilePlacement<'_, 'w, 's> {
+		let target = self.default_target();
+		TilePlacement {
+			placer: self,
+			pos: pos.into(),
+			target,
+			transform: TileTransform::IDENTITY,
+			color: None,
+			policy: None,
+			bundle: (),
+		}
+	}
+}
+
+impl<'a, 'w, 's, B: Bundle> TilePlacement<'a, 'w, 's, B> {
+	/// Place the tile in the given map and layer
+	pub fn on<MId: MapId>(mut self, map_id: MId, layer_id: u16) -> Self {
+		self.target = DefaultMapTarget::new(map_id, layer_id);
+		self
+	}
+
+	/// Flip the tile horizontally
+	pub fn flipped_x(mut self) -> Self {
+		self.transform = self.transform.with_flip_x();
+		self
+	}
+
+	/// Flip the tile vertically
+	pub fn flipped_y(mut self) -> Self {
+		self.transform = self.transform.with_flip_y();
+		self
+	}
+
+	/// Rotate the tile clockwise
+	///
+	/// The rotation is applied before any flips, like any other [`TileTransform`].
+	pub fn rotated(mut self, rotation: TileRotation) -> Self {
+		self.transform.rotation = self.transform.rotation.then(rotation);
+		self
+	}
+
+	/// Flip and rotate the tile using the given transform, replacing any previous flip or rotation
+	pub fn transformed<T: Into<TileTransform>>(mut self, transform: T) -> Self {
+		self.transform = transform.into();
+		self
+	}
+
+	/// Tint the tile with the given color
+	pub fn tinted(mut self, color: Color) -> Self {
+		self.color = Some(color);
+		self
+	}
+
+	/// Place the tile using the given [`PlacementPolicy`]
+	pub fn with_policy(mut self, policy: PlacementPolicy) -> Self {
+		self.policy = Some(policy);
+		self
+	}
+
+	/// Also insert the given bundle onto the placed tile
+	///
+	/// Can be called multiple times to insert several bundles.
+	pub fn with<Other: Bundle>(self, bundle: Other) -> TilePlacement<'a, 'w, 's, (B, Other)> {
+		TilePlacement {
+			placer: self.placer,
+			pos: self.pos,
+			target: self.target,
+			transform: self.transform,
+			color: self.color,
+			policy: self.policy,
+			bundle: (self.bundle, bundle),
+		}
+	}
+
+	/// Place the tile, replacing any existing tile (see [`TilePlacer::place`])
+	pub fn place<Id: Into<TileId>>(self, tile_id: Id) -> TilePlacementResult {
+		let id = tile_id.into();
+		self.run(|placer, pos, DefaultMapTarget { map_id, layer_id }| {
+			placer.place(id, pos, map_id, layer_id)
+		})
+	}
+
+	/// Place the tile only if the cell is empty (see [`TilePlacer::try_place`])
+	pub fn try_place<Id: Into<TileId>>(self, tile_id: Id) -> TilePlacementResult {
+		let id = tile_id.into();
+		self.run(|placer, pos, DefaultMapTarget { map_id, layer_id }| {
+			placer.try_place(id, pos, map_id, layer_id)
+		})
+	}
+
+	/// Place the tile only if the cell contains a tile of another group (see
+	/// [`TilePlacer::replace`])
+	pub fn replace<Id: Into<TileId>>(self, tile_id: Id) -> TilePlacementResult {
+		let id = tile_id.into();
+		self.run(|placer, pos, DefaultMapTarget { map_id, layer_id }| {
+			placer.replace(id, pos, map_id, layer_id)
+		})
+	}
+
+	/// Remove the tile at the configured position (see [`TilePlacer::remove`])
+	///
+	/// The flip, tint, and bundles are ignored.
+	pub fn remove(self) -> Result<(), TilePlacementError> {
+		let DefaultMapTarget { map_id, layer_id } = self.target;
+		let pos = self.pos;
+		match self.policy {
+			Some(policy) => self
+				.placer
+				.with_policy(policy, |placer| placer.remove(pos, map_id, layer_id)),
+			None => self.placer.remove(pos, map_id, layer_id),
+		}
+	}
+
+	/// Carry out the placement, then apply the configured parameters to the placed tile
+	fn run<F: FnOnce(&mut TilePlacer<'w, 's>, TilePos, DefaultMapTarget) -> TilePlacementResult>(
+		self,
+		place: F,
+	) -> TilePlacementResult {
+		let Self {
+			placer,
+			pos,
+			target,
+			transform,
+			color,
+			policy,
+			bundle,
+		} = self;
+
+		let result = match policy {
+			Some(policy) => placer.with_policy(policy, |placer| place(placer, pos, target))?,
+			None => place(placer, pos, target)?,
+		};
+
+		placer.insert_on_added(&result, bundle);
+		if transform != TileTransform::IDENTITY {
+			placer.insert_on_added(&result, TileFlip::from(transform));
+		}
+		if let Some(color) = color {
+			placer.insert_on_added(&result, TileTint(color));
+		}
+		Ok(result)
+	}
+}
@@ -0,0 +1,227 @@
+//! Tools for hiding the unexplored parts of a map
+
+use bevy::prelude::{
+	Added, Color, Commands, DetectChangesMut, Entity, Local, Query, ResMut, Resource,
+};
+use bevy::utils::HashSet;
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::color::FogTint;
+use crate::coord::circle;
+use crate::placement::MapId;
+
+/// How a [`FogOfWar`] covers the tiles outside its revealed region
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FogMode {
+	/// Hide covered tiles entirely
+	Hide,
+	/// Tint covered tiles with the given color
+	Darken(Color),
+}
+
+impl Default for FogMode {
+	fn default() -> Self {
+		Self::Hide
+	}
+}
+
+/// A resource covering every tile of a map outside its revealed region
+///
+/// Covered tiles are hidden or darkened (depending on the [`FogMode`]) rather than removed, so
+/// they keep their auto tile connections and are still saved along with the map. With the
+/// `serialization` feature, the revealed region is saved and loaded along with the map as well.
+///
+/// Fog of war is disabled unless this resource is inserted. The tint of [`FogMode::Darken`] is
+/// combined with each covered tile's [`TileTint`](crate::prelude::TileTint) into its
+/// [`TileColor`].
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{Component, Query, ResMut, Transform, With};
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::FogOfWar;
+/// # #[derive(Component)]
+/// # struct Player;
+/// fn explore(mut fog: ResMut<FogOfWar>, players: Query<&Transform, With<Player>>) {
+///   for transform in players.iter() {
+///     let x = (transform.translation.x / 16.0) as u32;
+///     let y = (transform.translation.y / 16.0) as u32;
+///     fog.reveal(TilePos { x, y }, 4);
+///   }
+/// }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct FogOfWar {
+	/// The map covered by the fog
+	pub map_id: u16,
+	/// The layers covered by the fog, or `None` to cover every layer of the map
+	pub layers: Option<Vec<u16>>,
+	/// How covered tiles are drawn
+	pub mode: FogMode,
+	revealed: HashSet<TilePos>,
+	dirty: HashSet<TilePos>,
+}
+
+impl FogOfWar {
+	/// Cover every layer of the given map, hiding all of its tiles
+	pub fn new<MId: MapId>(map_id: MId) -> Self {
+		Self {
+			map_id: map_id.into(),
+			layers: None,
+			mode: FogMode::default(),
+			revealed: HashSet::default(),
+			dirty: HashSet::default(),
+		}
+	}
+
+	/// Only cover the given layers of the map
+	pub fn with_layers<I: IntoIterator<Item = u16>>(mut self, layers: I) -> Self {
+		self.layers = Some(layers.into_iter().collect());
+		self
+	}
+
+	/// Draw covered tiles using the given mode
+	pub fn with_mode(mut self, mode: FogMode) -> Self {
+		self.mode = mode;
+		self
+	}
+
+	/// Reveal every position within `radius` tiles of the given position
+	///
+	/// The revealed region is circular, using the distance between tile centers.
+	pub fn reveal<Pos: Into<TilePos>>(&mut self, pos: Pos, radius: u32) {
+		for pos in circle(pos.into(), radius) {
+			if self.revealed.insert(pos) {
+				self.dirty.insert(pos);
+			}
+		}
+	}
+
+	/// Cover every position within `radius` tiles of the given position again
+	pub fn conceal<Pos: Into<TilePos>>(&mut self, pos: Pos, radius: u32) {
+		for pos in circle(pos.into(), radius) {
+			if self.revealed.remove(&pos) {
+				self.dirty.insert(pos);
+			}
+		}
+	}
+
+	/// Cover the entire map again
+	pub fn conceal_all(&mut self) {
+		self.dirty.extend(self.revealed.drain());
+	}
+
+	/// Returns true if the given position has been revealed
+	pub fn is_revealed<Pos: Into<TilePos>>(&self, pos: Pos) -> bool {
+		self.revealed.contains(&pos.into())
+	}
+
+	/// Iterate over every revealed position
+	pub fn revealed(&self) -> impl Iterator<Item = &TilePos> {
+		self.revealed.iter()
+	}
+
+	/// Returns true if the given layer of the given map is covered by this fog
+	pub fn covers(&self, map_id: u16, layer_id: u16) -> bool {
+		covers(self.map_id, &self.layers, map_id, layer_id)
+	}
+}
+
+/// Returns true if a fog over the given map and layers covers the given layer
+fn covers(fog_map: u16, fog_layers: &Option<Vec<u16>>, map_id: u16, layer_id: u16) -> bool {
+	map_id == fog_map
+		&& fog_layers
+			.as_ref()
+			.map(|layers| layers.contains(&layer_id))
+			.unwrap_or(true)
+}
+
+/// The parts of a [`FogOfWar`] that require every tile to be redrawn when changed
+#[derive(Debug, Clone, PartialEq)]
+struct FogConfig {
+	map_id: u16,
+	layers: Option<Vec<u16>>,
+	mode: FogMode,
+}
+
+/// __\[SYSTEM\]__ Hides or darkens the tiles covered by the [`FogOfWar`]
+///
+/// Only the tiles at revealed or concealed positions (and newly placed tiles) are updated, unless
+/// the fog's map, layers, or mode change.
+pub(crate) fn apply_fog_of_war(
+	fog: Option<ResMut<FogOfWar>>,
+	added_tiles: Query<Entity, Added<TilePos>>,
+	mut tiles: Query<(
+		Entity,
+		&TilePos,
+		&TileParent,
+		&mut TileVisible,
+		Option<&FogTint>,
+	)>,
+	mut previous: Local<Option<FogConfig>>,
+	mut commands: Commands,
+) {
+	let mut fog = match fog {
+		Some(fog) => fog,
+		None => return,
+	};
+
+	let config = FogConfig {
+		map_id: fog.map_id,
+		layers: fog.layers.clone(),
+		mode: fog.mode,
+	};
+	let full = previous.as_ref() != Some(&config);
+	if !full && fog.dirty.is_empty() && added_tiles.is_empty() {
+		return;
+	}
+
+	let added: HashSet<Entity> = added_tiles.iter().collect();
+	let dirty = std::mem::take(&mut fog.bypass_change_detection().dirty);
+	for (entity, pos, parent, mut visible, fog_tint) in tiles.iter_mut() {
+		let mut new_visible = visible.0;
+		// Revealed tiles have no fog tint, so they go back to their own tint
+		let tint = fog_tint.map(|tint| tint.0);
+		let mut new_tint = tint;
+
+		// Undo the previous fog before applying the new one
+		if full {
+			match previous
+				.as_ref()
+				.filter(|prev| covers(prev.map_id, &prev.layers, parent.map_id, parent.layer_id))
+				.map(|prev| prev.mode)
+			{
+				Some(FogMode::Hide) => new_visible = true,
+				Some(FogMode::Darken(_)) => new_tint = None,
+				None => {},
+			}
+		}
+
+		let outdated = full || dirty.contains(pos) || added.contains(&entity);
+		if outdated && fog.covers(parent.map_id, parent.layer_id) {
+			let revealed = fog.revealed.contains(pos);
+			match fog.mode {
+				FogMode::Hide => new_visible = revealed,
+				FogMode::Darken(darken) => new_tint = (!revealed).then_some(darken),
+			}
+		}
+
+		if visible.0 != new_visible {
+			visible.0 = new_visible;
+		}
+		if tint != new_tint {
+			let mut cmds = commands.entity(entity);
+			match new_tint {
+				Some(new_tint) => {
+					cmds.insert(FogTint(new_tint));
+				},
+				None => {
+					cmds.remove::<FogTint>();
+				},
+			}
+		}
+	}
+
+	*previous = Some(config);
+}
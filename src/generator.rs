@@ -0,0 +1,78 @@
+//! Tools for procedurally generating regions of a map
+
+use bevy::math::UVec2;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// A source of tiles used to procedurally fill a region of a map
+///
+/// Any `Fn(TilePos) -> Option<TileId>` closure can be used as a generator.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{TileGenerator, TileId};
+/// /// Generates a checkerboard of two tiles
+/// struct Checkerboard(TileId, TileId);
+///
+/// impl TileGenerator for Checkerboard {
+///   fn tile_at(&self, pos: TilePos) -> Option<TileId> {
+///     if (pos.x + pos.y) % 2 == 0 {
+///       Some(self.0)
+///     } else {
+///       Some(self.1)
+///     }
+///   }
+/// }
+/// ```
+pub trait TileGenerator {
+	/// Get the tile to place at the given position
+	///
+	/// Returns `None` to leave the position empty.
+	fn tile_at(&self, pos: TilePos) -> Option<TileId>;
+}
+
+impl<F: Fn(TilePos) -> Option<TileId>> TileGenerator for F {
+	fn tile_at(&self, pos: TilePos) -> Option<TileId> {
+		self(pos)
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Fill a region of the given layer using a [`TileGenerator`]
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. The
+	/// generator is given the absolute position of each tile.
+	///
+	/// Like [`place_stamp`](Self::place_stamp), every generated tile is validated before any are
+	/// placed, and auto tiles are only updated once after the whole region is placed.
+	///
+	/// Returns the results of each placed tile.
+	pub fn generate<Pos: Into<TilePos>, G: TileGenerator, MId: MapId>(
+		&mut self,
+		origin: Pos,
+		size: UVec2,
+		generator: &G,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let origin = origin.into();
+
+		let mut stamp = TileStamp::new(size.x, size.y);
+		for y in 0..size.y {
+			for x in 0..size.x {
+				let tile = generator.tile_at(TilePos {
+					x: origin.x + x,
+					y: origin.y + y,
+				});
+				stamp.set(TilePos { x, y }, 0, tile);
+			}
+		}
+
+		self.place_stamp(&stamp, origin, map_id, layer_id)
+	}
+}
@@ -0,0 +1,174 @@
+//! Tools for previewing tiles under the cursor before they're placed
+
+use bevy::prelude::{
+	BuildChildren, Color, Commands, Component, DespawnRecursiveExt, Entity, GlobalTransform, Query,
+	SpriteSheetBundle, TextureAtlasSprite, Transform, Vec2,
+};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileId, TileIndex, Tilesets};
+
+use crate::manager::TilemapLayer;
+use crate::picker::TilePicker;
+use crate::placement::MapId;
+use crate::stamp::TileStamp;
+
+/// How far above its layer a ghost is drawn
+const GHOST_Z_OFFSET: f32 = 0.5;
+
+/// A component rendering a semi-transparent preview of a tile (or stamp) under the cursor
+///
+/// Every frame, the cursor is snapped to a cell of the ghost's layer and the preview is drawn
+/// there using the tileset's texture. The snapped position is available through
+/// [`pos`](Self::pos), making it easy to place the previewed tiles on click.
+///
+/// The preview is drawn using sprites spawned as children of the ghost, so the ghost needs a
+/// `SpatialBundle` (and despawning it with `despawn_recursive` removes the preview too).
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{Commands, SpatialBundle};
+/// # use bevy_tileset_map::prelude::{TileGhost, TileId};
+/// fn spawn_cursor(mut commands: Commands) {
+///   # let wall = TileId::new(0, 0);
+///   commands.spawn((TileGhost::new(wall, 0u16, 1), SpatialBundle::default()));
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TileGhost {
+	/// The tiles being previewed, with the cursor at the bottom-left corner of the stamp
+	pub stamp: TileStamp,
+	/// The map the ghost is snapped to
+	pub map_id: u16,
+	/// The layer the ghost is snapped to
+	pub layer_id: u16,
+	/// The tint of the preview
+	pub color: Color,
+	/// The cell under the cursor, or `None` if the cursor isn't over the layer
+	pos: Option<TilePos>,
+	/// The previewed tiles the [`sprites`](Self::sprites) were spawned for
+	rendered: Option<(TilePos, TileStamp, Color)>,
+	/// The sprites drawing the preview
+	sprites: Vec<Entity>,
+}
+
+impl TileGhost {
+	/// Create a ghost previewing a single tile
+	pub fn new<Id: Into<TileId>, MId: MapId>(tile_id: Id, map_id: MId, layer_id: u16) -> Self {
+		Self::from_stamp(
+			TileStamp::new(1, 1).with_tile(tile_id, TilePos { x: 0, y: 0 }, 0),
+			map_id,
+			layer_id,
+		)
+	}
+
+	/// Create a ghost previewing a stamp
+	pub fn from_stamp<MId: MapId>(stamp: TileStamp, map_id: MId, layer_id: u16) -> Self {
+		Self {
+			stamp,
+			map_id: map_id.into(),
+			layer_id,
+			color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+			pos: None,
+			rendered: None,
+			sprites: Vec::new(),
+		}
+	}
+
+	/// Set the tint of the preview
+	pub fn with_color(mut self, color: Color) -> Self {
+		self.color = color;
+		self
+	}
+
+	/// Preview the given tile instead
+	pub fn set_tile<Id: Into<TileId>>(&mut self, tile_id: Id) {
+		self.stamp = TileStamp::new(1, 1).with_tile(tile_id, TilePos { x: 0, y: 0 }, 0);
+	}
+
+	/// The cell under the cursor, or `None` if the cursor isn't over the layer
+	pub fn pos(&self) -> Option<TilePos> {
+		self.pos
+	}
+}
+
+/// __\[SYSTEM\]__ Snaps each [`TileGhost`] to the cursor and redraws it when it changes
+pub(crate) fn update_tile_ghosts(
+	mut commands: Commands,
+	mut ghosts: Query<(Entity, &mut TileGhost, &GlobalTransform)>,
+	picker: TilePicker,
+	tilemaps: Query<(
+		Entity,
+		&TilemapLayer,
+		&TilemapGridSize,
+		&TilemapTileSize,
+		&TilemapType,
+		&GlobalTransform,
+	)>,
+	tilesets: Tilesets,
+) {
+	for (entity, mut ghost, ghost_transform) in ghosts.iter_mut() {
+		let tilemap = tilemaps.iter().find(|(_, layer, ..)| {
+			layer.map_id == ghost.map_id && layer.layer_id == ghost.layer_id
+		});
+		let pos = tilemap.and_then(|(entity, ..)| picker.cursor_to_tile(entity));
+		if ghost.pos != pos {
+			ghost.pos = pos;
+		}
+
+		let current = pos.map(|pos| (pos, ghost.stamp.clone(), ghost.color));
+		if ghost.rendered == current {
+			continue;
+		}
+
+		for sprite in ghost.sprites.drain(..) {
+			commands.entity(sprite).despawn_recursive();
+		}
+		ghost.rendered = current;
+
+		let (pos, (_, _, grid_size, tile_size, map_type, transform)) = match (pos, tilemap) {
+			(Some(pos), Some(tilemap)) => (pos, tilemap),
+			_ => continue,
+		};
+
+		let mut sprites = Vec::new();
+		for (offset, layer, tile_id) in ghost.stamp.iter() {
+			let tileset = match tilesets.get_by_id(&tile_id.tileset_id) {
+				Some(tileset) => tileset,
+				None => continue,
+			};
+			let index = match tileset.get_tile_index_by_id(*tile_id) {
+				Some(TileIndex::Standard(index)) | Some(TileIndex::Animated(index, ..)) => index,
+				None => continue,
+			};
+
+			let cell = TilePos {
+				x: pos.x + offset.x,
+				y: pos.y + offset.y,
+			};
+			let local = cell.center_in_world(grid_size, map_type);
+			let world = transform.transform_point(local.extend(layer as f32 + GHOST_Z_OFFSET));
+			let translation = ghost_transform
+				.compute_matrix()
+				.inverse()
+				.transform_point3(world);
+
+			let sprite = commands
+				.spawn(SpriteSheetBundle {
+					sprite: TextureAtlasSprite {
+						index,
+						color: ghost.color,
+						custom_size: Some(Vec2::new(tile_size.x, tile_size.y)),
+						..Default::default()
+					},
+					texture_atlas: tileset.atlas().clone(),
+					transform: Transform::from_translation(translation),
+					..Default::default()
+				})
+				.id();
+			sprites.push(sprite);
+		}
+		commands.entity(entity).push_children(&sprites);
+		ghost.sprites = sprites;
+	}
+}
@@ -0,0 +1,156 @@
+//! Tools for handling the different grid layouts of a map
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::Query;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::helpers::hex_grid::neighbors::{
+	HexColDirection, HexNeighbors, HexRowDirection, HEX_DIRECTIONS,
+};
+use bevy_ecs_tilemap::helpers::square_grid::neighbors::Neighbors;
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::manager::TilemapLayer;
+
+/// The grid layout of a map, used to find the neighbors of a tile
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct MapGrid {
+	pub size: TilemapSize,
+	pub map_type: TilemapType,
+}
+
+impl Default for MapGrid {
+	/// An unbounded square grid
+	fn default() -> Self {
+		Self {
+			size: TilemapSize {
+				x: u32::MAX,
+				y: u32::MAX,
+			},
+			map_type: TilemapType::Square,
+		}
+	}
+}
+
+impl MapGrid {
+	/// Get the neighbors of the given tile position
+	///
+	/// Neighbors are returned in the order: north, east, south, west, north-east, north-west,
+	/// south-east, and south-west. Directions that don't exist for the map type (hexagonal maps
+	/// only have six neighbors) or lie outside the map are `None`.
+	pub fn neighbors(&self, pos: &TilePos) -> [Option<TilePos>; 8] {
+		match self.map_type {
+			TilemapType::Square | TilemapType::Isometric(IsoCoordSystem::Diamond) => {
+				square_neighbors(Neighbors::get_square_neighboring_positions(
+					pos, &self.size, true,
+				))
+			},
+			TilemapType::Isometric(IsoCoordSystem::Staggered) => square_neighbors(
+				Neighbors::get_staggered_neighboring_positions(pos, &self.size, true),
+			),
+			TilemapType::Hexagon(coord_sys) => {
+				let neighbors =
+					HexNeighbors::get_neighboring_positions(pos, &self.size, &coord_sys);
+				let is_row = matches!(
+					coord_sys,
+					HexCoordSystem::Row | HexCoordSystem::RowEven | HexCoordSystem::RowOdd
+				);
+
+				let mut result = [None; 8];
+				for direction in HEX_DIRECTIONS {
+					let slot = if is_row {
+						match HexRowDirection::from(direction) {
+							HexRowDirection::North => 0,
+							HexRowDirection::South => 2,
+							HexRowDirection::NorthEast => 4,
+							HexRowDirection::NorthWest => 5,
+							HexRowDirection::SouthEast => 6,
+							HexRowDirection::SouthWest => 7,
+						}
+					} else {
+						match HexColDirection::from(direction) {
+							HexColDirection::East => 1,
+							HexColDirection::West => 3,
+							HexColDirection::NorthEast => 4,
+							HexColDirection::NorthWest => 5,
+							HexColDirection::SouthEast => 6,
+							HexColDirection::SouthWest => 7,
+						}
+					};
+					result[slot] = neighbors.get(direction).copied();
+				}
+				result
+			},
+		}
+	}
+}
+
+/// Order the given square neighbors
+fn square_neighbors(neighbors: Neighbors<TilePos>) -> [Option<TilePos>; 8] {
+	[
+		neighbors.north,
+		neighbors.east,
+		neighbors.south,
+		neighbors.west,
+		neighbors.north_east,
+		neighbors.north_west,
+		neighbors.south_east,
+		neighbors.south_west,
+	]
+}
+
+/// The [`MapGrid`] of each layer, falling back to the default for layers without a tilemap
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MapGridLookup(HashMap<(u16, u16), MapGrid>);
+
+impl MapGridLookup {
+	/// Get the grid of the given layer
+	pub fn get(&self, map_id: u16, layer_id: u16) -> MapGrid {
+		self.0.get(&(map_id, layer_id)).copied().unwrap_or_default()
+	}
+}
+
+/// A system param used to find the [`MapGrid`] of each layer
+#[derive(SystemParam)]
+pub(crate) struct MapGrids<'w, 's> {
+	tilemaps: Query<
+		'w,
+		's,
+		(
+			&'static TilemapLayer,
+			&'static TilemapSize,
+			&'static TilemapType,
+		),
+	>,
+}
+
+impl<'w, 's> MapGrids<'w, 's> {
+	/// Get the grid of the given layer
+	///
+	/// Layers without a tilemap use the default (unbounded square) grid.
+	pub fn get(&self, map_id: u16, layer_id: u16) -> MapGrid {
+		self.tilemaps
+			.iter()
+			.find(|(layer, ..)| layer.map_id == map_id && layer.layer_id == layer_id)
+			.map(|(_, size, map_type)| MapGrid {
+				size: *size,
+				map_type: *map_type,
+			})
+			.unwrap_or_default()
+	}
+
+	/// Get the grids of all layers
+	pub fn collect(&self) -> MapGridLookup {
+		MapGridLookup(
+			self.tilemaps
+				.iter()
+				.map(|(layer, size, map_type)| {
+					let grid = MapGrid {
+						size: *size,
+						map_type: *map_type,
+					};
+					((layer.map_id, layer.layer_id), grid)
+				})
+				.collect(),
+		)
+	}
+}
@@ -0,0 +1,122 @@
+//! Tools for referring to layers by name
+
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, TilePlacementError, TilePlacementResult, TilePlacer};
+
+/// A resource mapping layer names to layer IDs for each map
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::MapLayers;
+/// let mut layers = MapLayers::default();
+/// layers
+///   .insert(0u16, "ground", 0)
+///   .insert(0u16, "props", 1)
+///   .insert(0u16, "collision", 2);
+///
+/// assert_eq!(Some(1), layers.get(0u16, "props"));
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MapLayers {
+	maps: HashMap<u16, HashMap<String, u16>>,
+}
+
+impl MapLayers {
+	/// Name a layer of the given map
+	///
+	/// This replaces any layer previously registered with the same name.
+	pub fn insert<MId: MapId>(&mut self, map_id: MId, name: &str, layer_id: u16) -> &mut Self {
+		self.maps
+			.entry(map_id.into())
+			.or_insert_with(HashMap::default)
+			.insert(name.to_string(), layer_id);
+		self
+	}
+
+	/// Remove a named layer from the given map
+	///
+	/// Returns the ID of the removed layer (if any)
+	pub fn remove<MId: MapId>(&mut self, map_id: MId, name: &str) -> Option<u16> {
+		self.maps.get_mut(&map_id.into())?.remove(name)
+	}
+
+	/// Get the ID of the named layer in the given map
+	pub fn get<MId: MapId>(&self, map_id: MId, name: &str) -> Option<u16> {
+		self.maps.get(&map_id.into())?.get(name).copied()
+	}
+
+	/// Get the name of the given layer
+	pub fn name_of<MId: MapId>(&self, map_id: MId, layer_id: u16) -> Option<&str> {
+		self.maps
+			.get(&map_id.into())?
+			.iter()
+			.find(|(_, id)| **id == layer_id)
+			.map(|(name, _)| name.as_str())
+	}
+
+	/// Iterate over the named layers of the given map
+	pub fn iter<MId: MapId>(&self, map_id: MId) -> impl Iterator<Item = (&str, u16)> + '_ {
+		self.maps
+			.get(&map_id.into())
+			.into_iter()
+			.flat_map(|layers| layers.iter().map(|(name, id)| (name.as_str(), *id)))
+	}
+}
+
+macro_rules! named_layer_methods {
+	($($(#[$meta: meta])* $name: ident => $method: ident),* $(,)?) => {
+		$(
+			$(#[$meta])*
+			pub fn $name<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+				&mut self,
+				tile_id: Id,
+				pos: Pos,
+				map_id: MId,
+				layer: &str,
+			) -> TilePlacementResult {
+				let layer_id = self.layer_id(map_id, layer)?;
+				self.$method(tile_id, pos, map_id, layer_id)
+			}
+		)*
+	};
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Get the ID of the named layer in the given map
+	///
+	/// Layer names are registered in the [`MapLayers`] resource.
+	pub fn layer_id<MId: MapId>(&self, map_id: MId, name: &str) -> Result<u16, TilePlacementError> {
+		self.map_layers
+			.get(map_id, name)
+			.ok_or_else(|| TilePlacementError::UnknownLayer(name.to_string()))
+	}
+
+	named_layer_methods! {
+		/// Same as [`place`](Self::place), but using a named layer
+		place_named => place,
+		/// Same as [`try_place`](Self::try_place), but using a named layer
+		try_place_named => try_place,
+		/// Same as [`replace`](Self::replace), but using a named layer
+		replace_named => replace,
+		/// Same as [`toggle`](Self::toggle), but using a named layer
+		toggle_named => toggle,
+		/// Same as [`toggle_matching`](Self::toggle_matching), but using a named layer
+		toggle_matching_named => toggle_matching,
+	}
+
+	/// Same as [`remove`](Self::remove), but using a named layer
+	pub fn remove_named<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer: &str,
+	) -> Result<(), TilePlacementError> {
+		let layer_id = self.layer_id(map_id, layer)?;
+		self.remove(pos, map_id, layer_id)
+	}
+}
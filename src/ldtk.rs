@@ -0,0 +1,544 @@
+//! Tools for importing [LDtk](https://ldtk.io/) projects into tilemaps managed by this crate
+
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::prelude::{App, Commands, Entity, Res, Resource, Vec2};
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::TileId;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::prelude::{PlacedTile, TilePlacementError, TilePlacer};
+
+/// Errors related to importing LDtk projects
+#[derive(Error, Debug)]
+pub enum LdtkImportError {
+	/// The project could not be parsed
+	#[error("Could not parse LDtk project: {0}")]
+	Parse(#[from] serde_json::Error),
+	/// The requested level does not exist in the project
+	///
+	/// Contains the identifier of the level in question
+	#[error("Level {0:?} does not exist")]
+	MissingLevel(String),
+	/// The requested level does not contain any layers
+	///
+	/// This can happen when a project saves its levels in separate files
+	#[error("Level {0:?} has no layer instances")]
+	MissingLayers(String),
+	/// A tile could not be placed
+	#[error("Could not place tile: {0}")]
+	Placement(#[from] TilePlacementError),
+}
+
+/// A parsed LDtk project
+///
+/// This can be loaded as a Bevy asset from an `.ldtk` file or parsed directly using
+/// [`LdtkProject::from_json`].
+#[derive(Debug, Clone, Deserialize, TypeUuid)]
+#[uuid = "c7d4a2c1-6f3e-4b1d-9a57-2f0e8b3c5d91"]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkProject {
+	/// The definitions used by this project
+	pub defs: LdtkDefinitions,
+	/// The levels contained in this project
+	pub levels: Vec<LdtkLevel>,
+}
+
+/// The definitions section of an LDtk project
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkDefinitions {
+	/// The tileset definitions
+	#[serde(default)]
+	pub tilesets: Vec<LdtkTilesetDefinition>,
+}
+
+/// An LDtk tileset definition
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkTilesetDefinition {
+	/// The unique ID of the tileset
+	pub uid: i32,
+	/// The user-defined name of the tileset
+	pub identifier: String,
+	/// The path to the tileset image (relative to the project file)
+	pub rel_path: Option<String>,
+	/// The size of a single tile (in pixels)
+	pub tile_grid_size: i32,
+}
+
+/// An LDtk level
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkLevel {
+	/// The user-defined name of the level
+	pub identifier: String,
+	/// The unique ID of the level
+	pub uid: i32,
+	/// The width of the level (in pixels)
+	pub px_wid: i32,
+	/// The height of the level (in pixels)
+	pub px_hei: i32,
+	/// The layers of this level, ordered from top-most to bottom-most
+	///
+	/// This is `None` if the project saves levels in separate files.
+	pub layer_instances: Option<Vec<LdtkLayerInstance>>,
+}
+
+/// The type of an LDtk layer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum LdtkLayerType {
+	/// A layer of integer values, optionally with auto-layer rules
+	IntGrid,
+	/// A layer of entities
+	Entities,
+	/// A layer of hand-placed tiles
+	Tiles,
+	/// A layer of tiles generated from another layer's IntGrid values
+	AutoLayer,
+}
+
+/// An instance of a layer within an LDtk level
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkLayerInstance {
+	/// The user-defined name of the layer
+	#[serde(rename = "__identifier")]
+	pub identifier: String,
+	/// The type of this layer
+	#[serde(rename = "__type")]
+	pub layer_type: LdtkLayerType,
+	/// The width of the layer (in cells)
+	#[serde(rename = "__cWid")]
+	pub c_wid: i32,
+	/// The height of the layer (in cells)
+	#[serde(rename = "__cHei")]
+	pub c_hei: i32,
+	/// The size of a single cell (in pixels)
+	#[serde(rename = "__gridSize")]
+	pub grid_size: i32,
+	/// The unique ID of the tileset used by this layer (if any)
+	#[serde(rename = "__tilesetDefUid")]
+	pub tileset_def_uid: Option<i32>,
+	/// The IntGrid values of this layer, stored row by row starting at the top-left
+	#[serde(default)]
+	pub int_grid_csv: Vec<i32>,
+	/// The tiles generated by auto-layer rules
+	#[serde(default)]
+	pub auto_layer_tiles: Vec<LdtkTileInstance>,
+	/// The tiles placed by hand
+	#[serde(default)]
+	pub grid_tiles: Vec<LdtkTileInstance>,
+	/// The entities placed in this layer
+	#[serde(default)]
+	pub entity_instances: Vec<LdtkEntityInstance>,
+}
+
+/// A tile within an LDtk layer
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub struct LdtkTileInstance {
+	/// The pixel coordinates of the tile in the layer (starting at the top-left)
+	pub px: [i32; 2],
+	/// The ID of the tile in its tileset
+	pub t: i32,
+	/// The flip bits of the tile (`1` for x, `2` for y)
+	#[serde(default)]
+	pub f: i32,
+}
+
+/// An entity within an LDtk layer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LdtkEntityInstance {
+	/// The user-defined name of the entity
+	#[serde(rename = "__identifier")]
+	pub identifier: String,
+	/// The cell coordinates of the entity (starting at the top-left)
+	#[serde(rename = "__grid")]
+	pub grid: [i32; 2],
+	/// The pixel coordinates of the entity (starting at the top-left)
+	pub px: [i32; 2],
+	/// The width of the entity (in pixels)
+	pub width: i32,
+	/// The height of the entity (in pixels)
+	pub height: i32,
+	/// The custom fields of the entity
+	#[serde(default)]
+	pub field_instances: Vec<LdtkFieldInstance>,
+}
+
+/// A custom field on an LDtk entity
+#[derive(Debug, Clone, Deserialize)]
+pub struct LdtkFieldInstance {
+	/// The user-defined name of the field
+	#[serde(rename = "__identifier")]
+	pub identifier: String,
+	/// The type of the field
+	#[serde(rename = "__type")]
+	pub field_type: String,
+	/// The value of the field
+	#[serde(rename = "__value")]
+	pub value: serde_json::Value,
+}
+
+impl LdtkProject {
+	/// Parse an LDtk project from its JSON contents
+	pub fn from_json(json: &str) -> Result<Self, LdtkImportError> {
+		Ok(serde_json::from_str(json)?)
+	}
+
+	/// Get a level by its identifier
+	pub fn get_level(&self, identifier: &str) -> Option<&LdtkLevel> {
+		self.levels
+			.iter()
+			.find(|level| level.identifier == identifier)
+	}
+}
+
+/// An LDtk entity about to be spawned by an [`LdtkEntitySpawner`]
+pub struct LdtkEntitySpawn<'a> {
+	/// The entity as authored in LDtk
+	pub instance: &'a LdtkEntityInstance,
+	/// The tile position of the entity
+	pub pos: TilePos,
+	/// The pixel position of the entity relative to the bottom-left corner of its layer
+	pub px: Vec2,
+	/// The map the level is imported into
+	pub map_id: u16,
+	/// The layer the entity layer is imported as
+	pub layer_id: u16,
+}
+
+impl<'a> LdtkEntitySpawn<'a> {
+	/// Get the value of the custom field with the given name
+	pub fn field(&self, identifier: &str) -> Option<&serde_json::Value> {
+		self.instance
+			.field_instances
+			.iter()
+			.find(|field| field.identifier == identifier)
+			.map(|field| &field.value)
+	}
+}
+
+/// A factory turning LDtk entities of a given name into Bevy entities
+///
+/// Spawners are registered with the [`LdtkEntityFactories`] resource (or via
+/// [`AddLdtkEntity::add_ldtk_entity`]). When a level is imported, an entity is spawned for each
+/// LDtk entity with a registered spawner, which then inserts its components.
+///
+/// Any `Fn(&mut EntityCommands, &LdtkEntitySpawn)` function can be used as a spawner.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::ecs::system::EntityCommands;
+/// # use bevy::prelude::{App, Component, Transform};
+/// # use bevy_tileset_map::prelude::{AddLdtkEntity, LdtkEntitySpawn};
+/// # #[derive(Component)]
+/// # struct SpawnPoint;
+/// fn spawn_point(commands: &mut EntityCommands, spawn: &LdtkEntitySpawn) {
+///   commands.insert((SpawnPoint, Transform::from_translation(spawn.px.extend(10.0))));
+/// }
+///
+/// # let mut app = App::new();
+/// app.add_ldtk_entity("SpawnPoint", spawn_point);
+/// ```
+pub trait LdtkEntitySpawner: Send + Sync + 'static {
+	/// Insert the components of the given LDtk entity
+	fn spawn(&self, commands: &mut EntityCommands, entity: &LdtkEntitySpawn);
+}
+
+impl<F: Fn(&mut EntityCommands, &LdtkEntitySpawn) + Send + Sync + 'static> LdtkEntitySpawner for F {
+	fn spawn(&self, commands: &mut EntityCommands, entity: &LdtkEntitySpawn) {
+		self(commands, entity)
+	}
+}
+
+/// A resource containing the [`LdtkEntitySpawner`] registered for each LDtk entity name
+#[derive(Resource, Default)]
+pub struct LdtkEntityFactories {
+	spawners: HashMap<String, Box<dyn LdtkEntitySpawner>>,
+}
+
+impl LdtkEntityFactories {
+	/// Register the spawner for LDtk entities with the given name
+	///
+	/// This replaces any spawner previously registered for the same name.
+	pub fn add<S: LdtkEntitySpawner>(&mut self, identifier: &str, spawner: S) -> &mut Self {
+		self.spawners
+			.insert(identifier.to_string(), Box::new(spawner));
+		self
+	}
+
+	/// Get the spawner registered for LDtk entities with the given name
+	pub fn get(&self, identifier: &str) -> Option<&dyn LdtkEntitySpawner> {
+		self.spawners.get(identifier).map(Box::as_ref)
+	}
+}
+
+/// An extension trait for registering [`LdtkEntitySpawner`]s on an [`App`]
+pub trait AddLdtkEntity {
+	/// Register a spawner with the [`LdtkEntityFactories`] resource
+	fn add_ldtk_entity<S: LdtkEntitySpawner>(&mut self, identifier: &str, spawner: S) -> &mut Self;
+}
+
+impl AddLdtkEntity for App {
+	fn add_ldtk_entity<S: LdtkEntitySpawner>(&mut self, identifier: &str, spawner: S) -> &mut Self {
+		self.init_resource::<LdtkEntityFactories>();
+		self.world
+			.resource_mut::<LdtkEntityFactories>()
+			.add(identifier, spawner);
+		self
+	}
+}
+
+/// Settings used to configure how an LDtk project is imported
+#[derive(Debug, Clone, Default)]
+pub struct LdtkImportSettings {
+	/// Maps an LDtk tile (as `(tileset uid, tile id)`) to the tile that should be placed
+	///
+	/// Tiles without a mapping are skipped.
+	pub tiles: HashMap<(i32, i32), TileId>,
+	/// Maps an IntGrid value of a layer (as `(layer identifier, value)`) to the tile that should be placed
+	///
+	/// This can be used to re-map LDtk's IntGrid values onto Auto tiles, letting this crate resolve
+	/// the tile textures rather than using the tiles generated by LDtk's auto-layer rules. Layers
+	/// with at least one mapping ignore their auto-layer tiles.
+	pub int_grid: HashMap<(String, i32), TileId>,
+	/// Maps an LDtk layer identifier to the layer ID it should be placed in
+	///
+	/// Layers without a mapping are assigned IDs in order from the bottom-most layer, starting at `0`.
+	pub layers: HashMap<String, u16>,
+}
+
+/// The results of importing an LDtk level
+#[derive(Debug, Clone, Default)]
+pub struct LdtkImportResult {
+	/// The number of tiles placed
+	pub placed: usize,
+	/// The number of tiles and entities skipped for lying outside of their layer
+	pub skipped: usize,
+	/// The entities found in the level's entity layers, along with their tile position
+	///
+	/// Only the entities with a registered [`LdtkEntitySpawner`] are spawned automatically.
+	pub entities: Vec<(TilePos, LdtkEntityInstance)>,
+	/// The Bevy entities spawned for the level's LDtk entities
+	pub spawned: Vec<Entity>,
+}
+
+/// A system parameter that can be used to import LDtk levels into a tilemap
+///
+/// The map and its layers must already exist and be large enough to hold the imported level.
+#[derive(SystemParam)]
+pub struct LdtkImporter<'w, 's> {
+	tile_placer: TilePlacer<'w, 's>,
+	commands: Commands<'w, 's>,
+	factories: Res<'w, LdtkEntityFactories>,
+}
+
+impl<'w, 's> LdtkImporter<'w, 's> {
+	/// Import the level with the given identifier into the given map
+	pub fn import_level(
+		&mut self,
+		project: &LdtkProject,
+		level: &str,
+		map_id: u16,
+		settings: &LdtkImportSettings,
+	) -> Result<LdtkImportResult, LdtkImportError> {
+		let level = project
+			.get_level(level)
+			.ok_or_else(|| LdtkImportError::MissingLevel(level.to_string()))?;
+		let layers = level
+			.layer_instances
+			.as_ref()
+			.ok_or_else(|| LdtkImportError::MissingLayers(level.identifier.clone()))?;
+
+		let mut result = LdtkImportResult::default();
+
+		// LDtk orders layers from top to bottom
+		for (index, layer) in layers.iter().rev().enumerate() {
+			let layer_id = settings
+				.layers
+				.get(&layer.identifier)
+				.copied()
+				.unwrap_or(index as u16);
+
+			match layer.layer_type {
+				LdtkLayerType::Entities => {
+					self.import_entities(layer, map_id, layer_id, &mut result);
+				},
+				LdtkLayerType::IntGrid | LdtkLayerType::AutoLayer
+					if Self::has_int_grid_mapping(&layer.identifier, settings) =>
+				{
+					self.import_int_grid(layer, map_id, layer_id, settings, &mut result)?;
+				},
+				_ => {
+					self.import_tiles(layer, map_id, layer_id, settings, &mut result)?;
+				},
+			}
+		}
+
+		Ok(result)
+	}
+
+	/// Collect the entities of the given layer, spawning those with a registered spawner
+	fn import_entities(
+		&mut self,
+		layer: &LdtkLayerInstance,
+		map_id: u16,
+		layer_id: u16,
+		result: &mut LdtkImportResult,
+	) {
+		let height = (layer.c_hei * layer.grid_size) as f32;
+		for instance in &layer.entity_instances {
+			let pos = match to_tile_pos(instance.grid, layer) {
+				Some(pos) => pos,
+				None => {
+					result.skipped += 1;
+					continue;
+				},
+			};
+			if let Some(spawner) = self.factories.get(&instance.identifier) {
+				let spawn = LdtkEntitySpawn {
+					instance,
+					pos,
+					px: Vec2::new(instance.px[0] as f32, height - instance.px[1] as f32),
+					map_id,
+					layer_id,
+				};
+				let mut commands = self.commands.spawn_empty();
+				spawner.spawn(&mut commands, &spawn);
+				result.spawned.push(commands.id());
+			}
+			result.entities.push((pos, instance.clone()));
+		}
+	}
+
+	/// Place the mapped tiles for each IntGrid value in the given layer
+	fn import_int_grid(
+		&mut self,
+		layer: &LdtkLayerInstance,
+		map_id: u16,
+		layer_id: u16,
+		settings: &LdtkImportSettings,
+		result: &mut LdtkImportResult,
+	) -> Result<(), LdtkImportError> {
+		if layer.c_wid <= 0 {
+			return Ok(());
+		}
+
+		for (index, value) in layer.int_grid_csv.iter().enumerate() {
+			if let Some(tile_id) = settings.int_grid.get(&(layer.identifier.clone(), *value)) {
+				let index = index as i32;
+				let pos = match to_tile_pos([index % layer.c_wid, index / layer.c_wid], layer) {
+					Some(pos) => pos,
+					None => {
+						result.skipped += 1;
+						continue;
+					},
+				};
+				self.tile_placer.place(*tile_id, pos, map_id, layer_id)?;
+				result.placed += 1;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Place the mapped tiles for each tile in the given layer
+	fn import_tiles(
+		&mut self,
+		layer: &LdtkLayerInstance,
+		map_id: u16,
+		layer_id: u16,
+		settings: &LdtkImportSettings,
+		result: &mut LdtkImportResult,
+	) -> Result<(), LdtkImportError> {
+		let tileset_uid = match layer.tileset_def_uid {
+			Some(uid) => uid,
+			None => return Ok(()),
+		};
+
+		let tiles = layer.grid_tiles.iter().chain(layer.auto_layer_tiles.iter());
+		for tile in tiles {
+			if let Some(tile_id) = settings.tiles.get(&(tileset_uid, tile.t)) {
+				let grid = (Vec2::new(tile.px[0] as f32, tile.px[1] as f32)
+					/ layer.grid_size as f32)
+					.floor();
+				let pos = match to_tile_pos([grid.x as i32, grid.y as i32], layer) {
+					Some(pos) => pos,
+					None => {
+						result.skipped += 1;
+						continue;
+					},
+				};
+
+				let placed = self.tile_placer.place(*tile_id, pos, map_id, layer_id)?;
+				if tile.f != 0 {
+					if let PlacedTile::Added {
+						new_tile: (entity, ..),
+						..
+					} = placed
+					{
+						self.commands.entity(entity).insert(TileFlip {
+							x: tile.f & 1 != 0,
+							y: tile.f & 2 != 0,
+							d: false,
+						});
+					}
+				}
+				result.placed += 1;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Checks if any IntGrid values have been mapped for the given layer
+	fn has_int_grid_mapping(identifier: &str, settings: &LdtkImportSettings) -> bool {
+		settings
+			.int_grid
+			.keys()
+			.any(|(layer, ..)| layer == identifier)
+	}
+}
+
+/// Convert LDtk cell coordinates (top-down) to a tile position (bottom-up)
+///
+/// Returns `None` if the coordinates lie outside of the given layer
+fn to_tile_pos(grid: [i32; 2], layer: &LdtkLayerInstance) -> Option<TilePos> {
+	let [x, y] = grid;
+	if !(0..layer.c_wid).contains(&x) || !(0..layer.c_hei).contains(&y) {
+		return None;
+	}
+
+	Some(TilePos {
+		x: x as u32,
+		y: (layer.c_hei - 1 - y) as u32,
+	})
+}
+
+/// Asset loader for LDtk projects (`.ldtk` files)
+#[derive(Default)]
+pub struct LdtkProjectLoader;
+
+impl AssetLoader for LdtkProjectLoader {
+	fn load<'a>(
+		&'a self,
+		bytes: &'a [u8],
+		load_context: &'a mut LoadContext,
+	) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+		Box::pin(async move {
+			let project = serde_json::from_slice::<LdtkProject>(bytes)?;
+			load_context.set_default_asset(LoadedAsset::new(project));
+			Ok(())
+		})
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&["ldtk"]
+	}
+}
@@ -24,7 +24,7 @@
 //!     // bevy_tileset
 //!     .add_plugin(TilesetPlugin::default())
 //!     // bevy_tileset_map
-//!     .add_plugin(TilesetMapPlugin)
+//!     .add_plugin(TilesetMapPlugin::default())
 //!     // ...
 //!     .run();
 //! }
@@ -55,26 +55,179 @@
 //! * __`variants`__ - Enables usage of Variant tiles
 //! * __`auto-tile`__ - Enables usage of Auto tiles
 //! * __`serialization`__ - Enables tilemap serialization
+//! * __`binary`__ - Enables the compact binary serialization format
+//! * __`binary-lz4`__ - Enables LZ4 compression for the binary serialization format
+//! * __`ldtk`__ - Enables importing LDtk projects
+//! * __`lighting`__ - Enables tinting tiles by light levels propagated from emitter tiles
+//! * __`tiled`__ - Enables exporting tilemaps to Tiled's `.tmx` format
+//! * __`rapier2d`__ - Enables collider generation for bevy_rapier2d
+//! * __`xpbd`__ - Enables collider generation for bevy_xpbd_2d
+//! * __`derive`__ - Enables `#[derive(MapId)]` for using fieldless enums as map IDs
+//! * __`wfc`__ - Enables filling regions using Wave Function Collapse
+//! * __`scatter`__ - Enables randomly scattering tiles across a region
 //!
 
 pub use bevy_tileset as tileset;
 
+mod adopt;
+mod animation;
+mod big;
 #[cfg(feature = "auto-tile")]
 pub(crate) mod auto;
+mod automata;
+mod bounds;
+mod builder;
+mod bulk;
+mod bundle;
+mod changes;
+#[cfg(any(feature = "rapier2d", feature = "xpbd"))]
+mod collider;
+mod color;
+mod commands;
+mod constraints;
 mod coord;
+mod csv;
+mod diagnostics;
+mod elevation;
+mod events;
+mod export;
+mod fluent;
+mod fog;
+mod generator;
+mod ghost;
+mod grid;
+mod layers;
+#[cfg(feature = "ldtk")]
+mod ldtk;
+#[cfg(feature = "lighting")]
+mod lighting;
+mod lookup;
+mod manager;
+mod mask;
+mod metadata;
+mod minimap;
+mod names;
+mod nav;
+mod occupancy;
+mod orientation;
+mod overlay;
+mod picker;
 mod placement;
 mod plugin;
+mod policy;
+mod pool;
+mod reader;
+mod ready;
+mod recipe;
+mod registry;
+mod reload;
+mod replay;
+mod reskin;
+#[cfg(feature = "scatter")]
+mod scatter;
+mod selection;
 #[cfg(feature = "serialization")]
 mod serialization;
+mod settings;
+mod shapes;
+mod stages;
+mod stamp;
+mod target;
+mod terrain;
+#[cfg(feature = "tiled")]
+mod tiled;
+mod tile_ref;
+mod transaction;
+mod transform;
+mod validation;
+mod visibility;
+#[cfg(feature = "wfc")]
+mod wfc;
 
 pub mod prelude {
 	pub use bevy_tileset::prelude::*;
+	#[cfg(feature = "derive")]
+	pub use bevy_tileset_map_derive::MapId;
 
+	pub use super::adopt::TextureIndexMapping;
+	pub use super::animation::{PausedAnimation, TileAnimator};
 	#[cfg(feature = "auto-tile")]
-	pub use super::auto::RemoveAutoTileEvent;
+	pub use super::auto::{
+		AddAutoTileRules, AutoTileConnections, AutoTileLayers, AutoTileRuleSet, AutoTileRules,
+		CardinalRuleSet, CornerRuleSet, HexOrientation, HexRuleSet, NeighborGroups,
+		RemoveAutoTileEvent, WangCornerRuleSet,
+	};
+	pub use super::automata::{
+		AddTileAutomaton, CellNeighbors, TileAutomata, TileAutomaton, TileRule,
+	};
+	pub use super::big::{AddBigTile, BigTiles};
+	pub use super::bounds::OutOfBoundsPolicy;
+	pub use super::builder::TilemapBuilder;
+	pub use super::changes::{TileChange, TileChangeLog};
+	#[cfg(any(feature = "rapier2d", feature = "xpbd"))]
+	pub use super::collider::{TileColliders, COLLISION_PROPERTY};
+	pub use super::color::TileTint;
+	pub use super::commands::TilesetCommandsExt;
+	pub use super::constraints::LayerConstraints;
 	pub use super::coord::TileCoord;
+	pub use super::csv::{CellMapping, TileCellMapping};
+	pub use super::diagnostics::TilesetMapDiagnosticsPlugin;
+	pub use super::elevation::{ElevationSettings, LayerElevation};
+	pub use super::events::{
+		PlaceTileMode, PlaceTileRequest, PlaceTileResult, RemoveTileRequest, RemoveTileResult,
+	};
+	pub use super::export::MapRenderer;
+	pub use super::fluent::TilePlacement;
+	pub use super::fog::{FogMode, FogOfWar};
+	pub use super::generator::TileGenerator;
+	pub use super::ghost::TileGhost;
+	pub use super::layers::MapLayers;
+	#[cfg(feature = "ldtk")]
+	pub use super::ldtk::*;
+	#[cfg(feature = "lighting")]
+	pub use super::lighting::{LightGrid, LightSettings, LIGHT_PROPERTY, OPAQUE_PROPERTY};
+	pub use super::lookup::TileIdCache;
+	pub use super::manager::{LayerOpacity, MapAnchor, TilemapLayer, TilemapManager};
+	pub use super::mask::{PlacementMask, PlacementMasks};
+	pub use super::metadata::{
+		AddTilePropertyComponent, TileMetadata, TileProperties, TileProperty,
+		TilePropertyComponents, TilePropertyMapping,
+	};
+	pub use super::minimap::{Minimap, MINIMAP_COLOR_PROPERTY};
+	pub use super::names::TileNames;
+	pub use super::nav::{NavCell, NavGrid, COST_PROPERTY, WALKABLE_PROPERTY};
+	pub use super::occupancy::TileOccupancy;
+	pub use super::orientation::{TileOrientations, TileOrienter};
+	pub use super::overlay::GridOverlay;
+	pub use super::picker::{TileHit, TilePicker};
 	pub use super::placement::*;
 	pub use super::plugin::{TilesetMapLabel, TilesetMapPlugin, TilesetMapStage};
+	pub use super::policy::{PlacementPolicy, PolicyAction};
+	pub use super::pool::{PoolStats, TilePool};
+	pub use super::reader::TileReader;
+	pub use super::ready::{tilesets_ready, AddRequiredTileset, RequiredTilesets};
+	pub use super::recipe::{AddTileRecipe, RecipeLayer, TileRecipe, TileRecipes};
+	pub use super::registry::{MapHandle, TilemapRegistry, TilemapRegistryError};
+	pub use super::replay::TileReplay;
+	pub use super::reskin::TilesetSwapper;
+	#[cfg(feature = "scatter")]
+	pub use super::scatter::ScatterOptions;
+	pub use super::selection::TileSelection;
 	#[cfg(feature = "serialization")]
 	pub use super::serialization::*;
+	pub use super::settings::{LayerSettings, MapSettings};
+	pub use super::stages::{AddTileStage, TileStage, TileStages};
+	pub use super::stamp::TileStamp;
+	pub use super::target::DefaultMapTarget;
+	pub use super::terrain::TerrainTiers;
+	pub use super::tile_ref::PlacedTileRef;
+	#[cfg(feature = "tiled")]
+	pub use super::tiled::*;
+	pub use super::transaction::TileTransaction;
+	pub use super::transform::{TileRotation, TileTransform};
+	pub use super::validation::{
+		AddPlacementValidator, PlacementContext, PlacementValidator, PlacementValidators,
+	};
+	#[cfg(feature = "wfc")]
+	pub use super::wfc::{WfcConstraints, WfcDirection};
 }
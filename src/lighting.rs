@@ -0,0 +1,368 @@
+//! Tools for lighting placed tiles
+
+use std::collections::BinaryHeap;
+
+use bevy::math::UVec2;
+use bevy::prelude::{
+	Changed, Color, Commands, Entity, Local, Or, Query, RemovedComponents, Res, ResMut, Resource,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TilesetParent, Tilesets};
+
+use crate::color::LightTint;
+use crate::metadata::TileMetadata;
+use crate::placement::MapId;
+
+/// The [`TileMetadata`] property defining the light level emitted by a tile
+///
+/// Tiles without this property don't emit any light.
+pub const LIGHT_PROPERTY: &str = "light";
+
+/// The [`TileMetadata`] property marking whether a tile blocks light
+///
+/// Opaque tiles are still lit themselves, but light doesn't spread past them. Tiles are
+/// transparent unless this property is set to `true`.
+pub const OPAQUE_PROPERTY: &str = "opaque";
+
+/// A resource configuring how light spreads across each map
+///
+/// Changing this resource relights every map from scratch.
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct LightSettings {
+	/// The light level of every position, regardless of nearby emitters
+	pub ambient: u8,
+	/// The light level at which tiles are drawn untinted
+	///
+	/// Emitted levels above this are still propagated, but are clamped when tinting tiles.
+	pub max_level: u8,
+	/// How many light levels are lost with each tile the light spreads to
+	pub falloff: u8,
+	/// The tint of tiles with a light level of zero
+	///
+	/// Tiles are tinted by blending from this color (at level zero) to white (at
+	/// [`max_level`](Self::max_level)).
+	pub darkness: Color,
+}
+
+impl LightSettings {
+	/// The tint of a tile with the given light level
+	pub fn tint(&self, level: u8) -> Color {
+		let amount = level.min(self.max_level) as f32 / self.max_level.max(1) as f32;
+		let [r, g, b, a] = self.darkness.as_rgba_f32();
+		Color::rgba(
+			r + (1.0 - r) * amount,
+			g + (1.0 - g) * amount,
+			b + (1.0 - b) * amount,
+			a + (1.0 - a) * amount,
+		)
+	}
+
+	/// The furthest distance (in tiles) a light with the given level can reach
+	fn reach(&self, level: u8) -> u32 {
+		(level / self.falloff.max(1)) as u32
+	}
+}
+
+impl Default for LightSettings {
+	fn default() -> Self {
+		Self {
+			ambient: 0,
+			max_level: 15,
+			falloff: 1,
+			darkness: Color::BLACK,
+		}
+	}
+}
+
+/// The lighting data of a single position
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+struct LightCell {
+	/// The highest level emitted by the tiles at this position
+	emission: u8,
+	/// Whether any tile at this position blocks light
+	opaque: bool,
+}
+
+/// The lighting data of a single map
+#[derive(Debug, Clone, Default)]
+struct LitMap {
+	cells: HashMap<TilePos, LightCell>,
+	levels: HashMap<TilePos, u8>,
+}
+
+/// An inclusive rectangle of tile positions
+#[derive(Debug, Copy, Clone)]
+struct LightRegion {
+	min: UVec2,
+	max: UVec2,
+}
+
+impl LightRegion {
+	fn contains(&self, pos: &TilePos) -> bool {
+		(self.min.x..=self.max.x).contains(&pos.x) && (self.min.y..=self.max.y).contains(&pos.y)
+	}
+
+	fn expand(&self, amount: u32) -> Self {
+		Self {
+			min: self.min.saturating_sub(UVec2::splat(amount)),
+			max: self.max.saturating_add(UVec2::splat(amount)),
+		}
+	}
+}
+
+/// A resource containing the light level of every lit tile position, per map
+///
+/// Light spreads outwards from each tile with a [`LIGHT_PROPERTY`], losing
+/// [`LightSettings::falloff`] levels with each step, and is blocked by tiles with an
+/// [`OPAQUE_PROPERTY`]. When multiple layers have a tile at the same position, the brightest
+/// emitter is used and any opaque tile blocks the light.
+///
+/// This is kept in sync as tiles are placed and removed, only relighting the positions within
+/// reach of the changed tiles. Every tile is tinted according to the light level of its
+/// position (see [`LightSettings::tint`]), which is combined with its
+/// [`TileTint`](crate::prelude::TileTint) into its [`TileColor`].
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LightGrid {
+	maps: HashMap<u16, LitMap>,
+	ambient: u8,
+}
+
+impl LightGrid {
+	/// Get the light level at the given position
+	///
+	/// This is never lower than the [ambient level](LightSettings::ambient).
+	pub fn level<Pos: Into<TilePos>, MId: MapId>(&self, pos: Pos, map_id: MId) -> u8 {
+		self.maps
+			.get(&map_id.into())
+			.and_then(|map| map.levels.get(&pos.into()))
+			.copied()
+			.unwrap_or_default()
+			.max(self.ambient)
+	}
+
+	/// Returns true if the given position is lit above the ambient level
+	pub fn is_lit<Pos: Into<TilePos>, MId: MapId>(&self, pos: Pos, map_id: MId) -> bool {
+		self.level(pos, map_id) > self.ambient
+	}
+}
+
+impl LitMap {
+	/// The highest light level emitted by any position of this map
+	fn brightest(&self) -> u8 {
+		self.cells
+			.values()
+			.map(|cell| cell.emission)
+			.max()
+			.unwrap_or_default()
+	}
+
+	/// Recompute the light levels within the given region (or the entire map if `None`)
+	fn relight(&mut self, region: Option<LightRegion>, settings: &LightSettings) {
+		match region {
+			Some(region) => self.levels.retain(|pos, _| !region.contains(pos)),
+			None => self.levels.clear(),
+		}
+
+		// Only emitters within reach of the region can light it
+		let reach = settings.reach(self.brightest());
+		let sources = region.map(|region| region.expand(reach));
+		let mut open = BinaryHeap::new();
+		for (pos, cell) in self.cells.iter() {
+			let in_reach = sources.map(|sources| sources.contains(pos)).unwrap_or(true);
+			if cell.emission > 0 && in_reach {
+				open.push((cell.emission, pos.x, pos.y, true));
+			}
+		}
+
+		// Spread the brightest light first, so each position is only visited once
+		let mut visited: HashMap<TilePos, u8> = HashMap::default();
+		while let Some((level, x, y, is_source)) = open.pop() {
+			let pos = TilePos { x, y };
+			if visited
+				.get(&pos)
+				.map(|prev| *prev >= level)
+				.unwrap_or(false)
+			{
+				continue;
+			}
+			visited.insert(pos, level);
+
+			if region.map(|region| region.contains(&pos)).unwrap_or(true) {
+				let current = self.levels.entry(pos).or_default();
+				*current = (*current).max(level);
+			}
+
+			let opaque = self
+				.cells
+				.get(&pos)
+				.map(|cell| cell.opaque)
+				.unwrap_or(false);
+			let next = level.saturating_sub(settings.falloff.max(1));
+			if next == 0 || (opaque && !is_source) {
+				continue;
+			}
+
+			for neighbor in neighbors(&pos) {
+				if visited
+					.get(&neighbor)
+					.map(|prev| *prev < next)
+					.unwrap_or(true)
+				{
+					open.push((next, neighbor.x, neighbor.y, false));
+				}
+			}
+		}
+	}
+}
+
+/// Get the cardinal neighbors of the given position
+fn neighbors(pos: &TilePos) -> impl Iterator<Item = TilePos> {
+	let pos = *pos;
+	[(0i64, 1i64), (1, 0), (0, -1), (-1, 0)]
+		.into_iter()
+		.filter_map(move |(x, y)| {
+			let x = u32::try_from(pos.x as i64 + x).ok()?;
+			let y = u32::try_from(pos.y as i64 + y).ok()?;
+			Some(TilePos { x, y })
+		})
+}
+
+/// __\[SYSTEM\]__ Relights the tiles around placed and removed tiles
+///
+/// Every map is relit in full whenever the [`LightSettings`] change.
+pub(crate) fn update_lighting(
+	changed_tiles: Query<
+		(Entity, &TilePos, &TileParent),
+		Or<(Changed<TilePos>, Changed<TileTextureIndex>)>,
+	>,
+	mut removed_tiles: RemovedComponents<TilePos>,
+	mut tiles: Query<(
+		Entity,
+		&TilePos,
+		&TileParent,
+		&TileTextureIndex,
+		&TilesetParent,
+		Option<&mut LightTint>,
+	)>,
+	mut commands: Commands,
+	tilesets: Tilesets,
+	metadata: Res<TileMetadata>,
+	settings: Res<LightSettings>,
+	mut grid: ResMut<LightGrid>,
+	mut tracked: Local<HashMap<Entity, (u16, TilePos)>>,
+) {
+	let full = settings.is_changed();
+	let mut dirty = HashSet::default();
+	for (entity, pos, parent) in changed_tiles.iter() {
+		if let Some(previous) = tracked.insert(entity, (parent.map_id, *pos)) {
+			dirty.insert(previous);
+		}
+		dirty.insert((parent.map_id, *pos));
+	}
+	for entity in removed_tiles.iter() {
+		if let Some(previous) = tracked.remove(&entity) {
+			dirty.insert(previous);
+		}
+	}
+
+	if dirty.is_empty() && !full {
+		return;
+	}
+
+	// === Recompute Dirty Cells === //
+	let mut cells: HashMap<(u16, TilePos), LightCell> = HashMap::default();
+	for (_, pos, parent, index, tileset, _) in tiles.iter() {
+		let key = (parent.map_id, *pos);
+		if !full && !dirty.contains(&key) {
+			continue;
+		}
+
+		let properties = metadata.resolve(&tilesets, index, tileset);
+		let emission = properties
+			.and_then(|properties| properties.get(LIGHT_PROPERTY))
+			.and_then(|property| property.as_int())
+			.unwrap_or_default()
+			.clamp(0, u8::MAX as i64) as u8;
+		let opaque = properties
+			.and_then(|properties| properties.get(OPAQUE_PROPERTY))
+			.and_then(|property| property.as_bool())
+			.unwrap_or(false);
+
+		let cell = cells.entry(key).or_default();
+		cell.emission = cell.emission.max(emission);
+		cell.opaque |= opaque;
+	}
+
+	let mut regions: HashMap<u16, Option<LightRegion>> = HashMap::default();
+	if full {
+		grid.maps.clear();
+		grid.ambient = settings.ambient;
+		for ((map_id, pos), cell) in cells {
+			let map = grid.maps.entry(map_id).or_default();
+			map.cells.insert(pos, cell);
+			regions.insert(map_id, None);
+		}
+	} else {
+		// Light from a removed emitter may have reached further than any remaining one
+		let mut removed: HashMap<u16, u8> = HashMap::default();
+		for (map_id, pos) in dirty.iter() {
+			let map = grid.maps.entry(*map_id).or_default();
+			let previous = match cells.remove(&(*map_id, *pos)) {
+				Some(cell) => map.cells.insert(*pos, cell),
+				None => map.cells.remove(pos),
+			};
+			let emission = removed.entry(*map_id).or_default();
+			*emission = (*emission).max(previous.map(|cell| cell.emission).unwrap_or_default());
+		}
+
+		let reaches: HashMap<u16, u32> = removed
+			.into_iter()
+			.map(|(map_id, emission)| {
+				let brightest = grid.maps[&map_id].brightest().max(emission);
+				(map_id, settings.reach(brightest))
+			})
+			.collect();
+
+		for (map_id, pos) in dirty {
+			let dirty_region = LightRegion {
+				min: UVec2::new(pos.x, pos.y),
+				max: UVec2::new(pos.x, pos.y),
+			}
+			.expand(reaches[&map_id]);
+
+			let region = regions.entry(map_id).or_insert(Some(dirty_region));
+			if let Some(region) = region {
+				region.min = region.min.min(dirty_region.min);
+				region.max = region.max.max(dirty_region.max);
+			}
+		}
+	}
+
+	for (map_id, region) in regions.iter() {
+		if let Some(map) = grid.maps.get_mut(map_id) {
+			map.relight(*region, &settings);
+		}
+	}
+
+	// === Tint Relit Tiles === //
+	for (entity, pos, parent, .., light) in tiles.iter_mut() {
+		let relit = match regions.get(&parent.map_id) {
+			Some(Some(region)) => region.contains(pos),
+			Some(None) => true,
+			None => false,
+		};
+		if !relit {
+			continue;
+		}
+
+		let tint = settings.tint(grid.level(*pos, parent.map_id));
+		match light {
+			Some(mut light) if light.0 != tint => light.0 = tint,
+			Some(_) => {},
+			None => {
+				commands.entity(entity).insert(LightTint(tint));
+			},
+		}
+	}
+}
@@ -0,0 +1,121 @@
+//! Tools for quickly looking up placed tiles
+
+use bevy::prelude::{
+	AssetEvent, Assets, Entity, EventReader, Mut, Res, ResMut, Resource, TextureAtlas,
+};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::{TilePos, TileStorage, TileTextureIndex};
+use bevy_tileset::prelude::{TileId, Tileset, TilesetId, TilesetParent, Tilesets};
+
+use crate::placement::{MapId, TilePlacer};
+
+/// A resource caching the [`TileId`] of every texture index of each loaded tileset
+///
+/// Finding the ID of a placed tile otherwise requires looking up its tileset asset and then the
+/// tile within it, which adds up in systems that place or inspect many tiles per frame. The cache
+/// is rebuilt whenever a tileset is added, modified, or removed.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileIdCache {
+	tilesets: HashMap<TilesetId, Vec<Option<TileId>>>,
+}
+
+impl TileIdCache {
+	/// Get the cached ID of the tile with the given texture index in the given tileset
+	pub fn get(&self, tileset_id: &TilesetId, index: usize) -> Option<TileId> {
+		*self.tilesets.get(tileset_id)?.get(index)?
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Get the [`TileStorage`] of the given layer
+	pub(crate) fn tile_storage<MId: MapId>(
+		&self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<&TileStorage> {
+		let map_id = map_id.into();
+		let (tilemap, _) = self
+			.tilemaps
+			.iter()
+			.find(|(_, layer)| layer.map_id == map_id && layer.layer_id == layer_id)?;
+		self.map_query.get(tilemap).ok()
+	}
+
+	/// Get the [`TileStorage`] of the given layer for modification
+	pub(crate) fn tile_storage_mut<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<Mut<TileStorage>> {
+		let map_id = map_id.into();
+		let (tilemap, _) = self
+			.tilemaps
+			.iter()
+			.find(|(_, layer)| layer.map_id == map_id && layer.layer_id == layer_id)?;
+		self.map_query.get_mut(tilemap).ok()
+	}
+
+	/// Find the tile entity at the given position
+	///
+	/// This reads the layer's [`TileStorage`] directly rather than searching through the tiles.
+	pub(crate) fn find_entity<MId: MapId>(
+		&self,
+		pos: TilePos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<Entity> {
+		self.tile_storage(map_id, layer_id)?.checked_get(&pos)
+	}
+
+	/// Get the ID of the given placed tile
+	///
+	/// Returns `None` if the entity isn't a tile or its tileset isn't loaded.
+	pub(crate) fn tile_id_of(&self, entity: Entity) -> Option<TileId> {
+		let (.., index, tileset) = self.region_query.get(entity).ok()?;
+		self.resolve_tile_id(index, tileset)
+	}
+
+	/// Get the ID of the tile with the given texture index in the given tileset
+	///
+	/// Tilesets missing from the [`TileIdCache`] (such as ones loaded this frame) are looked up
+	/// directly.
+	pub(crate) fn resolve_tile_id(
+		&self,
+		index: &TileTextureIndex,
+		tileset: &TilesetParent,
+	) -> Option<TileId> {
+		let index = index.0 as usize;
+		if let Some(id) = self.tile_ids.get(&tileset.0, index) {
+			return Some(id);
+		}
+
+		self.tilesets
+			.get_by_id(&tileset.0)?
+			.get_tile_id(&index)
+			.copied()
+	}
+}
+
+/// __\[SYSTEM\]__ Rebuilds the [`TileIdCache`] whenever a tileset changes
+pub(crate) fn rebuild_tile_id_cache(
+	mut events: EventReader<AssetEvent<Tileset>>,
+	tilesets: Tilesets,
+	atlases: Res<Assets<TextureAtlas>>,
+	mut cache: ResMut<TileIdCache>,
+) {
+	if events.iter().count() == 0 {
+		return;
+	}
+
+	cache.tilesets.clear();
+	for (_, tileset) in tilesets.iter() {
+		let count = match atlases.get(tileset.atlas()) {
+			Some(atlas) => atlas.len(),
+			None => continue,
+		};
+		let ids = (0..count)
+			.map(|index| tileset.get_tile_id(&index).copied())
+			.collect();
+		cache.tilesets.insert(*tileset.id(), ids);
+	}
+}
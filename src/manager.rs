@@ -0,0 +1,378 @@
+//! Tools for managing the layers of a map at runtime
+
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::prelude::{
+	Commands, Component, DespawnRecursiveExt, Entity, Query, ResMut, Transform, Visibility,
+};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::Tilesets;
+
+use crate::layers::MapLayers;
+use crate::placement::MapId;
+
+/// A component identifying the map and layer a tilemap entity represents
+///
+/// Each layer of a map is its own tilemap entity. This component should be added when spawning
+/// the tilemap so the [`TilemapManager`] can find it.
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TilemapLayer {
+	pub map_id: u16,
+	pub layer_id: u16,
+}
+
+/// A component setting the opacity of every tile in a layer
+///
+/// This should be added to a tilemap entity (usually with [`TilemapManager::set_layer_opacity`]).
+/// It scales the alpha of each tile's [`TileColor`], including the tiles placed after it was
+/// added, on top of their [`TileTint`](crate::prelude::TileTint).
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub struct LayerOpacity(pub f32);
+
+impl Default for LayerOpacity {
+	fn default() -> Self {
+		Self(1.0)
+	}
+}
+
+/// The part of a map kept in place when it's resized
+///
+/// For example, resizing a map anchored to the [`TopRight`](Self::TopRight) adds or removes
+/// rows and columns along its bottom and left edges.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MapAnchor {
+	BottomLeft,
+	Bottom,
+	BottomRight,
+	Left,
+	Center,
+	Right,
+	TopLeft,
+	Top,
+	TopRight,
+}
+
+impl MapAnchor {
+	/// Get the amount tiles are moved by when resizing a map from `old_size` to `new_size`
+	fn offset(self, old_size: TilemapSize, new_size: TilemapSize) -> (i64, i64) {
+		// The position of the anchor along each axis, in halves of the map
+		let (x, y) = match self {
+			Self::BottomLeft => (0, 0),
+			Self::Bottom => (1, 0),
+			Self::BottomRight => (2, 0),
+			Self::Left => (0, 1),
+			Self::Center => (1, 1),
+			Self::Right => (2, 1),
+			Self::TopLeft => (0, 2),
+			Self::Top => (1, 2),
+			Self::TopRight => (2, 2),
+		};
+		let offset = |old: u32, new: u32, halves: i64| (new as i64 - old as i64) * halves / 2;
+		(
+			offset(old_size.x, new_size.x, x),
+			offset(old_size.y, new_size.y, y),
+		)
+	}
+}
+
+/// A system param used to create, remove, and reorder the layers of a map at runtime
+///
+/// New layers copy the configuration of an existing layer in the same map (including its
+/// texture), so they can use the same tileset without any additional setup.
+#[derive(SystemParam)]
+pub struct TilemapManager<'w, 's> {
+	pub(crate) commands: Commands<'w, 's>,
+	tilemaps: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static TilemapLayer,
+			&'static TilemapSize,
+			&'static TilemapGridSize,
+			&'static TilemapTileSize,
+			&'static TilemapSpacing,
+			&'static TilemapType,
+			&'static TilemapTexture,
+			&'static Transform,
+			Option<&'static TileStorage>,
+		),
+	>,
+	map_layers: ResMut<'w, MapLayers>,
+	pub(crate) tilesets: Tilesets<'w, 's>,
+	pub(crate) storages: Query<'w, 's, (&'static TileStorage, Option<&'static TilemapTexture>)>,
+	pub(crate) tiles: Query<'w, 's, (&'static TilePos, &'static TileTextureIndex)>,
+}
+
+impl<'w, 's> TilemapManager<'w, 's> {
+	/// Get the tilemap entity of the given layer
+	pub fn get_layer<MId: MapId>(&self, map_id: MId, layer_id: u16) -> Option<Entity> {
+		let map_id = map_id.into();
+		self.tilemaps
+			.iter()
+			.find(|(_, layer, ..)| layer.map_id == map_id && layer.layer_id == layer_id)
+			.map(|(entity, ..)| entity)
+	}
+
+	/// Get the IDs and tilemap entities of each layer in the given map, ordered by ID
+	pub fn layers<MId: MapId>(&self, map_id: MId) -> Vec<(u16, Entity)> {
+		let map_id = map_id.into();
+		let mut layers = self
+			.tilemaps
+			.iter()
+			.filter(|(_, layer, ..)| layer.map_id == map_id)
+			.map(|(entity, layer, ..)| (layer.layer_id, entity))
+			.collect::<Vec<_>>();
+		layers.sort_unstable_by_key(|(layer_id, _)| *layer_id);
+		layers
+	}
+
+	/// Create a new, empty layer in the given map
+	///
+	/// The layer copies the configuration of the lowest existing layer in the map and is offset
+	/// from it by one unit on the z-axis per layer. If `name` is given, the layer is also
+	/// registered in the [`MapLayers`] resource.
+	///
+	/// Returns `None` if the map has no layers to copy from or the layer already exists.
+	pub fn create_layer<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		name: Option<&str>,
+	) -> Option<Entity> {
+		if self.get_layer(map_id, layer_id).is_some() {
+			return None;
+		}
+
+		let (_, base_entity) = *self.layers(map_id).first()?;
+		let (_, base, size, grid_size, tile_size, spacing, map_type, texture, transform, _) =
+			self.tilemaps.get(base_entity).ok()?;
+
+		let mut transform = *transform;
+		transform.translation.z += layer_id as f32 - base.layer_id as f32;
+
+		let entity = self
+			.commands
+			.spawn(TilemapBundle {
+				grid_size: *grid_size,
+				map_type: *map_type,
+				size: *size,
+				spacing: *spacing,
+				storage: TileStorage::empty(*size),
+				texture: texture.clone(),
+				tile_size: *tile_size,
+				transform,
+				..Default::default()
+			})
+			.insert(TilemapLayer {
+				map_id: map_id.into(),
+				layer_id,
+			})
+			.id();
+
+		if let Some(name) = name {
+			self.map_layers.insert(map_id, name, layer_id);
+		}
+
+		Some(entity)
+	}
+
+	/// Remove a layer (and all of its tiles) from the given map
+	///
+	/// Returns true if the layer existed
+	pub fn remove_layer<MId: MapId>(&mut self, map_id: MId, layer_id: u16) -> bool {
+		let entity = match self.get_layer(map_id, layer_id) {
+			Some(entity) => entity,
+			None => return false,
+		};
+
+		if let Ok((.., Some(storage))) = self.tilemaps.get(entity) {
+			for tile in storage.iter().flatten() {
+				self.commands.entity(*tile).despawn_recursive();
+			}
+		}
+		self.commands.entity(entity).despawn_recursive();
+
+		if let Some(name) = self.map_layers.name_of(map_id, layer_id).map(String::from) {
+			self.map_layers.remove(map_id, &name);
+		}
+
+		true
+	}
+
+	/// Swap the order of two layers in the given map
+	///
+	/// This swaps their layer IDs, z-positions, and layer names. The tiles of each layer are
+	/// updated to reflect their new layer ID.
+	///
+	/// Returns false if either layer does not exist
+	pub fn swap_layers<MId: MapId>(&mut self, map_id: MId, layer_a: u16, layer_b: u16) -> bool {
+		let (entity_a, entity_b) = match (
+			self.get_layer(map_id, layer_a),
+			self.get_layer(map_id, layer_b),
+		) {
+			(Some(a), Some(b)) => (a, b),
+			_ => return false,
+		};
+
+		for (entity, other, layer_id) in
+			[(entity_a, entity_b, layer_b), (entity_b, entity_a, layer_a)]
+		{
+			let z = self
+				.tilemaps
+				.get(other)
+				.map(|(.., transform, _)| transform.translation.z)
+				.unwrap_or_default();
+
+			if let Ok((.., transform, storage)) = self.tilemaps.get(entity) {
+				let mut transform = *transform;
+				transform.translation.z = z;
+				self.commands.entity(entity).insert((
+					transform,
+					TilemapLayer {
+						map_id: map_id.into(),
+						layer_id,
+					},
+				));
+
+				for tile in storage
+					.into_iter()
+					.flat_map(|storage| storage.iter().flatten())
+				{
+					self.commands.entity(*tile).insert(TileParent {
+						map_id: map_id.into(),
+						layer_id,
+					});
+				}
+			}
+		}
+
+		let name_a = self.map_layers.name_of(map_id, layer_a).map(String::from);
+		let name_b = self.map_layers.name_of(map_id, layer_b).map(String::from);
+		if let Some(name) = name_a {
+			self.map_layers.insert(map_id, &name, layer_b);
+		}
+		if let Some(name) = name_b {
+			self.map_layers.insert(map_id, &name, layer_a);
+		}
+
+		true
+	}
+
+	/// Grow or crop every layer of the given map to the given size
+	///
+	/// Existing tiles are moved so that the part of the map given by `anchor` stays in place, and
+	/// any tile that no longer fits within the map is despawned. The transform of each layer is
+	/// left untouched, so moved tiles are also moved in the world.
+	///
+	/// Returns false if the map has no layers
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilemapSize;
+	/// # use bevy_tileset_map::prelude::{MapAnchor, TilemapManager};
+	/// fn add_border(mut manager: TilemapManager) {
+	///   manager.resize(0u16, TilemapSize { x: 34, y: 34 }, MapAnchor::Center);
+	/// }
+	/// ```
+	pub fn resize<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		new_size: TilemapSize,
+		anchor: MapAnchor,
+	) -> bool {
+		let layers = self.layers(map_id);
+
+		for (_, entity) in &layers {
+			let (_, _, size, .., storage) = match self.tilemaps.get(*entity) {
+				Ok(tilemap) => tilemap,
+				Err(_) => continue,
+			};
+
+			let (dx, dy) = anchor.offset(*size, new_size);
+			let mut resized = TileStorage::empty(new_size);
+			for (y, x) in (0..size.y).flat_map(|y| (0..size.x).map(move |x| (y, x))) {
+				let tile = match storage.and_then(|storage| storage.get(&TilePos { x, y })) {
+					Some(tile) => tile,
+					None => continue,
+				};
+
+				let (x, y) = (x as i64 + dx, y as i64 + dy);
+				if x < 0 || y < 0 || x >= new_size.x as i64 || y >= new_size.y as i64 {
+					self.commands.entity(tile).despawn_recursive();
+					continue;
+				}
+
+				let pos = TilePos {
+					x: x as u32,
+					y: y as u32,
+				};
+				resized.set(&pos, tile);
+				if dx != 0 || dy != 0 {
+					self.commands.entity(tile).insert(pos);
+				}
+			}
+
+			self.commands.entity(*entity).insert((new_size, resized));
+		}
+
+		!layers.is_empty()
+	}
+
+	/// Show or hide the given layer
+	///
+	/// Returns false if the layer does not exist
+	pub fn set_layer_visible<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		visible: bool,
+	) -> bool {
+		let entity = match self.get_layer(map_id, layer_id) {
+			Some(entity) => entity,
+			None => return false,
+		};
+
+		self.commands.entity(entity).insert(if visible {
+			Visibility::Inherited
+		} else {
+			Visibility::Hidden
+		});
+		true
+	}
+
+	/// Set the opacity of the given layer, from `0.0` (transparent) to `1.0` (opaque)
+	///
+	/// The opacity is applied to the tiles of the layer by inserting a [`LayerOpacity`] on its
+	/// tilemap entity.
+	///
+	/// Returns false if the layer does not exist
+	pub fn set_layer_opacity<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		opacity: f32,
+	) -> bool {
+		let entity = match self.get_layer(map_id, layer_id) {
+			Some(entity) => entity,
+			None => return false,
+		};
+
+		self.commands
+			.entity(entity)
+			.insert(LayerOpacity(opacity.clamp(0.0, 1.0)));
+		true
+	}
+
+	/// Get the commands for the tilemap entity of the given layer
+	///
+	/// This can be used to configure the layer by inserting or replacing its tilemap components.
+	pub fn configure_layer<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<EntityCommands<'w, 's, '_>> {
+		let entity = self.get_layer(map_id, layer_id)?;
+		Some(self.commands.entity(entity))
+	}
+}
@@ -0,0 +1,204 @@
+//! Tools for restricting placement to a region of each layer
+
+use bevy::math::UVec2;
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+
+use crate::occupancy::TileOccupancy;
+use crate::placement::{MapId, TilePlacementError, TilePlacer};
+use crate::policy::PolicyAction;
+
+/// The region of a layer in which tiles may be placed and removed
+///
+/// A mask allows every position set in its bitset, along with every position accepted by its
+/// filter (if any). Attach masks to layers using the [`PlacementMasks`] resource.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::math::UVec2;
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{PlacementMask, PolicyAction};
+/// // The player's claimed plot
+/// let mut plot = PlacementMask::default().with_action(PolicyAction::Skip);
+/// plot.allow_rect(TilePos { x: 10, y: 10 }, UVec2::new(8, 8));
+///
+/// assert!(plot.contains(TilePos { x: 12, y: 15 }));
+/// assert!(!plot.contains(TilePos { x: 2, y: 2 }));
+///
+/// // Only allow building on even columns
+/// let columns = PlacementMask::from_fn(|pos| pos.x % 2 == 0);
+/// assert!(columns.contains(TilePos { x: 4, y: 7 }));
+/// ```
+pub struct PlacementMask {
+	/// The allowed positions, split into blocks of 8x8 cells
+	cells: HashMap<(u32, u32), u64>,
+	filter: Option<Box<dyn Fn(TilePos) -> bool + Send + Sync>>,
+	/// How placements outside the mask are handled
+	pub action: PolicyAction,
+}
+
+impl Default for PlacementMask {
+	/// Create a mask that doesn't allow any position
+	fn default() -> Self {
+		Self {
+			cells: HashMap::default(),
+			filter: None,
+			action: PolicyAction::Error,
+		}
+	}
+}
+
+impl PlacementMask {
+	/// Create a mask allowing every position accepted by the given filter
+	pub fn from_fn<F: Fn(TilePos) -> bool + Send + Sync + 'static>(filter: F) -> Self {
+		Self {
+			filter: Some(Box::new(filter)),
+			..Default::default()
+		}
+	}
+
+	/// Handle placements outside the mask using the given action
+	///
+	/// By default, these fail with a [`TilePlacementError::Masked`] error.
+	pub fn with_action(mut self, action: PolicyAction) -> Self {
+		self.action = action;
+		self
+	}
+
+	/// Allow the given position
+	pub fn allow<Pos: Into<TilePos>>(&mut self, pos: Pos) -> &mut Self {
+		let (block, bit) = TileOccupancy::locate(pos.into());
+		*self.cells.entry(block).or_default() |= bit;
+		self
+	}
+
+	/// Allow every position of a region
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles.
+	pub fn allow_rect<Pos: Into<TilePos>>(&mut self, origin: Pos, size: UVec2) -> &mut Self {
+		let origin = origin.into();
+		for y in origin.y..origin.y + size.y {
+			for x in origin.x..origin.x + size.x {
+				self.allow(TilePos { x, y });
+			}
+		}
+		self
+	}
+
+	/// Disallow the given position
+	///
+	/// Positions accepted by the mask's filter are still allowed.
+	pub fn deny<Pos: Into<TilePos>>(&mut self, pos: Pos) -> &mut Self {
+		let (block, bit) = TileOccupancy::locate(pos.into());
+		if let Some(bits) = self.cells.get_mut(&block) {
+			*bits &= !bit;
+			if *bits == 0 {
+				self.cells.remove(&block);
+			}
+		}
+		self
+	}
+
+	/// Returns true if the given position lies within this mask
+	pub fn contains<Pos: Into<TilePos>>(&self, pos: Pos) -> bool {
+		let pos = pos.into();
+		let (block, bit) = TileOccupancy::locate(pos);
+		let is_set = self.cells.get(&block).map_or(false, |bits| bits & bit != 0);
+		is_set || self.filter.as_ref().map_or(false, |filter| filter(pos))
+	}
+}
+
+/// A resource containing the [`PlacementMask`] of each masked layer
+///
+/// Every [`TilePlacer`] operation on a masked layer is refused (or skipped, depending on the
+/// mask's [`action`](PlacementMask::action)) outside the mask, including removals. Layers without
+/// a mask are unrestricted.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::math::UVec2;
+/// # use bevy::prelude::ResMut;
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{PlacementMask, PlacementMasks};
+/// fn claim_plot(mut masks: ResMut<PlacementMasks>) {
+///   let mut plot = PlacementMask::default();
+///   plot.allow_rect(TilePos { x: 10, y: 10 }, UVec2::new(8, 8));
+///   masks.insert(0u16, 1, plot);
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct PlacementMasks {
+	masks: HashMap<(u16, u16), PlacementMask>,
+}
+
+impl PlacementMasks {
+	/// Attach a mask to the given layer, replacing any existing one
+	pub fn insert<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		mask: PlacementMask,
+	) -> &mut Self {
+		self.masks.insert((map_id.into(), layer_id), mask);
+		self
+	}
+
+	/// Remove the mask of the given layer, returning it (if any)
+	pub fn remove<MId: MapId>(&mut self, map_id: MId, layer_id: u16) -> Option<PlacementMask> {
+		self.masks.remove(&(map_id.into(), layer_id))
+	}
+
+	/// Get the mask of the given layer
+	pub fn get<MId: MapId>(&self, map_id: MId, layer_id: u16) -> Option<&PlacementMask> {
+		self.masks.get(&(map_id.into(), layer_id))
+	}
+
+	/// Get the mask of the given layer mutably
+	pub fn get_mut<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<&mut PlacementMask> {
+		self.masks.get_mut(&(map_id.into(), layer_id))
+	}
+
+	/// Returns true if the given position may be edited
+	pub fn allows<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> bool {
+		self.get(map_id, layer_id)
+			.map_or(true, |mask| mask.contains(pos))
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Check the given position against the [`PlacementMask`] of its layer
+	///
+	/// Returns `false` if the position should be skipped.
+	pub(crate) fn check_placement_mask(
+		&self,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<bool, TilePlacementError> {
+		let mask = match self.placement_masks.get(map_id, layer_id) {
+			Some(mask) if !mask.contains(pos) => mask,
+			_ => return Ok(true),
+		};
+
+		match mask.action {
+			PolicyAction::Error => Err(TilePlacementError::Masked {
+				pos,
+				map_id,
+				layer_id,
+			}),
+			PolicyAction::Skip => Ok(false),
+		}
+	}
+}
@@ -0,0 +1,332 @@
+//! Tools for attaching custom properties to tiles
+
+use std::marker::PhantomData;
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::{
+	App, Changed, Commands, Component, DetectChanges, Entity, Query, Res, Resource,
+};
+use bevy::reflect::{FromReflect, Reflect};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TileTextureIndex;
+use bevy_tileset::prelude::{TilesetParent, Tilesets};
+
+/// The value of a custom tile property
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
+#[cfg_attr(
+	feature = "serialization",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TileProperty {
+	Bool(bool),
+	Int(i64),
+	Float(f64),
+	String(String),
+}
+
+impl TileProperty {
+	/// Get this property as a `bool`
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Self::Bool(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	/// Get this property as an `i64`
+	pub fn as_int(&self) -> Option<i64> {
+		match self {
+			Self::Int(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	/// Get this property as an `f64`
+	///
+	/// Integer properties are converted automatically.
+	pub fn as_float(&self) -> Option<f64> {
+		match self {
+			Self::Float(value) => Some(*value),
+			Self::Int(value) => Some(*value as f64),
+			_ => None,
+		}
+	}
+
+	/// Get this property as a string
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(value) => Some(value),
+			_ => None,
+		}
+	}
+}
+
+impl From<bool> for TileProperty {
+	fn from(value: bool) -> Self {
+		Self::Bool(value)
+	}
+}
+
+impl From<i64> for TileProperty {
+	fn from(value: i64) -> Self {
+		Self::Int(value)
+	}
+}
+
+impl From<f64> for TileProperty {
+	fn from(value: f64) -> Self {
+		Self::Float(value)
+	}
+}
+
+impl From<&str> for TileProperty {
+	fn from(value: &str) -> Self {
+		Self::String(value.to_string())
+	}
+}
+
+impl From<String> for TileProperty {
+	fn from(value: String) -> Self {
+		Self::String(value)
+	}
+}
+
+/// The properties of a single tile
+pub type TileProperties = HashMap<String, TileProperty>;
+
+/// A resource containing custom properties for the tiles of each tileset
+///
+/// Properties are stored by tileset name and tile name, so they apply to every placed instance
+/// of a tile (regardless of its variant or auto tile state).
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::TileMetadata;
+/// let mut metadata = TileMetadata::default();
+/// metadata
+///   .insert("My Tileset", "Wall", "collision", true)
+///   .insert("My Tileset", "Mud", "cost", 3i64);
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileMetadata {
+	tilesets: HashMap<String, HashMap<String, TileProperties>>,
+}
+
+impl TileMetadata {
+	/// Set a property for the given tile
+	pub fn insert<V: Into<TileProperty>>(
+		&mut self,
+		tileset: &str,
+		tile: &str,
+		key: &str,
+		value: V,
+	) -> &mut Self {
+		self.tilesets
+			.entry(tileset.to_string())
+			.or_insert_with(HashMap::default)
+			.entry(tile.to_string())
+			.or_insert_with(HashMap::default)
+			.insert(key.to_string(), value.into());
+		self
+	}
+
+	/// Remove a property from the given tile
+	///
+	/// Returns the removed property (if any)
+	pub fn remove(&mut self, tileset: &str, tile: &str, key: &str) -> Option<TileProperty> {
+		self.tilesets.get_mut(tileset)?.get_mut(tile)?.remove(key)
+	}
+
+	/// Get a property of the given tile
+	pub fn get(&self, tileset: &str, tile: &str, key: &str) -> Option<&TileProperty> {
+		self.properties(tileset, tile)?.get(key)
+	}
+
+	/// Get all properties of the given tile
+	pub fn properties(&self, tileset: &str, tile: &str) -> Option<&TileProperties> {
+		self.tilesets.get(tileset)?.get(tile)
+	}
+
+	/// Get all properties of a placed tile
+	pub(crate) fn resolve(
+		&self,
+		tilesets: &Tilesets,
+		index: &TileTextureIndex,
+		tileset: &TilesetParent,
+	) -> Option<&TileProperties> {
+		let (tileset, tile) = resolve_tile_name(tilesets, index, tileset)?;
+		self.properties(tileset, tile)
+	}
+}
+
+/// Get the tileset name and tile name of a placed tile
+pub(crate) fn resolve_tile_name<'a>(
+	tilesets: &'a Tilesets,
+	index: &TileTextureIndex,
+	tileset: &TilesetParent,
+) -> Option<(&'a str, &'a str)> {
+	let tileset = tilesets.get_by_id(&tileset.0)?;
+	let tile_id = tileset.get_tile_id(&(index.0 as usize))?;
+	let tile_name = tileset.get_tile_name(&tile_id.group_id)?;
+	Some((tileset.name(), tile_name))
+}
+
+/// A mapping from a tile property to a component
+///
+/// See [`TilePropertyComponents`] for details.
+pub trait TilePropertyMapping: Send + Sync + 'static {
+	/// The key of the property this mapping reads
+	fn key(&self) -> &str;
+
+	/// Insert the component for the given property value
+	fn insert(&self, value: &TileProperty, commands: &mut EntityCommands);
+
+	/// Remove the component
+	fn remove(&self, commands: &mut EntityCommands);
+}
+
+/// A [`TilePropertyMapping`] that creates a component using a closure
+struct ComponentMapping<C: Component, F: Fn(&TileProperty) -> Option<C>> {
+	key: String,
+	create: F,
+	marker: PhantomData<fn() -> C>,
+}
+
+impl<C, F> TilePropertyMapping for ComponentMapping<C, F>
+where
+	C: Component,
+	F: Fn(&TileProperty) -> Option<C> + Send + Sync + 'static,
+{
+	fn key(&self) -> &str {
+		&self.key
+	}
+
+	fn insert(&self, value: &TileProperty, commands: &mut EntityCommands) {
+		match (self.create)(value) {
+			Some(component) => commands.insert(component),
+			None => commands.remove::<C>(),
+		};
+	}
+
+	fn remove(&self, commands: &mut EntityCommands) {
+		commands.remove::<C>();
+	}
+}
+
+/// A resource containing the components to attach to tiles based on their [`TileMetadata`]
+///
+/// Whenever a tile is placed (or its texture changes), each registered mapping whose property
+/// is set on the tile inserts its component. Mappings whose property is not set remove their
+/// component, so replacing a tile also removes any components that no longer apply.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::Component;
+/// # use bevy_tileset_map::prelude::TilePropertyComponents;
+/// #[derive(Component)]
+/// struct Flammable;
+///
+/// #[derive(Component)]
+/// struct Cost(f64);
+///
+/// let mut components = TilePropertyComponents::default();
+/// components
+///   .add("flammable", |value| value.as_bool()?.then(|| Flammable))
+///   .add("cost", |value| Some(Cost(value.as_float()?)));
+/// ```
+#[derive(Resource, Default)]
+pub struct TilePropertyComponents {
+	mappings: Vec<Box<dyn TilePropertyMapping>>,
+}
+
+impl TilePropertyComponents {
+	/// Register a component created from the given property
+	///
+	/// If `create` returns `None`, the component is removed instead.
+	pub fn add<C, F>(&mut self, key: &str, create: F) -> &mut Self
+	where
+		C: Component,
+		F: Fn(&TileProperty) -> Option<C> + Send + Sync + 'static,
+	{
+		self.add_mapping(ComponentMapping {
+			key: key.to_string(),
+			create,
+			marker: PhantomData,
+		})
+	}
+
+	/// Register a custom [`TilePropertyMapping`]
+	pub fn add_mapping<M: TilePropertyMapping>(&mut self, mapping: M) -> &mut Self {
+		self.mappings.push(Box::new(mapping));
+		self
+	}
+
+	/// Remove every mapped component from a tile
+	pub(crate) fn remove_all(&self, commands: &mut EntityCommands) {
+		for mapping in &self.mappings {
+			mapping.remove(commands);
+		}
+	}
+
+	/// Insert or remove the mapped components for a tile with the given properties
+	fn apply(&self, properties: Option<&TileProperties>, commands: &mut EntityCommands) {
+		for mapping in &self.mappings {
+			match properties.and_then(|properties| properties.get(mapping.key())) {
+				Some(value) => mapping.insert(value, commands),
+				None => mapping.remove(commands),
+			}
+		}
+	}
+}
+
+/// An extension trait for registering [`TilePropertyComponents`] on an [`App`]
+pub trait AddTilePropertyComponent {
+	/// Register a component created from the given property
+	fn add_tile_property_component<C, F>(&mut self, key: &str, create: F) -> &mut Self
+	where
+		C: Component,
+		F: Fn(&TileProperty) -> Option<C> + Send + Sync + 'static;
+}
+
+impl AddTilePropertyComponent for App {
+	fn add_tile_property_component<C, F>(&mut self, key: &str, create: F) -> &mut Self
+	where
+		C: Component,
+		F: Fn(&TileProperty) -> Option<C> + Send + Sync + 'static,
+	{
+		self.init_resource::<TilePropertyComponents>();
+		self.world
+			.resource_mut::<TilePropertyComponents>()
+			.add(key, create);
+		self
+	}
+}
+
+/// __\[SYSTEM\]__ Inserts and removes the [`TilePropertyComponents`] of placed tiles
+///
+/// If the metadata or mappings change, every tile is updated.
+pub(crate) fn apply_tile_property_components(
+	mut commands: Commands,
+	changed_tiles: Query<(Entity, &TileTextureIndex, &TilesetParent), Changed<TileTextureIndex>>,
+	all_tiles: Query<(Entity, &TileTextureIndex, &TilesetParent)>,
+	tilesets: Tilesets,
+	metadata: Res<TileMetadata>,
+	components: Res<TilePropertyComponents>,
+) {
+	if components.mappings.is_empty() {
+		return;
+	}
+
+	let mut apply = |(entity, index, tileset): (Entity, &TileTextureIndex, &TilesetParent)| {
+		let properties = metadata.resolve(&tilesets, index, tileset);
+		components.apply(properties, &mut commands.entity(entity));
+	};
+
+	if metadata.is_changed() || components.is_changed() {
+		all_tiles.iter().for_each(&mut apply);
+	} else {
+		changed_tiles.iter().for_each(&mut apply);
+	}
+}
@@ -0,0 +1,229 @@
+//! Tools for rendering a map layer into a minimap image
+
+use bevy::math::UVec2;
+use bevy::prelude::{
+	Added, Assets, Changed, Color, Component, DetectChanges, Entity, Handle, Image, Local, Or,
+	Query, RemovedComponents, Res, ResMut,
+};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TilesetParent, Tilesets};
+
+use crate::coord::TileCoord;
+use crate::metadata::{TileMetadata, TileProperty};
+
+/// The [`TileMetadata`] property containing the color of a tile on the minimap
+///
+/// This can either be a hex string (such as `"#3a9d23"`) or an integer (such as `0x3a9d23`).
+pub const MINIMAP_COLOR_PROPERTY: &str = "minimap_color";
+
+/// A component that renders a layer of a map into an [`Image`]
+///
+/// Each tile is drawn as a square block of pixels, colored by its [`MINIMAP_COLOR_PROPERTY`]
+/// (or [`default_color`](Self::default_color) if it doesn't have one). The image is redrawn as
+/// tiles are placed and removed, so it can be displayed directly (such as in a UI image) without
+/// rendering the map a second time.
+#[derive(Component, Debug, Clone)]
+pub struct Minimap {
+	/// The map to render
+	pub map_id: u16,
+	/// The layer to render
+	pub layer_id: u16,
+	/// The size of the rendered region (in tiles), starting from the bottom-left of the map
+	pub size: UVec2,
+	/// The width and height of the block drawn for each tile (in pixels)
+	pub tile_pixels: u32,
+	/// The color of empty cells
+	pub background: Color,
+	/// The color of tiles without a minimap color
+	pub default_color: Color,
+	/// The image the minimap is rendered into
+	pub image: Handle<Image>,
+}
+
+impl Minimap {
+	/// Create a minimap for the given layer, adding its image to the given assets
+	pub fn new(
+		map_id: u16,
+		layer_id: u16,
+		size: UVec2,
+		tile_pixels: u32,
+		images: &mut Assets<Image>,
+	) -> Self {
+		let background = Color::NONE;
+		let image = images.add(Image::new_fill(
+			Extent3d {
+				width: size.x * tile_pixels,
+				height: size.y * tile_pixels,
+				depth_or_array_layers: 1,
+			},
+			TextureDimension::D2,
+			&to_rgba_u8(background),
+			TextureFormat::Rgba8UnormSrgb,
+		));
+
+		Self {
+			map_id,
+			layer_id,
+			size,
+			tile_pixels,
+			background,
+			default_color: Color::GRAY,
+			image,
+		}
+	}
+
+	/// Returns true if the given coordinate is drawn on this minimap
+	fn contains(&self, coord: &TileCoord) -> bool {
+		coord.map_id == self.map_id
+			&& coord.layer_id == self.layer_id
+			&& coord.pos.x < self.size.x
+			&& coord.pos.y < self.size.y
+	}
+
+	/// Draw the block for the given tile position
+	fn paint(&self, image: &mut Image, pos: &TilePos, color: Color) {
+		let width = (self.size.x * self.tile_pixels) as usize;
+		let rgba = to_rgba_u8(color);
+
+		// Images go top-down while bevy_ecs_tilemap goes bottom-up
+		let top = ((self.size.y - 1 - pos.y) * self.tile_pixels) as usize;
+		let left = (pos.x * self.tile_pixels) as usize;
+		for y in top..top + self.tile_pixels as usize {
+			for x in left..left + self.tile_pixels as usize {
+				let index = (y * width + x) * 4;
+				if let Some(pixel) = image.data.get_mut(index..index + 4) {
+					pixel.copy_from_slice(&rgba);
+				}
+			}
+		}
+	}
+}
+
+/// Convert a color to RGBA bytes
+fn to_rgba_u8(color: Color) -> [u8; 4] {
+	color
+		.as_rgba_f32()
+		.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+/// Parse the value of a [`MINIMAP_COLOR_PROPERTY`]
+fn parse_color(value: &TileProperty) -> Option<Color> {
+	match value {
+		TileProperty::String(hex) => Color::hex(hex).ok(),
+		TileProperty::Int(rgb) => {
+			let [_, r, g, b] = (*rgb as u32).to_be_bytes();
+			Some(Color::rgb_u8(r, g, b))
+		},
+		_ => None,
+	}
+}
+
+/// __\[SYSTEM\]__ Redraws the tiles of each [`Minimap`] as they change
+///
+/// Newly added minimaps are drawn in full, as is every minimap whenever the [`TileMetadata`]
+/// changes.
+pub(crate) fn update_minimaps(
+	minimaps: Query<&Minimap>,
+	added_minimaps: Query<&Minimap, Added<Minimap>>,
+	changed_tiles: Query<
+		(
+			Entity,
+			&TilePos,
+			&TileParent,
+			&TileTextureIndex,
+			&TilesetParent,
+		),
+		Or<(
+			Changed<TilePos>,
+			Changed<TileParent>,
+			Changed<TileTextureIndex>,
+		)>,
+	>,
+	all_tiles: Query<(
+		Entity,
+		&TilePos,
+		&TileParent,
+		&TileTextureIndex,
+		&TilesetParent,
+	)>,
+	mut removed_tiles: RemovedComponents<TilePos>,
+	mut tracked: Local<HashMap<Entity, TileCoord>>,
+	mut images: ResMut<Assets<Image>>,
+	metadata: Res<TileMetadata>,
+	tilesets: Tilesets,
+) {
+	let color_of = |minimap: &Minimap, index: &TileTextureIndex, tileset: &TilesetParent| {
+		metadata
+			.resolve(&tilesets, index, tileset)
+			.and_then(|properties| properties.get(MINIMAP_COLOR_PROPERTY))
+			.and_then(parse_color)
+			.unwrap_or(minimap.default_color)
+	};
+
+	// === Full Redraw === //
+	// Tiles may have changed color if their metadata did
+	let redrawn = if metadata.is_changed() {
+		minimaps.iter().collect::<Vec<_>>()
+	} else {
+		added_minimaps.iter().collect()
+	};
+	for minimap in redrawn {
+		if let Some(image) = images.get_mut(&minimap.image) {
+			let background = to_rgba_u8(minimap.background);
+			for pixel in image.data.chunks_exact_mut(4) {
+				pixel.copy_from_slice(&background);
+			}
+
+			for (_, pos, parent, index, tileset) in all_tiles.iter() {
+				let coord = TileCoord {
+					pos: *pos,
+					map_id: parent.map_id,
+					layer_id: parent.layer_id,
+				};
+				if minimap.contains(&coord) {
+					minimap.paint(image, pos, color_of(minimap, index, tileset));
+				}
+			}
+		}
+	}
+
+	// === Removed Tiles === //
+	for entity in removed_tiles.iter() {
+		if let Some(coord) = tracked.remove(&entity) {
+			for minimap in minimaps.iter().filter(|minimap| minimap.contains(&coord)) {
+				if let Some(image) = images.get_mut(&minimap.image) {
+					minimap.paint(image, &coord.pos, minimap.background);
+				}
+			}
+		}
+	}
+
+	// === Changed Tiles === //
+	for (entity, pos, parent, index, tileset) in changed_tiles.iter() {
+		let coord = TileCoord {
+			pos: *pos,
+			map_id: parent.map_id,
+			layer_id: parent.layer_id,
+		};
+		if let Some(previous) = tracked.insert(entity, coord) {
+			if previous != coord {
+				for minimap in minimaps
+					.iter()
+					.filter(|minimap| minimap.contains(&previous))
+				{
+					if let Some(image) = images.get_mut(&minimap.image) {
+						minimap.paint(image, &previous.pos, minimap.background);
+					}
+				}
+			}
+		}
+
+		for minimap in minimaps.iter().filter(|minimap| minimap.contains(&coord)) {
+			if let Some(image) = images.get_mut(&minimap.image) {
+				minimap.paint(image, pos, color_of(minimap, index, tileset));
+			}
+		}
+	}
+}
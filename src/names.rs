@@ -0,0 +1,97 @@
+//! Tools for referring to tiles by name
+
+use bevy::prelude::{AssetEvent, EventReader, ResMut, Resource};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::{TileId, Tileset};
+
+use crate::placement::{MapId, TilePlacementError, TilePlacementResult, TilePlacer};
+
+/// A resource caching the [`TileId`] of each tile name looked up by the [`TilePlacer`]
+///
+/// Names are resolved against every loaded tileset the first time they're used and cached from
+/// then on. The cache is cleared whenever a tileset is added, modified, or removed, so IDs that
+/// change when a tileset is edited are picked up automatically.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileNames {
+	ids: HashMap<String, TileId>,
+}
+
+impl TileNames {
+	/// Get the cached ID of the given tile name
+	pub fn get(&self, name: &str) -> Option<TileId> {
+		self.ids.get(name).copied()
+	}
+
+	/// Forget every cached name
+	pub fn clear(&mut self) {
+		self.ids.clear();
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Find the ID of a tile by its name
+	///
+	/// The name can either be the name of the tile itself (found in any loaded tileset) or be
+	/// prefixed by the name of its tileset, such as `"Terrain/Grass"`, to disambiguate tiles
+	/// sharing the same name across tilesets.
+	///
+	/// Returns `None` if no loaded tileset contains the tile.
+	pub fn find_tile_by_name(&mut self, name: &str) -> Option<TileId> {
+		if let Some(id) = self.tile_names.get(name) {
+			return Some(id);
+		}
+
+		let qualified = name.split_once('/').and_then(|(tileset, tile)| {
+			let tileset = self.tilesets.get_by_name(tileset)?;
+			let group_id = tileset.get_tile_group_id(tile)?;
+			Some(TileId::new(*group_id, *tileset.id()))
+		});
+		let id = qualified.or_else(|| {
+			self.tilesets.iter().find_map(|(_, tileset)| {
+				let group_id = tileset.get_tile_group_id(name)?;
+				Some(TileId::new(*group_id, *tileset.id()))
+			})
+		})?;
+
+		self.tile_names.ids.insert(name.to_string(), id);
+		Some(id)
+	}
+
+	/// Place a tile by its name
+	///
+	/// See [`find_tile_by_name`](Self::find_tile_by_name) for how the name is resolved. Fails
+	/// with [`TilePlacementError::UnknownTile`] if no loaded tileset contains the tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::TilePlacer;
+	/// fn place_grass(mut placer: TilePlacer) {
+	///   placer.place_by_name("Terrain/Grass", TilePos { x: 2, y: 3 }, 0u16, 0).unwrap();
+	/// }
+	/// ```
+	pub fn place_by_name<Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		name: &str,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> TilePlacementResult {
+		let id = self
+			.find_tile_by_name(name)
+			.ok_or_else(|| TilePlacementError::UnknownTile(name.to_string()))?;
+		self.place(id, pos, map_id, layer_id)
+	}
+}
+
+/// __\[SYSTEM\]__ Clears the [`TileNames`] cache whenever a tileset changes
+pub(crate) fn clear_tile_names(
+	mut events: EventReader<AssetEvent<Tileset>>,
+	mut names: ResMut<TileNames>,
+) {
+	if events.iter().count() > 0 {
+		names.clear();
+	}
+}
@@ -0,0 +1,278 @@
+//! Tools for pathfinding across placed tiles
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use bevy::prelude::{
+	Changed, DetectChanges, Entity, Local, Or, Query, RemovedComponents, Res, ResMut, Resource,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TilesetParent, Tilesets};
+
+use crate::manager::TilemapLayer;
+use crate::metadata::TileMetadata;
+use crate::placement::MapId;
+
+/// The [`TileMetadata`] property marking whether a tile can be walked on
+///
+/// Tiles are walkable unless this property is set to `false`.
+pub const WALKABLE_PROPERTY: &str = "walkable";
+
+/// The [`TileMetadata`] property defining the cost of walking across a tile
+///
+/// Defaults to `1.0`. When multiple layers have a tile at the same position, the highest cost
+/// is used.
+pub const COST_PROPERTY: &str = "cost";
+
+/// A single cell of a [`NavGrid`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NavCell {
+	/// Whether this cell can be walked on
+	pub walkable: bool,
+	/// The cost of walking onto this cell
+	pub cost: f32,
+}
+
+impl Default for NavCell {
+	fn default() -> Self {
+		Self {
+			walkable: true,
+			cost: 1.0,
+		}
+	}
+}
+
+/// A resource containing the walkability of every occupied tile position, per map
+///
+/// This is built from the [`WALKABLE_PROPERTY`] and [`COST_PROPERTY`] properties of each tile's
+/// [`TileMetadata`] and is kept in sync as tiles are placed and removed. A position is only
+/// walkable if it contains at least one tile and none of its tiles (across all layers) are
+/// marked as unwalkable.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct NavGrid {
+	maps: HashMap<u16, HashMap<TilePos, NavCell>>,
+	/// The cost of the cheapest walkable cell of each map, used to scale the A* heuristic
+	min_costs: HashMap<u16, f32>,
+}
+
+impl NavGrid {
+	/// Get the cell at the given position
+	///
+	/// Returns `None` if no tile exists at that position
+	pub fn get<Pos: Into<TilePos>, MId: MapId>(&self, pos: Pos, map_id: MId) -> Option<&NavCell> {
+		self.maps.get(&map_id.into())?.get(&pos.into())
+	}
+
+	/// Returns true if the given position can be walked on
+	pub fn is_walkable<Pos: Into<TilePos>, MId: MapId>(&self, pos: Pos, map_id: MId) -> bool {
+		self.get(pos, map_id)
+			.map(|cell| cell.walkable)
+			.unwrap_or_default()
+	}
+
+	/// Find the cheapest path between two positions using A*
+	///
+	/// Movement is restricted to the four cardinal directions. The returned path includes both
+	/// `from` and `to`, along with the total cost of the path.
+	///
+	/// Returns `None` if no path exists.
+	pub fn astar<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		map_id: MId,
+		from: Pos,
+		to: Pos,
+	) -> Option<(Vec<TilePos>, f32)> {
+		let map_id = map_id.into();
+		let cells = self.maps.get(&map_id)?;
+		let from = from.into();
+		let to = to.into();
+		if !cells.get(&from)?.walkable || !cells.get(&to)?.walkable {
+			return None;
+		}
+
+		// Scale the heuristic by the cheapest cell so it never overestimates
+		let min_cost = self.min_costs.get(&map_id).copied().unwrap_or_default();
+		let heuristic =
+			|pos: &TilePos| (pos.x.abs_diff(to.x) + pos.y.abs_diff(to.y)) as f32 * min_cost;
+
+		let mut open = BinaryHeap::new();
+		let mut came_from: HashMap<TilePos, TilePos> = HashMap::default();
+		let mut costs: HashMap<TilePos, f32> = HashMap::default();
+		costs.insert(from, 0.0);
+		open.push(OpenNode {
+			pos: from,
+			score: heuristic(&from),
+		});
+
+		while let Some(OpenNode { pos, .. }) = open.pop() {
+			if pos == to {
+				let mut path = vec![pos];
+				let mut current = pos;
+				while let Some(prev) = came_from.get(&current) {
+					path.push(*prev);
+					current = *prev;
+				}
+				path.reverse();
+				return Some((path, costs[&to]));
+			}
+
+			let cost = costs[&pos];
+			for neighbor in neighbors(&pos) {
+				let cell = match cells.get(&neighbor) {
+					Some(cell) if cell.walkable => cell,
+					_ => continue,
+				};
+
+				let next_cost = cost + cell.cost;
+				if costs
+					.get(&neighbor)
+					.map(|existing| next_cost < *existing)
+					.unwrap_or(true)
+				{
+					costs.insert(neighbor, next_cost);
+					came_from.insert(neighbor, pos);
+					open.push(OpenNode {
+						pos: neighbor,
+						score: next_cost + heuristic(&neighbor),
+					});
+				}
+			}
+		}
+
+		None
+	}
+
+	/// Recompute the cost of the cheapest walkable cell of the given map
+	fn update_min_cost(&mut self, map_id: u16) {
+		let min_cost = self
+			.maps
+			.get(&map_id)
+			.into_iter()
+			.flat_map(|cells| cells.values())
+			.filter(|cell| cell.walkable)
+			.map(|cell| cell.cost)
+			.fold(f32::INFINITY, f32::min)
+			.max(0.0);
+		self.min_costs.insert(map_id, min_cost);
+	}
+}
+
+/// A node in the A* open set
+struct OpenNode {
+	pos: TilePos,
+	score: f32,
+}
+
+impl PartialEq for OpenNode {
+	fn eq(&self, other: &Self) -> bool {
+		self.score == other.score
+	}
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for OpenNode {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// Reversed so the `BinaryHeap` pops the lowest score first
+		other.score.total_cmp(&self.score)
+	}
+}
+
+/// Get the cardinal neighbors of the given position
+fn neighbors(pos: &TilePos) -> impl Iterator<Item = TilePos> {
+	let pos = *pos;
+	[(0i64, 1i64), (1, 0), (0, -1), (-1, 0)]
+		.into_iter()
+		.filter_map(move |(x, y)| {
+			let x = u32::try_from(pos.x as i64 + x).ok()?;
+			let y = u32::try_from(pos.y as i64 + y).ok()?;
+			Some(TilePos { x, y })
+		})
+}
+
+/// __\[SYSTEM\]__ Keeps the [`NavGrid`] in sync with placed and removed tiles
+///
+/// Only the cells of changed tiles are recomputed, unless the [`TileMetadata`] itself changed,
+/// in which case every cell is.
+pub(crate) fn update_nav_grid(
+	changed_tiles: Query<
+		(Entity, &TilePos, &TileParent),
+		Or<(Changed<TilePos>, Changed<TileTextureIndex>)>,
+	>,
+	mut removed_tiles: RemovedComponents<TilePos>,
+	tiles: Query<(&TileTextureIndex, &TilesetParent)>,
+	storages: Query<(&TilemapLayer, &TileStorage)>,
+	tilesets: Tilesets,
+	metadata: Res<TileMetadata>,
+	mut grid: ResMut<NavGrid>,
+	mut tracked: Local<HashMap<Entity, (u16, TilePos)>>,
+) {
+	let mut dirty = HashSet::default();
+	// Removals come first, so pooled tiles that were reused are tracked again
+	for entity in removed_tiles.iter() {
+		if let Some(previous) = tracked.remove(&entity) {
+			dirty.insert(previous);
+		}
+	}
+	for (entity, pos, parent) in changed_tiles.iter() {
+		if let Some(previous) = tracked.insert(entity, (parent.map_id, *pos)) {
+			dirty.insert(previous);
+		}
+		dirty.insert((parent.map_id, *pos));
+	}
+	if metadata.is_changed() {
+		dirty.extend(tracked.values().copied());
+	}
+
+	if dirty.is_empty() {
+		return;
+	}
+
+	// === Recompute Dirty Cells === //
+	let mut maps = HashSet::default();
+	for (map_id, pos) in dirty {
+		let mut cell: Option<NavCell> = None;
+		let layers = storages
+			.iter()
+			.filter(|(layer, _)| layer.map_id == map_id)
+			.filter_map(|(_, storage)| storage.checked_get(&pos));
+		for entity in layers {
+			let (index, tileset) = match tiles.get(entity) {
+				Ok(tile) => tile,
+				Err(_) => continue,
+			};
+
+			let properties = metadata.resolve(&tilesets, index, tileset);
+			let walkable = properties
+				.and_then(|properties| properties.get(WALKABLE_PROPERTY))
+				.and_then(|property| property.as_bool())
+				.unwrap_or(true);
+			let cost = properties
+				.and_then(|properties| properties.get(COST_PROPERTY))
+				.and_then(|property| property.as_float())
+				.unwrap_or(1.0) as f32;
+
+			let cell = cell.get_or_insert(NavCell { walkable, cost });
+			cell.walkable &= walkable;
+			cell.cost = cell.cost.max(cost);
+		}
+
+		let map = grid.maps.entry(map_id).or_insert_with(HashMap::default);
+		match cell {
+			Some(cell) => map.insert(pos, cell),
+			None => map.remove(&pos),
+		};
+		maps.insert(map_id);
+	}
+
+	for map_id in maps {
+		grid.update_min_cost(map_id);
+	}
+}
@@ -0,0 +1,131 @@
+//! Tools for quickly checking whether a cell contains a tile
+
+use bevy::prelude::{Changed, Entity, Or, Query, RemovedComponents, ResMut, Resource};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::{TileParent, TilePos};
+
+use crate::coord::TileCoord;
+use crate::placement::MapId;
+
+/// The width and height (in tiles) of each block of the occupancy bitset
+const BLOCK_SIZE: u32 = 8;
+
+/// A resource tracking which cells of each layer contain a tile
+///
+/// Cells are stored as a bitset per layer, so checking whether a cell is filled doesn't require
+/// querying any entities. The [`TilePlacer`] updates this as soon as it places or removes a
+/// tile, while tiles spawned or despawned by other means are picked up during the
+/// [`TilesetMapStage`].
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::Res;
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::TileOccupancy;
+/// fn count_walls(occupancy: Res<TileOccupancy>) {
+///   let walls = (0..32)
+///     .filter(|x| occupancy.is_occupied(TilePos { x: *x, y: 0 }, 0u16, 1))
+///     .count();
+/// }
+/// ```
+///
+/// [`TilePlacer`]: crate::prelude::TilePlacer
+/// [`TilesetMapStage`]: crate::prelude::TilesetMapStage
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileOccupancy {
+	/// The bitset of each layer, split into blocks of 8x8 cells
+	layers: HashMap<(u16, u16), HashMap<(u32, u32), u64>>,
+	/// The cell occupied by each tracked tile entity
+	tiles: HashMap<Entity, TileCoord>,
+}
+
+impl TileOccupancy {
+	/// Returns true if the given cell contains a tile
+	pub fn is_occupied<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> bool {
+		let (block, bit) = Self::locate(pos.into());
+		self.layers
+			.get(&(map_id.into(), layer_id))
+			.and_then(|blocks| blocks.get(&block))
+			.map_or(false, |bits| bits & bit != 0)
+	}
+
+	/// Returns true if the given layer contains no tiles
+	pub fn is_layer_empty<MId: MapId>(&self, map_id: MId, layer_id: u16) -> bool {
+		self.layers
+			.get(&(map_id.into(), layer_id))
+			.map_or(true, HashMap::is_empty)
+	}
+
+	/// Get the number of filled cells in the given layer
+	pub fn count<MId: MapId>(&self, map_id: MId, layer_id: u16) -> usize {
+		self.layers
+			.get(&(map_id.into(), layer_id))
+			.map_or(0, |blocks| {
+				blocks.values().map(|bits| bits.count_ones() as usize).sum()
+			})
+	}
+
+	/// Mark the given cell as filled or empty
+	pub(crate) fn set(&mut self, pos: TilePos, map_id: u16, layer_id: u16, occupied: bool) {
+		let (block, bit) = Self::locate(pos);
+		let blocks = self.layers.entry((map_id, layer_id)).or_default();
+		if occupied {
+			*blocks.entry(block).or_default() |= bit;
+		} else if let Some(bits) = blocks.get_mut(&block) {
+			*bits &= !bit;
+			if *bits == 0 {
+				blocks.remove(&block);
+			}
+		}
+	}
+
+	/// Get the block containing the given cell and the bit of the cell within it
+	pub(crate) fn locate(pos: TilePos) -> ((u32, u32), u64) {
+		let block = (pos.x / BLOCK_SIZE, pos.y / BLOCK_SIZE);
+		let bit = 1 << ((pos.y % BLOCK_SIZE) * BLOCK_SIZE + pos.x % BLOCK_SIZE);
+		(block, bit)
+	}
+}
+
+/// __\[SYSTEM\]__ Keeps the [`TileOccupancy`] in sync with tiles spawned, moved, or despawned
+/// outside of the [`TilePlacer`](crate::prelude::TilePlacer)
+///
+/// Tiles moved to another layer (such as by [`TilemapManager::swap_layers`]) have their
+/// [`TileParent`] re-inserted, so they're picked up as changed rather than added.
+///
+/// [`TilemapManager::swap_layers`]: crate::prelude::TilemapManager::swap_layers
+pub(crate) fn sync_tile_occupancy(
+	tiles: Query<(Entity, &TilePos, &TileParent), Or<(Changed<TileParent>, Changed<TilePos>)>>,
+	mut removed: RemovedComponents<TileParent>,
+	mut occupancy: ResMut<TileOccupancy>,
+) {
+	// Removals go first so that a tile replaced this frame stays occupied
+	for entity in removed.iter() {
+		if let Some(coord) = occupancy.tiles.remove(&entity) {
+			occupancy.set(coord.pos, coord.map_id, coord.layer_id, false);
+		}
+	}
+
+	// Clear every old cell first, since moved tiles may take each other's cells
+	for (entity, ..) in tiles.iter() {
+		if let Some(old) = occupancy.tiles.remove(&entity) {
+			occupancy.set(old.pos, old.map_id, old.layer_id, false);
+		}
+	}
+
+	for (entity, pos, parent) in tiles.iter() {
+		let coord = TileCoord {
+			pos: *pos,
+			map_id: parent.map_id,
+			layer_id: parent.layer_id,
+		};
+		occupancy.tiles.insert(entity, coord);
+		occupancy.set(coord.pos, coord.map_id, coord.layer_id, true);
+	}
+}
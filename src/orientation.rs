@@ -0,0 +1,140 @@
+//! Tools for rotating and mirroring stamps
+
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::{TileGroupId, TileId, TilesetId};
+
+use crate::stamp::TileStamp;
+use crate::transform::{TileRotation, TileTransform};
+
+/// A source of the tiles to use when a [`TileStamp`] is rotated or mirrored
+///
+/// Most tiles look the same regardless of how the stamp containing them is oriented (and auto
+/// tiles resolve their own texture once placed), but some are drawn facing a specific direction,
+/// such as a door on the left wall of a room. An orienter swaps those for the tile facing the
+/// new direction.
+///
+/// Any `Fn(TileId, TileTransform) -> TileId` closure can be used as an orienter.
+pub trait TileOrienter {
+	/// Get the tile replacing the given tile once transformed
+	fn orient(&self, tile_id: TileId, transform: TileTransform) -> TileId;
+}
+
+impl<F: Fn(TileId, TileTransform) -> TileId> TileOrienter for F {
+	fn orient(&self, tile_id: TileId, transform: TileTransform) -> TileId {
+		self(tile_id, transform)
+	}
+}
+
+/// A [`TileOrienter`] mapping tiles to their oriented counterparts
+///
+/// Tiles are matched by group, and any tile without a mapping for a transform is kept as-is.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{TileOrientations, TileId, TileRotation, TileTransform};
+/// # let (door_left, door_top) = (TileId::new(0, 0), TileId::new(1, 0));
+/// let mut orientations = TileOrientations::default();
+/// orientations.insert(door_left, TileTransform::rotated(TileRotation::Cw90), door_top);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TileOrientations {
+	tiles: HashMap<(TilesetId, TileGroupId, TileTransform), TileId>,
+}
+
+impl TileOrientations {
+	/// Replace `tile_id` with `oriented` whenever it's transformed by the given transform
+	pub fn insert<Id: Into<TileId>, OrientedId: Into<TileId>>(
+		&mut self,
+		tile_id: Id,
+		transform: TileTransform,
+		oriented: OrientedId,
+	) -> &mut Self {
+		let id = tile_id.into();
+		self.tiles
+			.insert((id.tileset_id, id.group_id, transform), oriented.into());
+		self
+	}
+}
+
+impl TileOrienter for TileOrientations {
+	fn orient(&self, tile_id: TileId, transform: TileTransform) -> TileId {
+		self.tiles
+			.get(&(tile_id.tileset_id, tile_id.group_id, transform))
+			.copied()
+			.unwrap_or(tile_id)
+	}
+}
+
+/// A [`TileOrienter`] keeping every tile as-is
+struct KeepTiles;
+
+impl TileOrienter for KeepTiles {
+	fn orient(&self, tile_id: TileId, _: TileTransform) -> TileId {
+		tile_id
+	}
+}
+
+impl TileStamp {
+	/// Get a copy of this stamp rotated clockwise
+	///
+	/// The tiles themselves are kept as-is. Use [`transformed`](Self::transformed) to swap
+	/// tiles facing a specific direction.
+	pub fn rotated(&self, rotation: TileRotation) -> Self {
+		self.transformed(TileTransform::rotated(rotation), &KeepTiles)
+	}
+
+	/// Get a copy of this stamp mirrored horizontally
+	pub fn mirrored_x(&self) -> Self {
+		self.transformed(TileTransform::IDENTITY.with_flip_x(), &KeepTiles)
+	}
+
+	/// Get a copy of this stamp mirrored vertically
+	pub fn mirrored_y(&self) -> Self {
+		self.transformed(TileTransform::IDENTITY.with_flip_y(), &KeepTiles)
+	}
+
+	/// Get a copy of this stamp with the given transform applied, using the given
+	/// [`TileOrienter`] to swap each tile
+	///
+	/// Like a [`TileTransform`] applied to a single tile, the rotation is applied first,
+	/// followed by the flips. Stamps rotated by 90° or 270° have their width and height swapped.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_tileset_map::prelude::{TileOrientations, TileRotation, TileStamp, TileTransform};
+	/// # let room = TileStamp::new(4, 3);
+	/// # let orientations = TileOrientations::default();
+	/// let transform = TileTransform::rotated(TileRotation::Cw90).with_flip_x();
+	/// let rotated = room.transformed(transform, &orientations);
+	/// ```
+	pub fn transformed<O: TileOrienter + ?Sized>(
+		&self,
+		transform: TileTransform,
+		orienter: &O,
+	) -> Self {
+		let size = self.size();
+		let (width, height) = match transform.rotation {
+			TileRotation::None | TileRotation::Cw180 => (size.x, size.y),
+			TileRotation::Cw90 | TileRotation::Cw270 => (size.y, size.x),
+		};
+
+		let mut stamp = Self::new(width, height);
+		for (pos, layer_id, tile_id) in self.iter() {
+			let (x, y) = match transform.rotation {
+				TileRotation::None => (pos.x, pos.y),
+				TileRotation::Cw90 => (pos.y, size.x - 1 - pos.x),
+				TileRotation::Cw180 => (size.x - 1 - pos.x, size.y - 1 - pos.y),
+				TileRotation::Cw270 => (size.y - 1 - pos.y, pos.x),
+			};
+			let pos = TilePos {
+				x: if transform.flip_x { width - 1 - x } else { x },
+				y: if transform.flip_y { height - 1 - y } else { y },
+			};
+			stamp.set(pos, layer_id, Some(orienter.orient(*tile_id, transform)));
+		}
+		stamp
+	}
+}
@@ -0,0 +1,217 @@
+//! Tools for drawing the grid of a map
+
+use bevy::prelude::{
+	Assets, BuildChildren, Changed, Color, ColorMaterial, Commands, DespawnRecursiveExt, Entity,
+	Local, Mesh, Or, Query, Res, ResMut, Resource, Transform, Vec2,
+};
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::manager::TilemapLayer;
+
+/// An opt-in resource drawing the grid lines of each map
+///
+/// The grid is drawn over the lowest layer of each map, matching its grid size and map type
+/// (square, isometric, or hexagonal). Insert this resource to start drawing the grid, and set
+/// [`enabled`](Self::enabled) to toggle it without losing its configuration:
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy_tileset_map::prelude::GridOverlay;
+/// # let mut app = App::new();
+/// app.init_resource::<GridOverlay>();
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct GridOverlay {
+	/// Whether the grid is drawn
+	pub enabled: bool,
+	/// The color of the grid lines
+	pub color: Color,
+	/// The maps to draw the grid for (`None` for every map)
+	pub map_ids: Option<HashSet<u16>>,
+	/// How far above the lowest layer of the map the grid is drawn
+	pub z_offset: f32,
+}
+
+impl Default for GridOverlay {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			color: Color::rgba(1.0, 1.0, 1.0, 0.25),
+			map_ids: None,
+			z_offset: 100.0,
+		}
+	}
+}
+
+impl GridOverlay {
+	/// Returns true if the grid of the given map should be drawn
+	fn shows(&self, map_id: u16) -> bool {
+		self.enabled
+			&& self
+				.map_ids
+				.as_ref()
+				.map_or(true, |map_ids| map_ids.contains(&map_id))
+	}
+}
+
+/// __\[SYSTEM\]__ Spawns, rebuilds, and removes the grid drawn for each map
+pub(crate) fn update_grid_overlays(
+	mut commands: Commands,
+	overlay: Option<Res<GridOverlay>>,
+	tilemaps: Query<(
+		Entity,
+		&TilemapLayer,
+		&TilemapSize,
+		&TilemapGridSize,
+		&TilemapType,
+	)>,
+	changed: Query<
+		(),
+		Or<(
+			Changed<TilemapSize>,
+			Changed<TilemapGridSize>,
+			Changed<TilemapType>,
+		)>,
+	>,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<ColorMaterial>>,
+	mut grids: Local<HashMap<Entity, Entity>>,
+) {
+	// The lowest layer of each map shown
+	let mut targets = HashMap::<u16, (u16, Entity)>::default();
+	if let Some(overlay) = overlay.as_ref() {
+		for (entity, layer, ..) in tilemaps.iter() {
+			if !overlay.shows(layer.map_id) {
+				continue;
+			}
+
+			let target = targets
+				.entry(layer.map_id)
+				.or_insert((layer.layer_id, entity));
+			if layer.layer_id < target.0 {
+				*target = (layer.layer_id, entity);
+			}
+		}
+	}
+	let targets = targets
+		.into_values()
+		.map(|(_, entity)| entity)
+		.collect::<HashSet<_>>();
+
+	// Remove the grids of hidden (or despawned) maps
+	grids.retain(|tilemap, grid| {
+		let keep = targets.contains(tilemap);
+		if !keep {
+			if let Some(mut grid) = commands.get_entity(*grid) {
+				grid.despawn();
+			}
+		}
+		keep
+	});
+
+	let overlay = match overlay {
+		Some(overlay) => overlay,
+		None => return,
+	};
+	for tilemap in targets {
+		let outdated = overlay.is_changed() || changed.contains(tilemap);
+		if grids.contains_key(&tilemap) && !outdated {
+			continue;
+		}
+
+		let (_, _, size, grid_size, map_type) = match tilemaps.get(tilemap) {
+			Ok(tilemap) => tilemap,
+			Err(_) => continue,
+		};
+		if let Some(grid) = grids.remove(&tilemap) {
+			commands.entity(grid).despawn_recursive();
+		}
+
+		let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+		mesh.insert_attribute(
+			Mesh::ATTRIBUTE_POSITION,
+			grid_lines(size, grid_size, map_type),
+		);
+		let grid = commands
+			.spawn(MaterialMesh2dBundle {
+				mesh: meshes.add(mesh).into(),
+				material: materials.add(ColorMaterial::from(overlay.color)),
+				transform: Transform::from_xyz(0.0, 0.0, overlay.z_offset),
+				..Default::default()
+			})
+			.id();
+		commands.entity(tilemap).add_child(grid);
+		grids.insert(tilemap, grid);
+	}
+}
+
+/// Get the vertices of the line segments outlining every cell of a map, in the map's local space
+fn grid_lines(
+	size: &TilemapSize,
+	grid_size: &TilemapGridSize,
+	map_type: &TilemapType,
+) -> Vec<[f32; 3]> {
+	let (w, h) = (grid_size.x, grid_size.y);
+
+	// Square grids are drawn using one line per row and column
+	if let TilemapType::Square = map_type {
+		let (width, height) = (size.x as f32 * w, size.y as f32 * h);
+		let origin = Vec2::new(-w / 2.0, -h / 2.0);
+		let mut lines = Vec::new();
+		for x in 0..=size.x {
+			let x = origin.x + x as f32 * w;
+			lines.push([x, origin.y, 0.0]);
+			lines.push([x, origin.y + height, 0.0]);
+		}
+		for y in 0..=size.y {
+			let y = origin.y + y as f32 * h;
+			lines.push([origin.x, y, 0.0]);
+			lines.push([origin.x + width, y, 0.0]);
+		}
+		return lines;
+	}
+
+	let corners: Vec<Vec2> = match map_type {
+		TilemapType::Hexagon(HexCoordSystem::Row)
+		| TilemapType::Hexagon(HexCoordSystem::RowEven)
+		| TilemapType::Hexagon(HexCoordSystem::RowOdd) => vec![
+			Vec2::new(0.0, h / 2.0),
+			Vec2::new(w / 2.0, h / 4.0),
+			Vec2::new(w / 2.0, -h / 4.0),
+			Vec2::new(0.0, -h / 2.0),
+			Vec2::new(-w / 2.0, -h / 4.0),
+			Vec2::new(-w / 2.0, h / 4.0),
+		],
+		TilemapType::Hexagon(_) => vec![
+			Vec2::new(w / 2.0, 0.0),
+			Vec2::new(w / 4.0, h / 2.0),
+			Vec2::new(-w / 4.0, h / 2.0),
+			Vec2::new(-w / 2.0, 0.0),
+			Vec2::new(-w / 4.0, -h / 2.0),
+			Vec2::new(w / 4.0, -h / 2.0),
+		],
+		_ => vec![
+			Vec2::new(0.0, h / 2.0),
+			Vec2::new(w / 2.0, 0.0),
+			Vec2::new(0.0, -h / 2.0),
+			Vec2::new(-w / 2.0, 0.0),
+		],
+	};
+
+	let mut lines = Vec::with_capacity((size.x * size.y) as usize * corners.len() * 2);
+	for y in 0..size.y {
+		for x in 0..size.x {
+			let center = TilePos { x, y }.center_in_world(grid_size, map_type);
+			for (index, corner) in corners.iter().enumerate() {
+				let next = corners[(index + 1) % corners.len()];
+				let (a, b) = (center + *corner, center + next);
+				lines.push([a.x, a.y, 0.0]);
+				lines.push([b.x, b.y, 0.0]);
+			}
+		}
+	}
+	lines
+}
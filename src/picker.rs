@@ -0,0 +1,153 @@
+//! Tools for finding the tile under the cursor
+
+use bevy::ecs::system::SystemParam;
+use bevy::math::Vec2;
+use bevy::prelude::{Camera, Entity, GlobalTransform, Query, With};
+use bevy::window::{PrimaryWindow, Window};
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::manager::TilemapLayer;
+
+/// A tile found by [`TilePicker::raycast`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TileHit {
+	/// The tilemap containing the tile
+	pub tilemap: Entity,
+	/// The map and layer of the tilemap (if it was created by this crate)
+	pub layer: Option<TilemapLayer>,
+	/// The position of the tile within its tilemap
+	pub pos: TilePos,
+	/// The tile entity
+	pub entity: Entity,
+	/// The world-space z of the tilemap
+	pub z: f32,
+}
+
+/// A system param used to convert screen and world positions into tile positions
+///
+/// This accounts for the camera, the tilemap's transform, its grid size, and its map type.
+/// The cursor position is taken from the primary window and converted using the first active
+/// camera.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{Entity, Res, Resource};
+/// # use bevy_tileset_map::prelude::TilePicker;
+/// # #[derive(Resource)]
+/// # struct MyTilemap(Entity);
+/// fn hover_system(picker: TilePicker, tilemap: Res<MyTilemap>) {
+///   if let Some(pos) = picker.cursor_to_tile(tilemap.0) {
+///     println!("Hovering over {:?}", pos);
+///   }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct TilePicker<'w, 's> {
+	windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+	cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+	tilemaps: Query<
+		'w,
+		's,
+		(
+			&'static TilemapSize,
+			&'static TilemapGridSize,
+			&'static TilemapType,
+			&'static GlobalTransform,
+			Option<&'static TileStorage>,
+		),
+	>,
+	all_tilemaps: Query<'w, 's, Entity, With<TileStorage>>,
+	layers: Query<'w, 's, &'static TilemapLayer>,
+}
+
+impl<'w, 's> TilePicker<'w, 's> {
+	/// Get the world position of the cursor
+	///
+	/// Returns `None` if the cursor is outside the primary window or there is no active camera.
+	pub fn cursor_to_world(&self) -> Option<Vec2> {
+		let cursor = self.windows.get_single().ok()?.cursor_position()?;
+		let (camera, transform) = self.cameras.iter().find(|(camera, _)| camera.is_active)?;
+		camera.viewport_to_world_2d(transform, cursor)
+	}
+
+	/// Get the position of the tile containing the given world position
+	///
+	/// Returns `None` if the position is outside the given tilemap.
+	pub fn world_to_tile(&self, world_pos: Vec2, tilemap: Entity) -> Option<TilePos> {
+		let (map_size, grid_size, map_type, transform, _) = self.tilemaps.get(tilemap).ok()?;
+
+		// Convert to the tilemap's local space
+		let local_pos = transform
+			.compute_matrix()
+			.inverse()
+			.transform_point3(world_pos.extend(0.0))
+			.truncate();
+
+		TilePos::from_world_pos(&local_pos, map_size, grid_size, map_type)
+	}
+
+	/// Get the position of the tile under the cursor
+	pub fn cursor_to_tile(&self, tilemap: Entity) -> Option<TilePos> {
+		self.world_to_tile(self.cursor_to_world()?, tilemap)
+	}
+
+	/// Get the position and entity of the tile under the cursor
+	///
+	/// Returns `None` if there is no tile under the cursor.
+	pub fn cursor_to_tile_entity(&self, tilemap: Entity) -> Option<(TilePos, Entity)> {
+		let pos = self.cursor_to_tile(tilemap)?;
+		let (.., storage) = self.tilemaps.get(tilemap).ok()?;
+		Some((pos, storage?.get(&pos)?))
+	}
+
+	/// Find every tile under the given screen position, as seen by the given camera
+	///
+	/// Every tilemap is checked, taking its full transform into account (so scaled, rotated, and
+	/// translated tilemaps are handled), as well as the camera's own transform and projection.
+	/// Only cells containing a tile are returned.
+	///
+	/// Hits are ordered by z, starting with the topmost tilemap.
+	pub fn raycast(&self, camera: Entity, screen_pos: Vec2) -> Vec<TileHit> {
+		let world_pos = match self
+			.cameras
+			.get(camera)
+			.ok()
+			.and_then(|(camera, transform)| camera.viewport_to_world_2d(transform, screen_pos))
+		{
+			Some(world_pos) => world_pos,
+			None => return Vec::new(),
+		};
+
+		let mut hits = self
+			.all_tilemaps
+			.iter()
+			.filter_map(|tilemap| {
+				let pos = self.world_to_tile(world_pos, tilemap)?;
+				let (.., transform, storage) = self.tilemaps.get(tilemap).ok()?;
+				Some(TileHit {
+					tilemap,
+					layer: self.layers.get(tilemap).ok().copied(),
+					pos,
+					entity: storage?.checked_get(&pos)?,
+					z: transform.translation().z,
+				})
+			})
+			.collect::<Vec<_>>();
+		hits.sort_by(|a, b| b.z.total_cmp(&a.z));
+		hits
+	}
+
+	/// Same as [`raycast`](Self::raycast), using the cursor position in the primary window
+	pub fn raycast_cursor(&self, camera: Entity) -> Vec<TileHit> {
+		match self
+			.windows
+			.get_single()
+			.ok()
+			.and_then(Window::cursor_position)
+		{
+			Some(cursor) => self.raycast(camera, cursor),
+			None => Vec::new(),
+		}
+	}
+}
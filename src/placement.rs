@@ -41,6 +41,100 @@ pub enum TilePlacementError {
 	/// Contains the generated error
 	#[error("Tilemap error: {0:?}")]
 	MapError(Error),
+	/// The named layer does not exist
+	///
+	/// Contains the name of the layer in question
+	#[error("Unknown layer {0:?}")]
+	UnknownLayer(String),
+	/// The tileset has not finished loading
+	#[error("Tileset is not loaded")]
+	UnloadedTileset,
+	/// The tile lies outside its tilemap while the [`OutOfBoundsPolicy`] is set to `Error`
+	///
+	/// [`OutOfBoundsPolicy`]: crate::prelude::OutOfBoundsPolicy
+	#[error("Tile position {pos:?} is outside the map of size {size:?}")]
+	OutOfBounds {
+		/// The position of the tile
+		pos: TilePos,
+		/// The size of the tilemap
+		size: TilemapSize,
+	},
+	/// The placement was rejected by a [`PlacementValidator`](crate::prelude::PlacementValidator)
+	///
+	/// Contains the reason given by the validator
+	#[error("Placement rejected: {0}")]
+	Rejected(String),
+	/// No tile belonging to a [`TileStage`](crate::prelude::TileStage) exists at the given position
+	#[error("No staged tile found at {0:?}")]
+	NoStagedTile(TilePos),
+	/// The [`TileStage`](crate::prelude::TileStage) has no stage with the given index
+	#[error("Invalid stage {0}")]
+	InvalidStage(usize),
+	/// A CSV cell value has no tile in its [`TileCellMapping`](crate::prelude::TileCellMapping)
+	#[error("No tile mapped to cell value {0:?}")]
+	UnmappedCell(String),
+	/// The cell is already covered by a [big tile](crate::prelude::BigTiles) or a big tile would
+	/// cover a filled cell
+	#[error("Tile overlaps another tile at {pos:?} (layer {layer_id})")]
+	Overlap {
+		/// The position of the overlapping cell
+		pos: TilePos,
+		/// The layer of the overlapping cell
+		layer_id: u16,
+	},
+	/// The tile isn't accepted by the [`LayerConstraints`](crate::prelude::LayerConstraints) of
+	/// its layer
+	#[error("Tile {tile_id:?} is not allowed in layer {layer_id} of map {map_id}")]
+	DisallowedTile {
+		/// The ID of the rejected tile
+		tile_id: TileId,
+		/// The map the tile was placed in
+		map_id: u16,
+		/// The layer the tile was placed in
+		layer_id: u16,
+	},
+	/// The position lies outside the [`PlacementMask`](crate::prelude::PlacementMask) of its
+	/// layer
+	#[error("Tile position {pos:?} is outside the placement mask of layer {layer_id} of map {map_id}")]
+	Masked {
+		/// The position of the tile
+		pos: TilePos,
+		/// The map of the tile
+		map_id: u16,
+		/// The layer of the tile
+		layer_id: u16,
+	},
+	/// The entity is not a tilemap
+	///
+	/// Contains the entity in question
+	#[error("Entity {0:?} is not a tilemap")]
+	InvalidTilemap(Entity),
+	/// Some tiles of an adopted tilemap are missing from its texture index mapping, so they would
+	/// be drawn with the wrong texture
+	#[error("{count} tile(s) of tilemap {tilemap:?} are missing from the texture index mapping")]
+	UnmappedTiles {
+		/// The adopted tilemap
+		tilemap: Entity,
+		/// The number of unmapped tiles
+		count: usize,
+	},
+	/// No tile exists at the given position
+	#[error("No tile found at {0:?}")]
+	EmptyCell(TilePos),
+	/// No loaded tileset contains a tile with the given name
+	#[error("Unknown tile {0:?}")]
+	UnknownTile(String),
+	/// No [`TileRecipe`](crate::prelude::TileRecipe) is registered with the given name
+	#[error("Unknown recipe {0:?}")]
+	UnknownRecipe(String),
+	/// No tiling satisfying the given [`WfcConstraints`](crate::prelude::WfcConstraints) was found
+	#[cfg(feature = "wfc")]
+	#[error("No valid tiling found")]
+	NoValidTiling,
+	/// The [`TilePrefab`](crate::prelude::TilePrefab) has not finished loading
+	#[cfg(feature = "serialization")]
+	#[error("Prefab is not loaded")]
+	UnloadedPrefab,
 }
 
 /// An enum denoting how a tile was placed or removed
@@ -48,7 +142,8 @@ pub enum TilePlacementError {
 /// This allows you to respond to the results the placement, such as handling cleanup
 /// or performing a secondary action.
 ///
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect_value(Debug, PartialEq)]
 pub enum PlacedTile {
 	/// A tile was added
 	Added {
@@ -62,6 +157,8 @@ pub enum PlacedTile {
 		/// The removed tile
 		old_tile: Option<(Entity, Option<TileId>)>,
 	},
+	/// The tile was skipped under a lenient [`PlacementPolicy`](crate::prelude::PlacementPolicy)
+	Skipped,
 }
 
 /// A result type alias for tile placement
@@ -99,9 +196,31 @@ impl MapId for u16 {}
 /// ```
 #[derive(SystemParam)]
 pub struct TilePlacer<'w, 's> {
-	map_query: Query<'w, 's, &'static TileStorage>,
-	tilesets: Tilesets<'w, 's>,
-	commands: Commands<'w, 's>,
+	pub(crate) map_query: Query<'w, 's, &'static mut TileStorage>,
+	pub(crate) tilesets: Tilesets<'w, 's>,
+	pub(crate) commands: Commands<'w, 's>,
+	/// Query used to find the tilemap of each layer
+	pub(crate) tilemaps: Query<'w, 's, (Entity, &'static crate::manager::TilemapLayer)>,
+	/// How tiles placed outside the bounds of their tilemap are handled
+	pub(crate) bounds_policy: Res<'w, crate::bounds::OutOfBoundsPolicy>,
+	/// Which problems abort a placement and which are skipped
+	pub(crate) policy: Res<'w, crate::policy::PlacementPolicy>,
+	/// The policy set by [`with_policy`](Self::with_policy), if any
+	pub(crate) policy_override: Local<'s, Option<crate::policy::PlacementPolicy>>,
+	/// The named layers of each map
+	pub(crate) map_layers: Res<'w, crate::layers::MapLayers>,
+	/// Query used to find the tiles within a map
+	pub(crate) region_query: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static TilePos,
+			&'static TileParent,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+		),
+	>,
 	/// Query used to get info about a tile
 	#[cfg(not(feature = "auto-tile"))]
 	#[allow(dead_code)]
@@ -121,7 +240,7 @@ pub struct TilePlacer<'w, 's> {
 	/// Query used to get and send data for the [`RemoveAutoTileEvent`] event
 	#[cfg(feature = "auto-tile")]
 	#[allow(dead_code)]
-	auto_query: Query<
+	pub(crate) auto_query: Query<
 		'w,
 		's,
 		(
@@ -133,7 +252,61 @@ pub struct TilePlacer<'w, 's> {
 	>,
 	#[cfg(feature = "auto-tile")]
 	#[allow(dead_code)]
-	event_writer: EventWriter<'w, 's, crate::auto::RemoveAutoTileEvent>,
+	pub(crate) event_writer: EventWriter<'w, 's, crate::auto::RemoveAutoTileEvent>,
+	/// The auto tiles a [`RemoveAutoTileEvent`] was already sent for during this run, along with
+	/// the tick of the run
+	#[cfg(feature = "auto-tile")]
+	removed_auto_tiles: Local<'s, (u32, bevy::utils::HashSet<Entity>)>,
+	/// The custom auto tile rules (used to preview auto tiles)
+	#[cfg(feature = "auto-tile")]
+	auto_rules: Res<'w, crate::auto::AutoTileRules>,
+	/// The auto tile layer connections (used to preview auto tiles)
+	#[cfg(feature = "auto-tile")]
+	auto_layers: Res<'w, crate::auto::AutoTileLayers>,
+	/// The auto tile group connections (used to preview auto tiles)
+	#[cfg(feature = "auto-tile")]
+	auto_connections: Res<'w, crate::auto::AutoTileConnections>,
+	/// Used to find the neighbors of a tile based on its map type
+	pub(crate) grids: crate::grid::MapGrids<'w, 's>,
+	/// The registered tile stages
+	pub(crate) stages: Res<'w, crate::stages::TileStages>,
+	/// The tiles used for each height of the terrain
+	pub(crate) terrain_tiers: Res<'w, crate::terrain::TerrainTiers>,
+	/// The pool of reusable tile entities, if pooling is enabled
+	pub(crate) pool: Option<ResMut<'w, crate::pool::TilePool>>,
+	/// The registered and placed big tiles
+	pub(crate) big_tiles: ResMut<'w, crate::big::BigTiles>,
+	/// The filled cells of each layer
+	pub(crate) occupancy: ResMut<'w, crate::occupancy::TileOccupancy>,
+	/// The map and layer used when none is given
+	pub(crate) default_target: Res<'w, crate::target::DefaultMapTarget>,
+	/// The cached IDs of tiles looked up by name
+	pub(crate) tile_names: ResMut<'w, crate::names::TileNames>,
+	/// The cached IDs of each texture index (used to identify placed tiles)
+	pub(crate) tile_ids: Res<'w, crate::lookup::TileIdCache>,
+	/// The validators consulted before placing a tile
+	validators: Res<'w, crate::validation::PlacementValidators>,
+	/// The tiles accepted by each constrained layer
+	pub(crate) layer_constraints: Res<'w, crate::constraints::LayerConstraints>,
+	/// The region each masked layer may be edited in
+	pub(crate) placement_masks: Res<'w, crate::mask::PlacementMasks>,
+	/// The registered multi-layer tile recipes
+	pub(crate) recipes: Res<'w, crate::recipe::TileRecipes>,
+	/// The components attached to tiles based on their metadata (removed from pooled tiles)
+	pub(crate) property_components: Res<'w, crate::metadata::TilePropertyComponents>,
+	/// The (optional) log recording every edit
+	change_log: Option<ResMut<'w, crate::changes::TileChangeLog>>,
+	change_tick: bevy::ecs::system::SystemChangeTick,
+	/// The loaded prefabs (used to place prefabs by handle)
+	#[cfg(feature = "serialization")]
+	pub(crate) prefabs: Res<'w, Assets<crate::serialization::TilePrefab>>,
+}
+
+/// A tile found in an occupied cell
+struct ExistingTile {
+	entity: Entity,
+	/// The ID of the tile (if its tileset is loaded)
+	id: Option<TileId>,
 }
 
 impl<'w, 's> TilePlacer<'w, 's> {
@@ -147,6 +320,125 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		self.place_unchecked(tile_id, pos, map_id, layer_id)
 	}
 
+	/// Place a tile, randomly selecting one of its variants based on their weights
+	///
+	/// Any variant index contained in the given [`TileId`] is ignored. If the tile has no
+	/// variants to choose from, this behaves exactly like [`place`](Self::place).
+	#[cfg(feature = "variants")]
+	pub fn place_random<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> TilePlacementResult {
+		let mut id = tile_id.into();
+		id.variant_index = self.select_variant_index(&id);
+		self.place_unchecked(id, pos, map_id, layer_id)
+	}
+
+	/// Replace the tile at the given position with the next variant in its variant group,
+	/// wrapping back to the first variant after the last one
+	///
+	/// Fails with [`TilePlacementError::EmptyCell`] if there is no tile at the given position,
+	/// or [`TilePlacementError::TileAlreadyExists`] if the tile has no other variants to cycle to.
+	#[cfg(feature = "variants")]
+	pub fn cycle_variant<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> TilePlacementResult {
+		let pos = pos.into();
+		let existing = self
+			.get_existing(pos, map_id, layer_id)
+			.ok_or(TilePlacementError::EmptyCell(pos))?;
+		let existing_id = existing.id.ok_or(TilePlacementError::UnloadedTileset)?;
+
+		let count = self.variant_count(&existing_id);
+		if count < 2 {
+			return Err(TilePlacementError::TileAlreadyExists {
+				new: existing_id,
+				existing: Some(existing_id),
+				pos,
+			});
+		}
+
+		let next = existing_id
+			.variant_index
+			.map_or(0, |index| (index + 1) % count);
+		let mut id = existing_id;
+		id.variant_index = Some(next);
+		self.place_unchecked(id, pos, map_id, layer_id)
+	}
+
+	/// Place a tile, or cycle to its next variant if the same tile already occupies the cell
+	///
+	/// Placing a tile of another group replaces the existing tile like [`place`](Self::place)
+	/// does. See [`cycle_variant`](Self::cycle_variant) for how variants are cycled.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::prelude::{Res, Resource};
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+	/// # #[derive(Resource)]
+	/// # struct Flowers(TileId);
+	/// fn decorate_system(mut placer: TilePlacer, flowers: Res<Flowers>) {
+	///   // Clicking the same cell again picks the next flower
+	///   placer.place_or_cycle(flowers.0, TilePos { x: 3, y: 2 }, 0u16, 1).ok();
+	/// }
+	/// ```
+	#[cfg(feature = "variants")]
+	pub fn place_or_cycle<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> TilePlacementResult {
+		let id = tile_id.into();
+		let pos = pos.into();
+
+		if let Some(existing) = self.get_existing(pos, map_id, layer_id) {
+			if let Some(existing_id) = existing.id {
+				if existing_id.eq_tile_group(&id) {
+					return self.cycle_variant(pos, map_id, layer_id);
+				}
+			}
+		}
+
+		self.place_unchecked(id, pos, map_id, layer_id)
+	}
+
+	/// Get the texture index the given tile would have if placed, without modifying the map
+	///
+	/// For auto tiles, this resolves the texture based on the tiles currently surrounding the
+	/// given position, matching what [`place`](Self::place) would result in. This makes it useful
+	/// for rendering a preview of a tile before it's placed.
+	///
+	/// Note that for tiles with variants, the returned index is just one of the possible variants.
+	pub fn preview<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<TileIndex, TilePlacementError> {
+		let id = tile_id.into();
+
+		#[cfg(feature = "auto-tile")]
+		if let Some(index) = self.preview_auto_tile(&id, pos.into(), map_id, layer_id)? {
+			return Ok(index);
+		}
+
+		#[cfg(not(feature = "auto-tile"))]
+		let _ = (pos, map_id, layer_id);
+
+		self.get_tile_index(&id)
+	}
+
 	pub fn try_place<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
 		&mut self,
 		tile_id: Id,
@@ -157,7 +449,7 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		let id = tile_id.into();
 		let pos = pos.into();
 
-		if let Some(existing) = self.get_existing(id, pos, map_id, layer_id) {
+		if let Some(existing) = self.get_existing(pos, map_id, layer_id) {
 			// Tile already exists -> don't place
 			return Err(TilePlacementError::TileAlreadyExists {
 				new: id,
@@ -179,7 +471,7 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		let id = tile_id.into();
 		let pos = pos.into();
 
-		if let Some(existing) = self.get_existing(id, pos, map_id, layer_id) {
+		if let Some(existing) = self.get_existing(pos, map_id, layer_id) {
 			// Check that the existing tile is of a different type
 			if let Some(existing_id) = existing.id {
 				if existing_id.eq_tile_group(&id) {
@@ -205,7 +497,7 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		let id = tile_id.into();
 		let pos = pos.into();
 
-		if let Some(existing) = self.get_existing(id, pos, map_id, layer_id) {
+		if let Some(existing) = self.get_existing(pos, map_id, layer_id) {
 			// Remove the existing tile if it matches
 			if let Some(existing_id) = existing.id {
 				if existing_id.eq_tile_group(&id) {
@@ -237,7 +529,7 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		let id = tile_id.into();
 		let pos = pos.into();
 
-		if let Some(existing) = self.get_existing(id, pos, map_id, layer_id) {
+		if let Some(existing) = self.get_existing(pos, map_id, layer_id) {
 			self.remove(pos, map_id, layer_id)?;
 			return Ok(PlacedTile::Removed {
 				old_tile: Some((existing.entity, existing.id)),
@@ -254,25 +546,63 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		layer_id: u16,
 	) -> Result<(), TilePlacementError> {
 		let pos = pos.into();
+		if !self.check_placement_mask(pos, map_id.into(), layer_id)? {
+			return Ok(());
+		}
+		if self.remove_big_tile(pos, map_id.into(), layer_id)? {
+			return Ok(());
+		}
+
+		// Get the current tile entity
+		let entity = match self.find_entity(pos, map_id, layer_id) {
+			Some(entity) => entity,
+			None => return Ok(()),
+		};
+		let old = self.tile_id_of(entity);
+		self.push_change(pos, map_id, layer_id, old, None);
 
+		// Attempt to remove the auto tile
 		#[cfg(feature = "auto-tile")]
-		{
-			// Get the current tile entity
-			let entity = self
-				.map_query
-				.get_tile_entity(pos, map_id, layer_id)
-				.map_err(|err| TilePlacementError::MapError(err))?;
+		self.try_remove_auto_tile(entity);
 
-			// Attempt to remove the auto tile
-			self.try_remove_auto_tile(entity);
-		}
+		self.release_cell(pos, map_id.into(), layer_id);
 
-		// Despawn the tile and notify the chunk
-		self.map_query
-			.remove(pos);
+		// Despawn (or pool) the tile and remove it from its storage
+		if let Some(mut storage) = self.tile_storage_mut(map_id, layer_id) {
+			storage.remove(&pos);
+		}
+		self.despawn_pooled(entity);
 		Ok(())
 	}
 
+	/// Mark the given cell as empty in the [`TileOccupancy`] and release any big tile reserving
+	/// it
+	///
+	/// Every removal path calls this for each removed tile.
+	///
+	/// [`TileOccupancy`]: crate::prelude::TileOccupancy
+	pub(crate) fn release_cell(&mut self, pos: TilePos, map_id: u16, layer_id: u16) {
+		self.occupancy.set(pos, map_id, layer_id, false);
+		self.big_tiles.take_placed(&crate::coord::TileCoord {
+			pos,
+			map_id,
+			layer_id,
+		});
+	}
+
+	/// Insert the given bundle onto the tile added by a placement (if any)
+	///
+	/// Every `place_with_*` variant uses this to decorate the placed tile.
+	pub(crate) fn insert_on_added<B: Bundle>(&mut self, result: &PlacedTile, bundle: B) {
+		if let PlacedTile::Added {
+			new_tile: (entity, _),
+			..
+		} = result
+		{
+			self.commands.entity(*entity).insert(bundle);
+		}
+	}
+
 	pub fn add_to_layer<TId: Into<TileId>, Pos: Into<TilePos>>(
 		&mut self,
 		tile_id: TId,
@@ -288,13 +618,49 @@ impl<'w, 's> TilePlacer<'w, 's> {
 	) -> Result<(), TilePlacementError> {
 	}
 
-	fn place_unchecked<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+	/// Run every check a placement goes through before the tile is placed, without modifying the
+	/// map
+	///
+	/// This covers the [`PlacementPolicy`](crate::prelude::PlacementPolicy), the
+	/// [`OutOfBoundsPolicy`](crate::prelude::OutOfBoundsPolicy), the tile's tileset, overlapping
+	/// big tiles, and the layer's validation. Operations placing several tiles at once use it to
+	/// validate every tile before placing any of them. The given overlay (if any) is applied on
+	/// top of the map for the layer's validators.
+	///
+	/// Returns the position the tile would be placed at, or `None` if it would be skipped.
+	pub(crate) fn can_place(
+		&self,
+		tile_id: &TileId,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+		overlay: Option<&crate::validation::TileOverlay>,
+	) -> Result<Option<TilePos>, TilePlacementError> {
+		let pos = match self.resolve_placement(tile_id, pos, map_id, layer_id)? {
+			Some(pos) => pos,
+			None => return Ok(None),
+		};
+		self.get_tile_index(tile_id)?;
+		self.check_big_tile_overlap(pos, map_id, layer_id)?;
+		self.validate_placement(*tile_id, pos, map_id, layer_id, overlay)?;
+		Ok(Some(pos))
+	}
+
+	pub(crate) fn place_unchecked<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
 		&mut self,
 		tile_id: Id,
 		pos: Pos,
 		map_id: MId,
 		layer_id: u16,
 	) -> TilePlacementResult {
+		let id = tile_id.into();
+		let pos = match self.can_place(&id, pos.into(), map_id.into(), layer_id, None)? {
+			Some(pos) => pos,
+			None => return Ok(PlacedTile::Skipped),
+		};
+		self.grow_to_fit(pos, map_id.into(), layer_id);
+		self.record_change(pos, map_id, layer_id, Some(id));
+		self.occupancy.set(pos, map_id.into(), layer_id, true);
 	}
 
 	#[cfg(feature = "auto-tile")]
@@ -318,6 +684,87 @@ impl<'w, 's> TilePlacer<'w, 's> {
 		}
 	}
 
+	/// Resolve the texture index of an auto tile placed at the given position
+	///
+	/// Returns `None` if the given tile is not an auto tile
+	#[cfg(feature = "auto-tile")]
+	fn preview_auto_tile<MId: MapId>(
+		&self,
+		id: &TileId,
+		pos: TilePos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Option<TileIndex>, TilePlacementError> {
+		if !self.get_tile_data(id)?.is_auto() {
+			return Ok(None);
+		}
+
+		let tileset = self.get_tileset(id)?;
+		let auto_id = bevy_tileset::auto::AutoTileId {
+			group_id: id.group_id,
+			tileset_id: *tileset.id(),
+		};
+
+		let mut rule = AutoTileRule::default();
+		let mut groups = crate::auto::NeighborGroups {
+			group: id.group_id,
+			neighbors: [None; 8],
+		};
+		let neighbor_layers = self.auto_layers.neighbor_layers(&auto_id, layer_id);
+		let grid = self.grids.get(map_id.into(), layer_id);
+		let directions = [
+			&mut rule.north,
+			&mut rule.east,
+			&mut rule.south,
+			&mut rule.west,
+			&mut rule.north_east,
+			&mut rule.north_west,
+			&mut rule.south_east,
+			&mut rule.south_west,
+		];
+		let neighbors = grid
+			.neighbors(&pos)
+			.into_iter()
+			.zip(groups.neighbors.iter_mut());
+		for ((neighbor, group), dir) in neighbors.zip(directions) {
+			let neighbor = match neighbor {
+				Some(neighbor) => neighbor,
+				None => continue,
+			};
+
+			// Matching (or connected) auto tile on the same layer
+			let other = self
+				.find_entity(neighbor, map_id, layer_id)
+				.and_then(|entity| self.auto_query.get(entity).ok())
+				.map(|(.., other)| *other);
+			let is_match = other
+				.map(|other| self.auto_connections.is_connected(&auto_id, &other))
+				.unwrap_or_default();
+			*group = other
+				.filter(|other| other.tileset_id == auto_id.tileset_id)
+				.map(|other| other.group_id);
+
+			// Any tile on a connected layer
+			let is_connected = neighbor_layers
+				.iter()
+				.any(|layer| self.find_entity(neighbor, map_id, *layer).is_some());
+
+			if is_match || is_connected {
+				*dir = Some(true);
+			}
+		}
+
+		let index = self
+			.auto_rules
+			.select_groups(&auto_id, &groups)
+			.or_else(|| self.auto_rules.select(&auto_id, &rule))
+			.or_else(|| {
+				let name = tileset.get_tile_name(&id.group_id)?;
+				tileset.get_auto_index(name, rule)
+			});
+		Ok(index)
+	}
+
 	#[cfg(feature = "auto-tile")]
 	fn try_remove_auto_tile(&mut self, entity: Entity) -> bool {
 		// Create the remove event
@@ -334,18 +781,84 @@ impl<'w, 's> TilePlacer<'w, 's> {
 
 		// Send the remove event (separated due to mutability rules)
 		if let Some(event) = event {
-			self.event_writer.send(event);
+			if self.mark_auto_tile_removed(entity) {
+				self.event_writer.send(event);
+			}
 			true
 		} else {
 			false
 		}
 	}
 
+	/// Record that a [`RemoveAutoTileEvent`] is being sent for the given auto tile
+	///
+	/// Returns false if one was already sent for it during this run, in which case sending
+	/// another would only cause redundant updates.
+	#[cfg(feature = "auto-tile")]
+	pub(crate) fn mark_auto_tile_removed(&mut self, entity: Entity) -> bool {
+		let tick = self.change_tick.change_tick();
+		let (last_tick, removed) = &mut *self.removed_auto_tiles;
+		if *last_tick != tick {
+			*last_tick = tick;
+			removed.clear();
+		}
+		removed.insert(entity)
+	}
+
+	/// Randomly select the index of a variant for the given tile using [`Tileset::select_variant`]
+	///
+	/// Returns `None` if the tile has no variants to choose from
+	#[cfg(feature = "variants")]
+	fn select_variant_index(&self, tile_id: &TileId) -> Option<usize> {
+		let data = self.get_tile_data(tile_id).ok()?;
+		let variants = match data.tile() {
+			TileType::Variant(variants) => variants,
+			#[cfg(feature = "auto-tile")]
+			TileType::Auto(autos) => autos.get(tile_id.auto_index?)?.variants(),
+			_ => return None,
+		};
+
+		let selected = Tileset::select_variant(variants)?;
+		variants
+			.iter()
+			.position(|variant| std::ptr::eq(variant, selected))
+	}
+
+	/// Get the number of variants the given tile has to choose from
+	#[cfg(feature = "variants")]
+	fn variant_count(&self, tile_id: &TileId) -> usize {
+		let data = match self.get_tile_data(tile_id) {
+			Ok(data) => data,
+			Err(_) => return 0,
+		};
+		match data.tile() {
+			TileType::Variant(variants) => variants.len(),
+			#[cfg(feature = "auto-tile")]
+			TileType::Auto(autos) => tile_id
+				.auto_index
+				.and_then(|index| autos.get(index))
+				.map_or(0, |auto| auto.variants().len()),
+			_ => 0,
+		}
+	}
+
 	/// Tries to get the existing tile for a given tile coordinate
-	fn get_existing<Pos: Into<TilePos>>(
-		&mut self,
-		pos: Pos,
-	) -> Option<Entity> {
+	///
+	/// Empty cells are ruled out by the [`TileOccupancy`](crate::prelude::TileOccupancy) without
+	/// querying any entities, while occupied ones are read from the layer's [`TileStorage`].
+	fn get_existing<MId: MapId>(
+		&self,
+		pos: TilePos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<ExistingTile> {
+		if !self.occupancy.is_occupied(pos, map_id, layer_id) {
+			return None;
+		}
+
+		let entity = self.find_entity(pos, map_id, layer_id)?;
+		let id = self.tile_id_of(entity);
+		Some(ExistingTile { entity, id })
 	}
 
 	/// Get the tileset belonging to the given `TileId`
@@ -357,7 +870,7 @@ impl<'w, 's> TilePlacer<'w, 's> {
 	}
 
 	/// Get the `TileIndex` matching the given `TileId`
-	fn get_tile_index(&self, tile_id: &TileId) -> Result<TileIndex, TilePlacementError> {
+	pub(crate) fn get_tile_index(&self, tile_id: &TileId) -> Result<TileIndex, TilePlacementError> {
 	}
 
 	/// Get the `TileData` matching the given `TileId`
@@ -1,3 +1,6 @@
+use std::sync::Arc;
+
+use bevy::ecs::schedule::{IntoRunCriteria, StageLabelId};
 use bevy::prelude::*;
 use bevy_ecs_tilemap::TilemapStage;
 
@@ -10,29 +13,257 @@ pub enum TilesetMapLabel {
 	UpdateAutoTiles,
 	/// Labels the system that handles auto tile removals
 	RemoveAutoTiles,
+	/// Labels the system that composes the color of each tile from its tints
+	ComposeTileColors,
 }
 
 /// Plugin for setting up tilesets
-#[derive(Default)]
-pub struct TilesetMapPlugin;
+///
+/// By default, the plugin's systems run in the [`TilesetMapStage`] (added right before
+/// `bevy_ecs_tilemap`'s `TilemapStage`), while auto tiles are resolved during the `TilemapStage`
+/// itself. Projects using their own stages can move the systems elsewhere and restrict when
+/// they run:
+///
+/// ```
+/// # use bevy::ecs::schedule::ShouldRun;
+/// # use bevy::prelude::{App, CoreStage, Res, Resource};
+/// # use bevy_tileset_map::prelude::TilesetMapPlugin;
+/// # #[derive(Resource)]
+/// # struct Editor { is_open: bool }
+/// fn is_editing(editor: Res<Editor>) -> ShouldRun {
+///   if editor.is_open { ShouldRun::Yes } else { ShouldRun::No }
+/// }
+///
+/// App::new().add_plugin(
+///   TilesetMapPlugin::default()
+///     .in_stage(CoreStage::PostUpdate)
+///     .with_run_criteria(|| is_editing),
+/// );
+/// ```
+#[derive(Default, Clone)]
+pub struct TilesetMapPlugin {
+	/// The stage the systems run in (`None` for the [`TilesetMapStage`])
+	stage: Option<StageLabelId>,
+	/// The stage auto tiles are resolved in (`None` for `TilemapStage`)
+	auto_tile_stage: Option<StageLabelId>,
+	/// Applies the user's run criteria to a set of systems
+	run_criteria: Option<Arc<dyn Fn(SystemSet) -> SystemSet + Send + Sync>>,
+	/// The initial [`DefaultMapTarget`](crate::prelude::DefaultMapTarget)
+	default_target: Option<crate::target::DefaultMapTarget>,
+}
+
+impl TilesetMapPlugin {
+	/// Run the systems in the given stage instead of the [`TilesetMapStage`]
+	///
+	/// The [`TilesetMapStage`] isn't added to the app in this case.
+	pub fn in_stage<S: StageLabel>(mut self, stage: S) -> Self {
+		self.stage = Some(stage.as_label());
+		self
+	}
+
+	/// Resolve auto tiles in the given stage instead of `TilemapStage`
+	///
+	/// This stage should run after the one given to [`in_stage`](Self::in_stage), so removed
+	/// auto tiles are accounted for within the same frame.
+	pub fn with_auto_tile_stage<S: StageLabel>(mut self, stage: S) -> Self {
+		self.auto_tile_stage = Some(stage.as_label());
+		self
+	}
+
+	/// Only run the systems when the run criteria created by the given function says so
+	///
+	/// The function is called once for each set of systems added by this plugin.
+	pub fn with_run_criteria<
+		M: 'static,
+		R: IntoRunCriteria<M>,
+		F: Fn() -> R + Send + Sync + 'static,
+	>(
+		mut self,
+		run_criteria: F,
+	) -> Self {
+		self.run_criteria = Some(Arc::new(move |set: SystemSet| {
+			set.with_run_criteria(run_criteria())
+		}));
+		self
+	}
+
+	/// Target the given map and layer when placing tiles without naming their map and layer
+	///
+	/// See [`DefaultMapTarget`](crate::prelude::DefaultMapTarget) for details.
+	pub fn with_default_target<MId: crate::placement::MapId>(
+		mut self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Self {
+		self.default_target = Some(crate::target::DefaultMapTarget::new(map_id, layer_id));
+		self
+	}
+
+	/// Create an empty set of systems with the run criteria applied
+	fn system_set(&self) -> SystemSet {
+		match &self.run_criteria {
+			Some(run_criteria) => run_criteria(SystemSet::new()),
+			None => SystemSet::new(),
+		}
+	}
+}
 
 impl Plugin for TilesetMapPlugin {
 	fn build(&self, app: &mut App) {
-		app.add_stage_before(TilemapStage, TilesetMapStage, SystemStage::parallel());
+		let stage = match self.stage {
+			Some(stage) => stage,
+			None => {
+				app.add_stage_before(TilemapStage, TilesetMapStage, SystemStage::parallel());
+				TilesetMapStage.as_label()
+			},
+		};
 
-		#[cfg(feature = "auto-tile")]
-		app.add_event::<crate::auto::RemoveAutoTileEvent>()
+		match self.default_target {
+			Some(target) => app.insert_resource(target),
+			None => app.init_resource::<crate::target::DefaultMapTarget>(),
+		};
+
+		app.register_type::<crate::coord::TileCoord>()
+			.register_type::<crate::placement::PlacedTile>()
+			.register_type::<crate::stamp::TileStamp>()
+			.register_type::<crate::changes::TileChange>()
+			.register_type::<crate::transform::TileRotation>()
+			.register_type::<crate::transform::TileTransform>()
+			.register_type::<crate::events::PlaceTileMode>()
+			.register_type::<crate::metadata::TileProperty>()
+			.init_resource::<crate::layers::MapLayers>()
+			.init_resource::<crate::settings::MapSettings>()
+			.init_resource::<crate::bounds::OutOfBoundsPolicy>()
+			.init_resource::<crate::policy::PlacementPolicy>()
+			.init_resource::<crate::registry::TilemapRegistry>()
+			.init_resource::<crate::validation::PlacementValidators>()
+			.init_resource::<crate::constraints::LayerConstraints>()
+			.init_resource::<crate::mask::PlacementMasks>()
+			.init_resource::<crate::recipe::TileRecipes>()
+			.init_resource::<crate::stages::TileStages>()
+			.init_resource::<crate::occupancy::TileOccupancy>()
+			.init_resource::<crate::big::BigTiles>()
+			.init_resource::<crate::terrain::TerrainTiers>()
+			.init_resource::<crate::names::TileNames>()
+			.init_resource::<crate::lookup::TileIdCache>()
+			.init_resource::<crate::metadata::TileMetadata>()
+			.init_resource::<crate::metadata::TilePropertyComponents>()
+			.init_resource::<crate::nav::NavGrid>()
+			.init_resource::<crate::elevation::ElevationSettings>()
+			.add_event::<crate::events::PlaceTileRequest>()
+			.add_event::<crate::events::RemoveTileRequest>()
+			.add_event::<crate::events::PlaceTileResult>()
+			.add_event::<crate::events::RemoveTileResult>()
 			.add_system_set_to_stage(
-				TilesetMapStage,
-				SystemSet::new().with_system(
-					crate::auto::on_remove_auto_tile.label(TilesetMapLabel::RemoveAutoTiles),
+				stage,
+				self.system_set()
+					.with_system(crate::names::clear_tile_names)
+					.with_system(crate::lookup::rebuild_tile_id_cache)
+					.with_system(crate::occupancy::sync_tile_occupancy)
+					.with_system(crate::big::release_big_tiles)
+					.with_system(crate::metadata::apply_tile_property_components)
+					.with_system(crate::nav::update_nav_grid)
+					.with_system(crate::animation::reset_replaced_animations)
+					.with_system(crate::events::handle_tile_requests)
+					.with_system(crate::replay::update_tile_replay)
+					.with_system(crate::automata::step_tile_automata)
+					.with_system(crate::minimap::update_minimaps)
+					.with_system(crate::reload::reload_tilesets)
+					.with_system(crate::settings::apply_layer_transforms)
+					.with_system(
+						crate::fog::apply_fog_of_war.before(TilesetMapLabel::ComposeTileColors),
+					)
+					.with_system(
+						crate::color::compose_tile_colors.label(TilesetMapLabel::ComposeTileColors),
+					)
+					.with_system(crate::ghost::update_tile_ghosts)
+					.with_system(crate::overlay::update_grid_overlays)
+					.with_system(crate::selection::update_tile_selection),
+			);
+
+		app.add_system_set_to_stage(
+			CoreStage::Last,
+			self.system_set().with_system(crate::pool::trim_tile_pool),
+		);
+
+		#[cfg(any(feature = "rapier2d", feature = "xpbd"))]
+		app.add_system_set_to_stage(
+			stage,
+			self.system_set()
+				.with_system(crate::collider::update_tile_colliders),
+		);
+
+		#[cfg(feature = "lighting")]
+		app.init_resource::<crate::lighting::LightSettings>()
+			.init_resource::<crate::lighting::LightGrid>()
+			.add_system_set_to_stage(
+				stage,
+				self.system_set().with_system(
+					crate::lighting::update_lighting.before(TilesetMapLabel::ComposeTileColors),
 				),
-			)
-			.add_system_to_stage(
-				TilemapStage,
-				crate::auto::on_change_auto_tile
-					.label(TilesetMapLabel::UpdateAutoTiles)
-					.before(bevy_ecs_tilemap::TilemapLabel::UpdateChunkVisibility),
 			);
+
+		#[cfg(feature = "serialization")]
+		app.register_type::<crate::serialization::SerializableTile>()
+			.register_type::<crate::serialization::SerializableFlip>()
+			.register_type::<crate::serialization::SerializableAnimation>()
+			.register_type::<crate::serialization::SerializableColor>()
+			.register_type::<crate::serialization::SerializableTilemap>()
+			.register_type::<crate::serialization::SerializableChunkedTilemap>()
+			.register_type::<crate::serialization::ChunkCoord>()
+			.register_type::<crate::serialization::TileEdit>()
+			.register_type::<crate::serialization::TileDelta>()
+			.register_type::<crate::serialization::TilePrefab>()
+			.init_resource::<crate::serialization::MapMigrations>()
+			.add_asset::<crate::serialization::TilePrefab>()
+			.init_asset_loader::<crate::serialization::TilePrefabLoader>()
+			.add_event::<crate::serialization::ChunkSavedEvent>()
+			.add_system_set_to_stage(
+				stage,
+				self.system_set()
+					.with_system(crate::serialization::stream_chunks)
+					.with_system(crate::serialization::cull_chunks)
+					.with_system(crate::serialization::track_dirty_chunks)
+					.with_system(crate::serialization::clear_saved_chunks),
+			);
+
+		#[cfg(all(feature = "serialization", feature = "auto-tile"))]
+		app.init_resource::<crate::serialization::AutoTileSerialization>();
+
+		#[cfg(feature = "ldtk")]
+		app.add_asset::<crate::ldtk::LdtkProject>()
+			.init_resource::<crate::ldtk::LdtkEntityFactories>()
+			.init_asset_loader::<crate::ldtk::LdtkProjectLoader>();
+
+		#[cfg(feature = "auto-tile")]
+		{
+			app.add_event::<crate::auto::RemoveAutoTileEvent>()
+				.init_resource::<crate::auto::AutoTileRules>()
+				.init_resource::<crate::auto::AutoTileLayers>()
+				.init_resource::<crate::auto::AutoTileConnections>()
+				.init_resource::<crate::auto::DirtyAutoTiles>()
+				.add_system_set_to_stage(
+					stage,
+					self.system_set().with_system(
+						crate::auto::on_remove_auto_tile.label(TilesetMapLabel::RemoveAutoTiles),
+					),
+				);
+
+			let update_auto_tiles =
+				crate::auto::on_change_auto_tile.label(TilesetMapLabel::UpdateAutoTiles);
+			match self.auto_tile_stage {
+				Some(auto_tile_stage) => app.add_system_set_to_stage(
+					auto_tile_stage,
+					self.system_set().with_system(update_auto_tiles),
+				),
+				None => app.add_system_set_to_stage(
+					TilemapStage,
+					self.system_set().with_system(
+						update_auto_tiles
+							.before(bevy_ecs_tilemap::TilemapLabel::UpdateChunkVisibility),
+					),
+				),
+			};
+		}
 	}
-}
\ No newline at end of file
+}
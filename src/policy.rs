@@ -0,0 +1,154 @@
+//! Tools for choosing how strictly tiles are placed
+
+use bevy::prelude::Resource;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{TilePlacementError, TilePlacer};
+
+/// How a [`PlacementPolicy`] handles a problem encountered while placing a tile
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PolicyAction {
+	/// Fail with the corresponding [`TilePlacementError`]
+	Error,
+	/// Leave the map untouched and report the tile as [`PlacedTile::Skipped`]
+	///
+	/// [`PlacedTile::Skipped`]: crate::prelude::PlacedTile::Skipped
+	Skip,
+}
+
+/// A resource determining which problems abort a placement and which are skipped silently
+///
+/// By default every problem is reported as an error. Skipping them instead allows batch
+/// operations (such as [`TilePlacer::place_stamp`] or loading a map) over partially-loaded maps
+/// to place every tile they can rather than stopping at the first one they can't.
+///
+/// Use [`TilePlacer::with_policy`] to override the policy for a handful of calls.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy_tileset_map::prelude::{PlacementPolicy, PolicyAction};
+/// # let mut app = App::new();
+/// app.insert_resource(PlacementPolicy {
+///   missing_tileset: PolicyAction::Skip,
+///   ..PlacementPolicy::STRICT
+/// });
+/// ```
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PlacementPolicy {
+	/// Tiles placed outside their tilemap while the [`OutOfBoundsPolicy`] is `Error`
+	///
+	/// [`OutOfBoundsPolicy`]: crate::prelude::OutOfBoundsPolicy
+	pub out_of_bounds: PolicyAction,
+	/// Tiles placed in a layer without a tilemap
+	pub missing_layer: PolicyAction,
+	/// Tiles whose tileset doesn't exist or hasn't finished loading
+	pub missing_tileset: PolicyAction,
+}
+
+impl PlacementPolicy {
+	/// Report every problem as an error
+	pub const STRICT: Self = Self {
+		out_of_bounds: PolicyAction::Error,
+		missing_layer: PolicyAction::Error,
+		missing_tileset: PolicyAction::Error,
+	};
+
+	/// Skip every tile that can't be placed
+	pub const LENIENT: Self = Self {
+		out_of_bounds: PolicyAction::Skip,
+		missing_layer: PolicyAction::Skip,
+		missing_tileset: PolicyAction::Skip,
+	};
+}
+
+impl Default for PlacementPolicy {
+	fn default() -> Self {
+		Self::STRICT
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// The [`PlacementPolicy`] currently in use
+	pub fn policy(&self) -> PlacementPolicy {
+		self.policy_override.unwrap_or(*self.policy)
+	}
+
+	/// Run the given function with the [`PlacementPolicy`] replaced by `policy`
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::prelude::Res;
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{PlacementPolicy, TilePlacer, TileStamp};
+	/// # #[derive(bevy::prelude::Resource)]
+	/// # struct Level(TileStamp);
+	/// fn load_level(mut placer: TilePlacer, level: Res<Level>) {
+	///   placer
+	///     .with_policy(PlacementPolicy::LENIENT, |placer| {
+	///       placer.place_stamp(&level.0, TilePos { x: 0, y: 0 }, 0u16, 0)
+	///     })
+	///     .unwrap();
+	/// }
+	/// ```
+	pub fn with_policy<R, F: FnOnce(&mut Self) -> R>(
+		&mut self,
+		policy: PlacementPolicy,
+		f: F,
+	) -> R {
+		let previous = self.policy_override.replace(policy);
+		let result = f(self);
+		*self.policy_override = previous;
+		result
+	}
+
+	/// Returns true if the given tile should be skipped because its tileset or layer is
+	/// missing and the [`PlacementPolicy`] skips them
+	pub(crate) fn skips_placement(&self, tile_id: &TileId, map_id: u16, layer_id: u16) -> bool {
+		let policy = self.policy();
+		let skip_tileset = policy.missing_tileset == PolicyAction::Skip
+			&& self.tilesets.get_by_id(&tile_id.tileset_id).is_none();
+		let skip_layer = policy.missing_layer == PolicyAction::Skip
+			&& !self
+				.tilemaps
+				.iter()
+				.any(|(_, layer)| layer.map_id == map_id && layer.layer_id == layer_id);
+		skip_tileset || skip_layer
+	}
+
+	/// Resolve the position of a tile about to be placed, according to the
+	/// [`OutOfBoundsPolicy`](crate::prelude::OutOfBoundsPolicy), the [`PlacementPolicy`], and
+	/// the layer's [`PlacementMask`](crate::prelude::PlacementMask)
+	///
+	/// Returns `None` if the tile should be skipped.
+	pub(crate) fn resolve_placement(
+		&self,
+		tile_id: &TileId,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<Option<TilePos>, TilePlacementError> {
+		if self.skips_placement(tile_id, map_id, layer_id) {
+			return Ok(None);
+		}
+
+		let pos = match self.resolve_bounds(pos, map_id, layer_id) {
+			Ok(pos) => pos,
+			Err(TilePlacementError::OutOfBounds { .. })
+				if self.policy().out_of_bounds == PolicyAction::Skip =>
+			{
+				return Ok(None);
+			},
+			Err(err) => return Err(err),
+		};
+
+		if self.check_placement_mask(pos, map_id, layer_id)? {
+			Ok(Some(pos))
+		} else {
+			Ok(None)
+		}
+	}
+}
@@ -0,0 +1,218 @@
+//! Tools for reusing tile entities
+
+use bevy::ecs::bundle::Bundle;
+use bevy::prelude::{Commands, DespawnRecursiveExt, Entity, ResMut, Resource};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::TilesetParent;
+
+use crate::color::{FogTint, LightTint, TileTint};
+use crate::placement::TilePlacer;
+
+/// Statistics about a [`TilePool`], useful for tuning its capacity
+///
+/// These are counted from the moment the pool is created (or last
+/// [reset](TilePool::reset_stats)).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats {
+	/// The number of placed tiles that reused a pooled entity
+	pub reused: u64,
+	/// The number of placed tiles that spawned a new entity since the pool was empty
+	pub spawned: u64,
+	/// The number of removed tiles whose entity was kept in the pool
+	pub recycled: u64,
+	/// The number of removed tiles that were despawned since the pool was full
+	pub despawned: u64,
+}
+
+impl PoolStats {
+	/// The fraction of placed tiles that reused a pooled entity, from `0.0` to `1.0`
+	pub fn hit_rate(&self) -> f32 {
+		let total = self.reused + self.spawned;
+		if total == 0 {
+			return 0.0;
+		}
+		self.reused as f32 / total as f32
+	}
+}
+
+/// A resource keeping the entities of removed tiles around so they can be reused by later
+/// placements
+///
+/// Placing and removing the same tiles over and over (such as while dragging a brush or running
+/// a water simulation) otherwise spawns and despawns an entity for every change. Pooled entities
+/// lose every component added by this crate (including their [`TilePropertyComponents`]), so
+/// they aren't drawn or picked up by other systems, but keep everything else, so tiles with
+/// components added outside this crate may carry them over.
+///
+/// Removed entities are only reused from the next frame on, so systems reacting to removed
+/// components see the removal before the entity becomes a tile again.
+///
+/// Pooling is disabled unless this resource is inserted.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{App, Res};
+/// # use bevy_tileset_map::prelude::TilePool;
+/// # let mut app = App::new();
+/// app.insert_resource(TilePool::new(1024));
+///
+/// fn log_pool(pool: Res<TilePool>) {
+///   println!("{} pooled, {:.0}% reused", pool.len(), pool.stats().hit_rate() * 100.0);
+/// }
+/// ```
+///
+/// [`TilePropertyComponents`]: crate::prelude::TilePropertyComponents
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TilePool {
+	capacity: usize,
+	free: Vec<Entity>,
+	/// The entities recycled during this frame, which can't be reused until the next one
+	recycled: Vec<Entity>,
+	evicted: Vec<Entity>,
+	stats: PoolStats,
+}
+
+impl TilePool {
+	/// Create a pool holding up to `capacity` entities
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity,
+			free: Vec::with_capacity(capacity),
+			recycled: Vec::new(),
+			evicted: Vec::new(),
+			stats: PoolStats::default(),
+		}
+	}
+
+	/// The maximum number of entities kept in the pool
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Change the maximum number of entities kept in the pool
+	///
+	/// Any pooled entities beyond the new capacity are despawned.
+	pub fn set_capacity(&mut self, capacity: usize) {
+		self.capacity = capacity;
+		let excess = self.len().saturating_sub(capacity);
+		let from_recycled = excess.min(self.recycled.len());
+		let from_free = excess - from_recycled;
+		self.evicted
+			.extend(self.recycled.drain(self.recycled.len() - from_recycled..));
+		self.evicted
+			.extend(self.free.drain(self.free.len() - from_free..));
+	}
+
+	/// The number of entities currently in the pool
+	///
+	/// This includes the entities recycled during this frame, which can't be reused yet.
+	pub fn len(&self) -> usize {
+		self.free.len() + self.recycled.len()
+	}
+
+	/// Returns true if the pool contains no entities
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Despawn every pooled entity
+	pub fn clear(&mut self) {
+		self.evicted.append(&mut self.free);
+		self.evicted.append(&mut self.recycled);
+	}
+
+	/// Get the statistics of this pool
+	pub fn stats(&self) -> PoolStats {
+		self.stats
+	}
+
+	/// Reset the statistics of this pool
+	pub fn reset_stats(&mut self) {
+		self.stats = PoolStats::default();
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Spawn a tile entity with the given components, reusing a pooled entity if possible
+	pub(crate) fn spawn_pooled<B: Bundle>(&mut self, bundle: B) -> Entity {
+		let entity = match self.pool.as_mut() {
+			Some(pool) => match pool.free.pop() {
+				Some(entity) => {
+					pool.stats.reused += 1;
+					Some(entity)
+				},
+				None => {
+					pool.stats.spawned += 1;
+					None
+				},
+			},
+			None => None,
+		};
+
+		match entity {
+			Some(entity) => {
+				self.commands.entity(entity).insert(bundle);
+				entity
+			},
+			None => self.commands.spawn(bundle).id(),
+		}
+	}
+
+	/// Despawn the given tile entity, or keep it in the [`TilePool`] if there's room
+	///
+	/// The tile should already be removed from its [`TileStorage`].
+	pub(crate) fn despawn_pooled(&mut self, entity: Entity) {
+		let pool = match self.pool.as_mut() {
+			Some(pool) => pool,
+			None => {
+				self.commands.entity(entity).despawn_recursive();
+				return;
+			},
+		};
+
+		if pool.len() >= pool.capacity {
+			pool.stats.despawned += 1;
+			self.commands.entity(entity).despawn_recursive();
+			return;
+		}
+
+		pool.stats.recycled += 1;
+		pool.recycled.push(entity);
+		let mut cmds = self.commands.entity(entity);
+		cmds.remove::<(TileBundle, TileParent, TilesetParent)>();
+		cmds.remove::<(AnimatedTile, crate::animation::PausedAnimation)>();
+		cmds.remove::<(TileTint, LightTint, FogTint)>();
+		#[cfg(feature = "auto-tile")]
+		cmds.remove::<bevy_tileset::auto::AutoTileId>()
+			.remove::<crate::auto::ResolvedAutoTile>();
+		self.property_components.remove_all(&mut cmds);
+	}
+}
+
+/// __\[SYSTEM\]__ Despawns the entities evicted from the [`TilePool`] and makes the entities
+/// recycled during this frame available for reuse
+///
+/// This runs at the end of the frame so recycled entities are never reused within the frame
+/// they were removed in.
+pub(crate) fn trim_tile_pool(pool: Option<ResMut<TilePool>>, mut commands: Commands) {
+	let mut pool = match pool {
+		Some(pool) => pool,
+		None => return,
+	};
+
+	if !pool.recycled.is_empty() {
+		let pool = &mut *pool;
+		pool.free.append(&mut pool.recycled);
+	}
+
+	if pool.evicted.is_empty() {
+		return;
+	}
+
+	let evicted = std::mem::take(&mut pool.evicted);
+	pool.stats.despawned += evicted.len() as u64;
+	for entity in evicted {
+		commands.entity(entity).despawn_recursive();
+	}
+}
@@ -0,0 +1,162 @@
+//! Tools for reading placed tiles
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Entity, Query};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileId, TilesetId, TilesetParent, Tilesets};
+
+use crate::manager::TilemapLayer;
+use crate::placement::MapId;
+
+/// A read-only system param used to query placed tiles
+///
+/// Tiles are resolved back to their [`TileId`] (and name) using their tileset, allowing you to
+/// ask what tile exists at a given position.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::TileReader;
+/// fn inspect_tile(reader: TileReader) {
+///   if let Some(name) = reader.get_tile_name(TilePos { x: 0, y: 0 }, 0u16, 0) {
+///     println!("Found tile: {}", name);
+///   }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct TileReader<'w, 's> {
+	tiles: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static TilePos,
+			&'static TileParent,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+		),
+	>,
+	storages: Query<'w, 's, (&'static TilemapLayer, &'static TileStorage)>,
+	tilesets: Tilesets<'w, 's>,
+}
+
+impl<'w, 's> TileReader<'w, 's> {
+	/// Get the tile entity at the given position
+	pub fn get_entity<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<Entity> {
+		let map_id = map_id.into();
+		let (_, storage) = self
+			.storages
+			.iter()
+			.find(|(layer, _)| layer.map_id == map_id && layer.layer_id == layer_id)?;
+		storage
+			.checked_get(&pos.into())
+			.filter(|entity| self.tiles.contains(*entity))
+	}
+
+	/// Get the ID of the tile at the given position
+	pub fn get_tile_id<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<TileId> {
+		self.resolve(self.get_entity(pos, map_id, layer_id)?)
+	}
+
+	/// Get the name of the tile at the given position
+	pub fn get_tile_name<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<&str> {
+		let id = self.get_tile_id(pos, map_id, layer_id)?;
+		let tileset = self.tilesets.get_by_id(&id.tileset_id)?;
+		tileset.get_tile_name(&id.group_id)
+	}
+
+	/// Get the ID of the given tile entity
+	pub fn resolve(&self, entity: Entity) -> Option<TileId> {
+		let (.., index, tileset) = self.tiles.get(entity).ok()?;
+		self.resolve_index(index, tileset)
+	}
+
+	/// Iterate over all tiles in the given layer of a map
+	///
+	/// Tiles that can't be resolved to a [`TileId`] (such as those belonging to an unloaded
+	/// tileset) are skipped.
+	pub fn iter_layer<MId: MapId>(
+		&self,
+		map_id: MId,
+		layer_id: u16,
+	) -> impl Iterator<Item = (Entity, TilePos, TileId)> + '_ {
+		let map_id = map_id.into();
+		self.tiles
+			.iter()
+			.filter(move |(_, _, parent, ..)| {
+				parent.map_id == map_id && parent.layer_id == layer_id
+			})
+			.filter_map(|(entity, pos, _, index, tileset)| {
+				Some((entity, *pos, self.resolve_index(index, tileset)?))
+			})
+	}
+
+	/// Iterate over all tiles of the given tile group in the given layer of a map
+	///
+	/// Only the tileset and group of the given [`TileId`] are compared, so every variant (and auto
+	/// tile) of the group is included.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_tileset_map::prelude::{TileId, TileReader};
+	/// fn find_grass(reader: TileReader) {
+	///   # let grass = TileId::new(0, 0);
+	///   for (entity, pos, _) in reader.iter_group(grass, 0u16, 0) {
+	///     println!("Grass at {:?} ({:?})", pos, entity);
+	///   }
+	/// }
+	/// ```
+	pub fn iter_group<Id: Into<TileId>, MId: MapId>(
+		&self,
+		tile_id: Id,
+		map_id: MId,
+		layer_id: u16,
+	) -> impl Iterator<Item = (Entity, TilePos, TileId)> + '_ {
+		let tile_id = tile_id.into();
+		self.iter_by_tileset(tile_id.tileset_id, map_id, layer_id)
+			.filter(move |(.., id)| id.eq_tile_group(&tile_id))
+	}
+
+	/// Iterate over all tiles of the given tileset in the given layer of a map
+	///
+	/// Tiles of other tilesets are skipped without resolving their [`TileId`].
+	pub fn iter_by_tileset<MId: MapId>(
+		&self,
+		tileset_id: TilesetId,
+		map_id: MId,
+		layer_id: u16,
+	) -> impl Iterator<Item = (Entity, TilePos, TileId)> + '_ {
+		let map_id = map_id.into();
+		self.tiles
+			.iter()
+			.filter(move |(_, _, parent, _, tileset)| {
+				tileset.0 == tileset_id && parent.map_id == map_id && parent.layer_id == layer_id
+			})
+			.filter_map(|(entity, pos, _, index, tileset)| {
+				Some((entity, *pos, self.resolve_index(index, tileset)?))
+			})
+	}
+
+	/// Get the ID of a tile from its texture index and tileset
+	fn resolve_index(&self, index: &TileTextureIndex, tileset: &TilesetParent) -> Option<TileId> {
+		let tileset = self.tilesets.get_by_id(&tileset.0)?;
+		tileset.get_tile_id(&(index.0 as usize)).copied()
+	}
+}
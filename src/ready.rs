@@ -0,0 +1,89 @@
+//! Tools for waiting on tilesets to finish loading
+
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::{App, AssetServer, Assets, Handle, Res, Resource};
+use bevy_tileset::prelude::Tileset;
+
+/// A resource containing the tilesets that must be loaded before [`tilesets_ready`] lets
+/// systems run
+///
+/// Tilesets can be added using [`AddRequiredTileset::require_tileset`], which also starts
+/// loading them, or by adding existing handles directly.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RequiredTilesets {
+	handles: Vec<Handle<Tileset>>,
+}
+
+impl RequiredTilesets {
+	/// Require the given tileset to be loaded
+	pub fn add(&mut self, handle: Handle<Tileset>) -> &mut Self {
+		self.handles.push(handle);
+		self
+	}
+
+	/// Iterate over the handles of all required tilesets
+	pub fn iter(&self) -> impl Iterator<Item = &Handle<Tileset>> {
+		self.handles.iter()
+	}
+
+	/// Returns true if every required tileset has been loaded
+	pub fn is_ready(&self, tilesets: &Assets<Tileset>) -> bool {
+		self.handles.iter().all(|handle| tilesets.contains(handle))
+	}
+}
+
+/// A helper trait for loading the tilesets required by an app
+pub trait AddRequiredTileset {
+	/// Start loading the tileset at the given path and add it to the [`RequiredTilesets`]
+	///
+	/// The `AssetPlugin` must be added before calling this.
+	fn require_tileset(&mut self, path: &str) -> &mut Self;
+}
+
+impl AddRequiredTileset for App {
+	fn require_tileset(&mut self, path: &str) -> &mut Self {
+		let handle = self.world.resource::<AssetServer>().load(path);
+		self.init_resource::<RequiredTilesets>();
+		self.world.resource_mut::<RequiredTilesets>().add(handle);
+		self
+	}
+}
+
+/// Create run criteria that only runs systems once every tileset in the [`RequiredTilesets`]
+/// has finished loading
+///
+/// If no tilesets are required, the systems run as soon as any tileset has been loaded.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{App, SystemSet};
+/// # use bevy_tileset_map::prelude::{tilesets_ready, AddRequiredTileset, TilePlacer};
+/// fn build_level(mut placer: TilePlacer) {
+///   // ...
+/// }
+///
+/// # let mut app = App::new();
+/// # app.add_plugins(bevy::MinimalPlugins).add_plugin(bevy::asset::AssetPlugin::default());
+/// app.require_tileset("tilesets/terrain.ron")
+///   .require_tileset("tilesets/props.ron")
+///   .add_system_set(
+///     SystemSet::new()
+///       .with_run_criteria(tilesets_ready())
+///       .with_system(build_level),
+///   );
+/// ```
+pub fn tilesets_ready(
+) -> impl FnMut(Option<Res<RequiredTilesets>>, Res<Assets<Tileset>>) -> ShouldRun {
+	|required: Option<Res<RequiredTilesets>>, tilesets: Res<Assets<Tileset>>| {
+		let is_ready = match required {
+			Some(required) if !required.handles.is_empty() => required.is_ready(&tilesets),
+			_ => !tilesets.is_empty(),
+		};
+		if is_ready {
+			ShouldRun::Yes
+		} else {
+			ShouldRun::No
+		}
+	}
+}
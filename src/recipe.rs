@@ -0,0 +1,236 @@
+//! Tools for placing a single logical tile across multiple layers
+
+use bevy::prelude::{App, Resource};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+
+/// The layer a part of a [`TileRecipe`] is placed on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RecipeLayer {
+	/// The layer with the given ID
+	Id(u16),
+	/// The layer with the given name (see [`MapLayers`](crate::prelude::MapLayers))
+	Named(String),
+}
+
+impl From<u16> for RecipeLayer {
+	fn from(layer_id: u16) -> Self {
+		Self::Id(layer_id)
+	}
+}
+
+impl From<&str> for RecipeLayer {
+	fn from(name: &str) -> Self {
+		Self::Named(name.to_string())
+	}
+}
+
+/// A set of tiles placed together on different layers of the same cell
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{TileId, TileRecipe};
+/// # let (door, blocker) = (TileId::new(0, 0), TileId::new(0, 1));
+/// let recipe = TileRecipe::default()
+///   .with_tile("props", door)
+///   .with_tile("collision", blocker);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TileRecipe {
+	parts: Vec<(RecipeLayer, TileId)>,
+}
+
+impl TileRecipe {
+	/// Add a tile placed on the given layer
+	pub fn with_tile<L: Into<RecipeLayer>, Id: Into<TileId>>(
+		mut self,
+		layer: L,
+		tile_id: Id,
+	) -> Self {
+		self.parts.push((layer.into(), tile_id.into()));
+		self
+	}
+
+	/// Iterate over the tiles of this recipe, along with their layer
+	pub fn iter(&self) -> impl Iterator<Item = (&RecipeLayer, &TileId)> {
+		self.parts.iter().map(|(layer, tile_id)| (layer, tile_id))
+	}
+}
+
+/// A resource containing the registered [`TileRecipe`]s, by name
+///
+/// Recipes map one logical tile to placements on multiple layers, such as a door that places the
+/// door tile on the props layer along with a blocker tile on the collision layer. They're placed
+/// and removed as a whole using [`TilePlacer::place_recipe`] and [`TilePlacer::remove_recipe`].
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileRecipes {
+	recipes: HashMap<String, TileRecipe>,
+}
+
+impl TileRecipes {
+	/// Register a recipe, replacing any recipe previously registered with the same name
+	pub fn add(&mut self, name: &str, recipe: TileRecipe) -> &mut Self {
+		self.recipes.insert(name.to_string(), recipe);
+		self
+	}
+
+	/// Get the recipe with the given name
+	pub fn get(&self, name: &str) -> Option<&TileRecipe> {
+		self.recipes.get(name)
+	}
+
+	/// Remove the recipe with the given name, returning it (if any)
+	pub fn remove(&mut self, name: &str) -> Option<TileRecipe> {
+		self.recipes.remove(name)
+	}
+}
+
+/// A helper trait for registering tile recipes
+pub trait AddTileRecipe {
+	/// Register a recipe with the [`TileRecipes`] resource
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::prelude::App;
+	/// # use bevy_tileset_map::prelude::{AddTileRecipe, TileId, TileRecipe};
+	/// # let mut app = App::new();
+	/// # let (door, blocker) = (TileId::new(0, 0), TileId::new(0, 1));
+	/// app.add_tile_recipe(
+	///   "door",
+	///   TileRecipe::default()
+	///     .with_tile("props", door)
+	///     .with_tile("collision", blocker),
+	/// );
+	/// ```
+	fn add_tile_recipe(&mut self, name: &str, recipe: TileRecipe) -> &mut Self;
+}
+
+impl AddTileRecipe for App {
+	fn add_tile_recipe(&mut self, name: &str, recipe: TileRecipe) -> &mut Self {
+		self.init_resource::<TileRecipes>();
+		self.world.resource_mut::<TileRecipes>().add(name, recipe);
+		self
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Place every tile of the named [`TileRecipe`] at the given position
+	///
+	/// The recipe is placed atomically: every tile is validated before any are placed, so an
+	/// invalid tile results in an error without modifying the map. If any tile would be skipped
+	/// (see [`PlacementPolicy`](crate::prelude::PlacementPolicy)), the whole recipe is skipped.
+	///
+	/// Returns the results of each placed tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::TilePlacer;
+	/// fn place_door(mut placer: TilePlacer) {
+	///   placer.place_recipe("door", TilePos { x: 6, y: 2 }, 0u16).unwrap();
+	/// }
+	/// ```
+	pub fn place_recipe<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		name: &str,
+		pos: Pos,
+		map_id: MId,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let pos = pos.into();
+		let map_id = map_id.into();
+		let parts = self.recipe_parts(name, map_id)?;
+
+		// === Validate === //
+		let mut skipped = false;
+		for (layer_id, tile_id) in &parts {
+			if self.can_place(tile_id, pos, map_id, *layer_id, None)?.is_none() {
+				skipped = true;
+			}
+		}
+		if skipped {
+			return Ok(vec![PlacedTile::Skipped; parts.len()]);
+		}
+
+		// === Place === //
+		let mut placed = Vec::with_capacity(parts.len());
+		for (layer_id, tile_id) in parts {
+			placed.push(self.place_unchecked(tile_id, pos, map_id, layer_id)?);
+		}
+		Ok(placed)
+	}
+
+	/// Remove every tile of the named [`TileRecipe`] at the given position
+	///
+	/// Only tiles belonging to the same tile group as their part of the recipe are removed, so
+	/// unrelated tiles on the same layers are left untouched. Like
+	/// [`place_recipe`](Self::place_recipe), either every tile is removed or none are.
+	///
+	/// Returns the results of each removed tile.
+	pub fn remove_recipe<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		name: &str,
+		pos: Pos,
+		map_id: MId,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let pos = pos.into();
+		let map_id = map_id.into();
+		let parts = self.recipe_parts(name, map_id)?;
+
+		// === Validate === //
+		let mut matching = Vec::with_capacity(parts.len());
+		for (layer_id, tile_id) in parts {
+			if !self.check_placement_mask(pos, map_id, layer_id)? {
+				return Ok(Vec::new());
+			}
+
+			let existing = self.find_entity(pos, map_id, layer_id).and_then(|entity| {
+				let existing = self.tile_id_of(entity)?;
+				existing
+					.eq_tile_group(&tile_id)
+					.then_some((entity, existing))
+			});
+			if let Some(existing) = existing {
+				matching.push((layer_id, existing));
+			}
+		}
+
+		// === Remove === //
+		let mut removed = Vec::with_capacity(matching.len());
+		for (layer_id, (entity, tile_id)) in matching {
+			self.remove(pos, map_id, layer_id)?;
+			removed.push(PlacedTile::Removed {
+				old_tile: Some((entity, Some(tile_id))),
+			});
+		}
+		Ok(removed)
+	}
+
+	/// Get the tiles of the named recipe, along with the ID of their layer in the given map
+	fn recipe_parts(
+		&self,
+		name: &str,
+		map_id: u16,
+	) -> Result<Vec<(u16, TileId)>, TilePlacementError> {
+		let recipe = self
+			.recipes
+			.get(name)
+			.ok_or_else(|| TilePlacementError::UnknownRecipe(name.to_string()))?;
+
+		recipe
+			.iter()
+			.map(|(layer, tile_id)| {
+				let layer_id = match layer {
+					RecipeLayer::Id(layer_id) => *layer_id,
+					RecipeLayer::Named(name) => self.layer_id(map_id, name)?,
+				};
+				Ok((layer_id, *tile_id))
+			})
+			.collect()
+	}
+}
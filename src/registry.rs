@@ -0,0 +1,146 @@
+//! Tools for referring to maps by name rather than by raw ID
+
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use thiserror::Error;
+
+use crate::placement::MapId;
+
+/// Errors related to registering maps in the [`TilemapRegistry`]
+#[derive(Error, Debug)]
+pub enum TilemapRegistryError {
+	/// The ID is already registered under another name
+	#[error("Cannot register map {name:?} as {map_id}, which is already used by {existing:?}")]
+	DuplicateId {
+		/// The name of the map being registered
+		name: String,
+		/// The name the ID is already registered under
+		existing: String,
+		/// The requested ID
+		map_id: u16,
+	},
+	/// Every map ID is already registered
+	#[error("Cannot register map {0:?}, every map ID is already in use")]
+	Exhausted(String),
+}
+
+/// A strongly typed handle to a map registered in the [`TilemapRegistry`]
+///
+/// Handles can only be created by the registry, so they can't be mixed up with other `u16`
+/// values. They can be used anywhere a [`MapId`] is accepted, such as the [`TilePlacer`].
+///
+/// [`TilePlacer`]: crate::prelude::TilePlacer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MapHandle(u16);
+
+impl MapHandle {
+	/// Get the raw ID of the map
+	pub fn id(&self) -> u16 {
+		self.0
+	}
+}
+
+impl From<MapHandle> for u16 {
+	fn from(handle: MapHandle) -> Self {
+		handle.0
+	}
+}
+
+impl MapId for MapHandle {}
+
+/// A resource used to register maps by name
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::TilemapRegistry;
+/// let mut registry = TilemapRegistry::default();
+/// let overworld = registry.register("overworld").unwrap();
+/// let dungeon = registry.register("dungeon").unwrap();
+///
+/// assert_ne!(overworld, dungeon);
+/// assert_eq!(Some(overworld), registry.get("overworld"));
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct TilemapRegistry {
+	maps: HashMap<String, MapHandle>,
+	/// The name each registered ID is registered under
+	names: HashMap<MapHandle, String>,
+	next_id: u16,
+}
+
+impl TilemapRegistry {
+	/// Register a map with the given name, assigning it an unused ID
+	///
+	/// If a map with that name is already registered, its existing handle is returned. Fails if
+	/// every ID is already in use.
+	pub fn register(&mut self, name: &str) -> Result<MapHandle, TilemapRegistryError> {
+		if let Some(handle) = self.get(name) {
+			return Ok(handle);
+		}
+		if self.names.len() > u16::MAX as usize {
+			return Err(TilemapRegistryError::Exhausted(name.to_string()));
+		}
+
+		while self.names.contains_key(&MapHandle(self.next_id)) {
+			self.next_id = self.next_id.wrapping_add(1);
+		}
+		self.register_id(name, self.next_id)
+	}
+
+	/// Register a map with the given name and raw ID
+	///
+	/// This is useful for maps that were created with a raw ID (such as loaded maps). Any map
+	/// previously registered under this name is replaced. Fails if the ID is already registered
+	/// under another name.
+	pub fn register_id(
+		&mut self,
+		name: &str,
+		map_id: u16,
+	) -> Result<MapHandle, TilemapRegistryError> {
+		let handle = MapHandle(map_id);
+		if let Some(existing) = self.names.get(&handle) {
+			if existing != name {
+				return Err(TilemapRegistryError::DuplicateId {
+					name: name.to_string(),
+					existing: existing.clone(),
+					map_id,
+				});
+			}
+		}
+
+		if let Some(previous) = self.maps.insert(name.to_string(), handle) {
+			self.names.remove(&previous);
+		}
+		self.names.insert(handle, name.to_string());
+		Ok(handle)
+	}
+
+	/// Unregister the map with the given name
+	///
+	/// Returns the handle of the removed map
+	pub fn remove(&mut self, name: &str) -> Option<MapHandle> {
+		let handle = self.maps.remove(name)?;
+		self.names.remove(&handle);
+		Some(handle)
+	}
+
+	/// Get the handle of the map with the given name
+	pub fn get(&self, name: &str) -> Option<MapHandle> {
+		self.maps.get(name).copied()
+	}
+
+	/// Get the name of the given map
+	pub fn name_of<MId: MapId>(&self, map_id: MId) -> Option<&str> {
+		self.names
+			.get(&MapHandle(map_id.into()))
+			.map(|name| name.as_str())
+	}
+
+	/// Iterate over the names and handles of all registered maps
+	pub fn iter(&self) -> impl Iterator<Item = (&str, MapHandle)> {
+		self.maps
+			.iter()
+			.map(|(name, handle)| (name.as_str(), *handle))
+	}
+}
@@ -0,0 +1,183 @@
+//! Tools for keeping placed tiles in sync with hot-reloaded tilesets
+
+use bevy::asset::HandleId;
+use bevy::log::warn;
+use bevy::prelude::{
+	AssetEvent, Assets, Commands, Entity, EventReader, Handle, Image, Local, Query, Res,
+	TextureAtlas,
+};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileId, TileIndex, Tileset, TilesetId, TilesetParent, Tilesets};
+
+/// The tiles of a tileset as they were before it was modified
+pub(crate) struct TilesetSnapshot {
+	id: TilesetId,
+	texture: Handle<Image>,
+	/// The tile (and its name) found at each texture index
+	tiles: HashMap<u32, (TileId, String)>,
+}
+
+impl TilesetSnapshot {
+	/// Take a snapshot of the given tileset
+	///
+	/// Returns `None` if the tileset's atlas has not been loaded.
+	fn new(tileset: &Tileset, atlases: &Assets<TextureAtlas>) -> Option<Self> {
+		let atlas = atlases.get(tileset.atlas())?;
+		let tiles = (0..atlas.textures.len())
+			.filter_map(|index| {
+				let tile_id = *tileset.get_tile_id(&index)?;
+				let name = tileset.get_tile_name(&tile_id.group_id)?.clone();
+				Some((index as u32, (tile_id, name)))
+			})
+			.collect();
+
+		Some(Self {
+			id: *tileset.id(),
+			texture: tileset.texture().clone(),
+			tiles,
+		})
+	}
+
+	/// Find the index in the new tileset for a tile previously at `index`
+	///
+	/// Tiles are matched by name first, falling back to their group ID so renamed tiles are still
+	/// found as long as their ID didn't change.
+	fn remap(&self, index: u32, tileset: &Tileset) -> Option<(TileId, TileIndex)> {
+		let (old_id, name) = self.tiles.get(&index)?;
+
+		let by_name = tileset.get_tile_group_id(name).and_then(|group_id| {
+			let new_id = TileId {
+				group_id: *group_id,
+				tileset_id: *tileset.id(),
+				..*old_id
+			};
+			let index = tileset
+				.get_tile_index_by_id(new_id)
+				.or_else(|| tileset.get_tile_index(name))?;
+			Some((new_id, index))
+		});
+
+		by_name.or_else(|| {
+			let new_id = TileId {
+				tileset_id: *tileset.id(),
+				..*old_id
+			};
+			Some((new_id, tileset.get_tile_index_by_id(new_id)?))
+		})
+	}
+}
+
+/// __\[SYSTEM\]__ Re-resolves the texture index of placed tiles when their tileset is modified
+///
+/// A snapshot of every loaded tileset is kept so that, when a tileset is hot-reloaded, each tile
+/// referencing it can be matched to its new texture index (see [`TilesetSnapshot::remap`]).
+/// Tiles that no longer exist in the tileset are left untouched and a warning is logged.
+pub(crate) fn reload_tilesets(
+	mut commands: Commands,
+	mut events: EventReader<AssetEvent<Tileset>>,
+	mut snapshots: Local<HashMap<HandleId, TilesetSnapshot>>,
+	atlases: Res<Assets<TextureAtlas>>,
+	tilesets: Tilesets,
+	#[cfg(not(feature = "auto-tile"))] tiles: Query<(Entity, &TileTextureIndex, &TilesetParent)>,
+	#[cfg(feature = "auto-tile")] tiles: Query<(
+		Entity,
+		&TileTextureIndex,
+		&TilesetParent,
+		Option<&bevy_tileset::auto::AutoTileId>,
+	)>,
+	mut tilemaps: Query<&mut TilemapTexture>,
+) {
+	for event in events.iter() {
+		match event {
+			AssetEvent::Created { handle } => {
+				if let Some(snapshot) = tilesets
+					.get(handle)
+					.and_then(|tileset| TilesetSnapshot::new(tileset, &atlases))
+				{
+					snapshots.insert(handle.id(), snapshot);
+				}
+			},
+			AssetEvent::Modified { handle } => {
+				let tileset = match tilesets.get(handle) {
+					Some(tileset) => tileset,
+					None => continue,
+				};
+
+				if let Some(old) = snapshots.get(&handle.id()) {
+					// === Tilemaps === //
+					if old.texture != *tileset.texture() {
+						for mut texture in tilemaps.iter_mut() {
+							if let TilemapTexture::Single(current) = &mut *texture {
+								if *current == old.texture {
+									*current = tileset.texture().clone();
+								}
+							}
+						}
+					}
+
+					// === Tiles === //
+					let mut missing = 0;
+					for item in tiles.iter() {
+						#[cfg(not(feature = "auto-tile"))]
+						let (entity, index, parent) = item;
+						#[cfg(feature = "auto-tile")]
+						let (entity, index, parent, auto_id) = item;
+
+						if parent.0 != old.id {
+							continue;
+						}
+
+						#[cfg_attr(not(feature = "auto-tile"), allow(unused_variables))]
+						let (new_id, new_index) = match old.remap(index.0, tileset) {
+							Some(remapped) => remapped,
+							None => {
+								missing += 1;
+								continue;
+							},
+						};
+
+						let mut cmds = commands.entity(entity);
+						cmds.insert(TilesetParent(*tileset.id()));
+						match new_index {
+							TileIndex::Standard(index) => {
+								cmds.insert(TileTextureIndex(index as u32))
+									.remove::<AnimatedTile>();
+							},
+							TileIndex::Animated(start, end, speed) => {
+								cmds.insert((
+									TileTextureIndex(start as u32),
+									AnimatedTile {
+										start: start as u32,
+										end: end as u32,
+										speed,
+									},
+								));
+							},
+						}
+
+						#[cfg(feature = "auto-tile")]
+						if auto_id.is_some() {
+							cmds.insert(bevy_tileset::auto::AutoTileId::from(new_id));
+						}
+					}
+
+					if missing > 0 {
+						warn!(
+							"{} placed tile(s) could not be found in the reloaded tileset {:?}",
+							missing,
+							tileset.name()
+						);
+					}
+				}
+
+				if let Some(snapshot) = TilesetSnapshot::new(tileset, &atlases) {
+					snapshots.insert(handle.id(), snapshot);
+				}
+			},
+			AssetEvent::Removed { handle } => {
+				snapshots.remove(&handle.id());
+			},
+		}
+	}
+}
@@ -0,0 +1,188 @@
+//! Tools for replaying recorded tile edits over time
+
+use bevy::log::warn;
+use bevy::prelude::{Res, ResMut, Resource, Time};
+
+use crate::changes::{TileChange, TileChangeLog};
+use crate::placement::TilePlacer;
+
+/// A resource replaying a sequence of recorded [`TileChange`]s over time
+///
+/// Changes are replayed one step at a time, where a step is every change made by the same system
+/// run (such as a single brush stroke). The replay advances by [`speed`](Self::speed) steps per
+/// second, and can be paused or seeked at any time: seeking backwards reverts the replayed
+/// changes, while seeking forwards applies them all at once.
+///
+/// Start a replay with [`TilesetCommandsExt::replay`] (or by inserting this resource). Note that
+/// replayed changes are themselves recorded if a [`TileChangeLog`] exists.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{Input, KeyCode, Res, ResMut};
+/// # use bevy_tileset_map::prelude::TileReplay;
+/// fn replay_controls(keys: Res<Input<KeyCode>>, mut replay: ResMut<TileReplay>) {
+///   if keys.just_pressed(KeyCode::Space) {
+///     replay.toggle();
+///   }
+///   if keys.just_pressed(KeyCode::Home) {
+///     replay.seek(0);
+///   }
+/// }
+/// ```
+///
+/// [`TilesetCommandsExt::replay`]: crate::prelude::TilesetCommandsExt::replay
+#[derive(Resource, Debug, Clone)]
+pub struct TileReplay {
+	changes: Vec<TileChange>,
+	/// The number of changes currently applied
+	applied: usize,
+	/// The position requested by [`seek`](Self::seek), if any
+	target: Option<usize>,
+	speed: f32,
+	paused: bool,
+	/// The number of steps due since the last one was applied
+	elapsed: f32,
+}
+
+impl TileReplay {
+	/// Replay the given changes, advancing by `speed` steps per second
+	///
+	/// None of the changes are assumed to be applied yet.
+	pub fn new<I: IntoIterator<Item = TileChange>>(changes: I, speed: f32) -> Self {
+		Self {
+			changes: changes.into_iter().collect(),
+			applied: 0,
+			target: None,
+			speed,
+			paused: false,
+			elapsed: 0.0,
+		}
+	}
+
+	/// Replay every change recorded in the given log
+	pub fn from_log(log: &TileChangeLog, speed: f32) -> Self {
+		Self::new(log.iter().copied(), speed)
+	}
+
+	/// The number of steps replayed per second
+	pub fn speed(&self) -> f32 {
+		self.speed
+	}
+
+	/// Set the number of steps replayed per second
+	pub fn set_speed(&mut self, speed: f32) {
+		self.speed = speed;
+	}
+
+	/// Pause the replay
+	pub fn pause(&mut self) {
+		self.paused = true;
+	}
+
+	/// Resume the replay
+	pub fn resume(&mut self) {
+		self.paused = false;
+	}
+
+	/// Pause the replay if it's running, or resume it if it's paused
+	pub fn toggle(&mut self) {
+		self.paused = !self.paused;
+	}
+
+	/// Returns true if the replay is paused
+	pub fn is_paused(&self) -> bool {
+		self.paused
+	}
+
+	/// Jump to the given position, which is the number of changes applied
+	///
+	/// The position is clamped to the number of changes, and is reached during the next update.
+	pub fn seek(&mut self, position: usize) {
+		self.target = Some(position.min(self.changes.len()));
+	}
+
+	/// The number of changes applied so far (or about to be, if seeking)
+	pub fn position(&self) -> usize {
+		self.target.unwrap_or(self.applied)
+	}
+
+	/// The replayed changes
+	pub fn changes(&self) -> &[TileChange] {
+		&self.changes
+	}
+
+	/// The total number of changes to replay
+	pub fn len(&self) -> usize {
+		self.changes.len()
+	}
+
+	/// Returns true if there are no changes to replay
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Returns true if every change has been replayed
+	pub fn is_finished(&self) -> bool {
+		self.position() >= self.changes.len()
+	}
+
+	/// Get the position after the step starting at the given one
+	fn step_end(&self, start: usize) -> usize {
+		let tick = match self.changes.get(start) {
+			Some(change) => change.tick,
+			None => return start,
+		};
+		self.changes[start..]
+			.iter()
+			.position(|change| change.tick != tick)
+			.map_or(self.changes.len(), |len| start + len)
+	}
+}
+
+/// __\[SYSTEM\]__ Advances the [`TileReplay`], applying or reverting its changes
+pub(crate) fn update_tile_replay(
+	replay: Option<ResMut<TileReplay>>,
+	mut placer: TilePlacer,
+	time: Res<Time>,
+) {
+	let mut replay = match replay {
+		Some(replay) => replay,
+		None => return,
+	};
+
+	// === Seek === //
+	if let Some(target) = replay.target.take() {
+		let applied = replay.applied;
+		let result = if target < applied {
+			placer.revert_changes(&replay.changes[target..applied])
+		} else {
+			placer.apply_changes(&replay.changes[applied..target])
+		};
+		if let Err(err) = result {
+			warn!("Failed to seek tile replay: {}", err);
+		}
+		replay.applied = target;
+		replay.elapsed = 0.0;
+	}
+
+	if replay.paused || replay.is_finished() {
+		return;
+	}
+
+	// === Advance === //
+	replay.elapsed += time.delta_seconds() * replay.speed;
+	while replay.elapsed >= 1.0 && !replay.is_finished() {
+		replay.elapsed -= 1.0;
+		let start = replay.applied;
+		let end = replay.step_end(start);
+		if let Err(err) = placer.apply_changes(&replay.changes[start..end]) {
+			warn!("Failed to replay tile changes: {}", err);
+		}
+		replay.applied = end;
+	}
+
+	if replay.is_finished() {
+		replay.elapsed = 0.0;
+	}
+}
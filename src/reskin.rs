@@ -0,0 +1,200 @@
+//! Tools for swapping the tileset used by a map at runtime
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Commands, Entity, Query};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TileId, TileIndex, TilesetId, TilesetParent, Tilesets};
+
+use crate::manager::TilemapLayer;
+use crate::placement::{MapId, TilePlacementError};
+
+/// A system param used to swap the tileset of a map (or layer) for another one
+///
+/// Tiles are matched between tilesets by name, making this useful for seasonal or biome
+/// reskins: as long as both tilesets define the same tile names, the map can be retextured
+/// without being rebuilt.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{TilesetSwapper, Tilesets};
+/// fn make_winter(mut swapper: TilesetSwapper, tilesets: Tilesets) {
+///   let summer = *tilesets.get_by_name("Summer").unwrap().id();
+///   let winter = *tilesets.get_by_name("Winter").unwrap().id();
+///   swapper.swap_map(0u16, &summer, &winter).unwrap();
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct TilesetSwapper<'w, 's> {
+	commands: Commands<'w, 's>,
+	tilesets: Tilesets<'w, 's>,
+	#[cfg(not(feature = "auto-tile"))]
+	tiles: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static TileParent,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+		),
+	>,
+	#[cfg(feature = "auto-tile")]
+	tiles: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static TileParent,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+			Option<&'static bevy_tileset::auto::AutoTileId>,
+		),
+	>,
+	tilemaps: Query<'w, 's, (Entity, &'static TilemapLayer)>,
+}
+
+/// A tile to be swapped to a new tileset
+struct SwappedTile {
+	entity: Entity,
+	layer_id: u16,
+	index: TileIndex,
+	/// The new auto tile ID (if the tile is an auto tile)
+	#[cfg(feature = "auto-tile")]
+	auto_id: Option<bevy_tileset::auto::AutoTileId>,
+}
+
+impl<'w, 's> TilesetSwapper<'w, 's> {
+	/// Swap every tile in the given map from one tileset to another
+	///
+	/// See [`swap_layer`](Self::swap_layer) for details.
+	pub fn swap_map<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		from: &TilesetId,
+		to: &TilesetId,
+	) -> Result<usize, TilePlacementError> {
+		self.swap(map_id.into(), None, from, to)
+	}
+
+	/// Swap every tile in the given layer from one tileset to another
+	///
+	/// Each tile belonging to the `from` tileset is remapped to the tile with the same name in
+	/// the `to` tileset, keeping its variant (if the new tile has it). Auto tiles are moved to
+	/// the new tileset's group and re-resolved. The texture of the affected tilemaps is also
+	/// updated to the new tileset's texture.
+	///
+	/// Nothing is changed if either tileset is missing or any tile has no match in the new
+	/// tileset.
+	///
+	/// Returns the number of swapped tiles
+	pub fn swap_layer<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		from: &TilesetId,
+		to: &TilesetId,
+	) -> Result<usize, TilePlacementError> {
+		self.swap(map_id.into(), Some(layer_id), from, to)
+	}
+
+	fn swap(
+		&mut self,
+		map_id: u16,
+		layer_id: Option<u16>,
+		from: &TilesetId,
+		to: &TilesetId,
+	) -> Result<usize, TilePlacementError> {
+		let old_tileset = self
+			.tilesets
+			.get_by_id(from)
+			.ok_or(TilePlacementError::InvalidTileset(*from))?;
+		let new_tileset = self
+			.tilesets
+			.get_by_id(to)
+			.ok_or(TilePlacementError::InvalidTileset(*to))?;
+
+		// === Remap Tiles === //
+		// Every tile is remapped before anything is modified so that a missing tile leaves
+		// the map untouched
+		let mut swapped = Vec::new();
+		for item in self.tiles.iter() {
+			#[cfg(not(feature = "auto-tile"))]
+			let (entity, parent, index, tileset) = item;
+			#[cfg(feature = "auto-tile")]
+			let (entity, parent, index, tileset, auto_id) = item;
+
+			if parent.map_id != map_id
+				|| layer_id.map_or(false, |layer_id| parent.layer_id != layer_id)
+				|| tileset.0 != *from
+			{
+				continue;
+			}
+
+			let old_id = *old_tileset
+				.get_tile_id(&(index.0 as usize))
+				.ok_or(TilePlacementError::InvalidTileset(*from))?;
+			let name = old_tileset
+				.get_tile_name(&old_id.group_id)
+				.ok_or(TilePlacementError::InvalidTile(old_id))?;
+			let group_id = *new_tileset
+				.get_tile_group_id(name)
+				.ok_or(TilePlacementError::InvalidTile(old_id))?;
+
+			let new_id = TileId {
+				group_id,
+				tileset_id: *to,
+				..old_id
+			};
+			let index = new_tileset
+				.get_tile_index_by_id(new_id)
+				.or_else(|| new_tileset.get_tile_index(name))
+				.ok_or(TilePlacementError::InvalidTile(new_id))?;
+
+			swapped.push(SwappedTile {
+				entity,
+				layer_id: parent.layer_id,
+				index,
+				#[cfg(feature = "auto-tile")]
+				auto_id: auto_id.map(|_| new_id.into()),
+			});
+		}
+
+		// === Apply === //
+		let texture = TilemapTexture::Single(new_tileset.texture().clone());
+		for (entity, layer) in self.tilemaps.iter() {
+			if layer.map_id == map_id && swapped.iter().any(|tile| tile.layer_id == layer.layer_id)
+			{
+				self.commands.entity(entity).insert(texture.clone());
+			}
+		}
+
+		for tile in &swapped {
+			let mut cmds = self.commands.entity(tile.entity);
+			cmds.insert(TilesetParent(*to));
+			match tile.index {
+				TileIndex::Standard(index) => {
+					cmds.insert(TileTextureIndex(index as u32))
+						.remove::<AnimatedTile>();
+				},
+				TileIndex::Animated(start, end, speed) => {
+					cmds.insert((
+						TileTextureIndex(start as u32),
+						AnimatedTile {
+							start: start as u32,
+							end: end as u32,
+							speed,
+						},
+					));
+				},
+			}
+
+			#[cfg(feature = "auto-tile")]
+			if let Some(auto_id) = tile.auto_id {
+				cmds.insert(auto_id);
+			}
+		}
+
+		Ok(swapped.len())
+	}
+}
@@ -0,0 +1,117 @@
+//! Tools for randomly scattering tiles across a region
+
+use bevy::math::UVec2;
+use bevy::utils::HashSet;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// Options controlling how [`TilePlacer::scatter`] fills a region
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScatterOptions {
+	/// The fraction of cells to fill, from `0.0` (none) to `1.0` (all)
+	pub density: f32,
+	/// The seed of the random number generator
+	///
+	/// Scattering the same region with the same seed always fills the same cells.
+	pub seed: u64,
+	/// Whether to prevent two scattered tiles from being placed next to each other
+	pub avoid_adjacent: bool,
+}
+
+impl ScatterOptions {
+	/// Create options filling the given fraction of cells
+	pub fn new(density: f32, seed: u64) -> Self {
+		Self {
+			density,
+			seed,
+			avoid_adjacent: false,
+		}
+	}
+
+	/// Prevent two scattered tiles from being placed next to each other (including diagonally)
+	pub fn avoiding_adjacent(mut self) -> Self {
+		self.avoid_adjacent = true;
+		self
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Randomly place the given tile in a fraction of the empty cells of a region
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. Occupied
+	/// cells are skipped, so scattering never replaces existing tiles. Like
+	/// [`place_stamp`](Self::place_stamp), auto tiles are only updated once after every tile is
+	/// placed.
+	///
+	/// Returns the results of each placed tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::math::UVec2;
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{ScatterOptions, TileId, TilePlacer};
+	/// fn plant_flowers(mut placer: TilePlacer) {
+	///   # let flower = TileId::new(0, 0);
+	///   let options = ScatterOptions::new(0.1, 1234).avoiding_adjacent();
+	///   let origin = TilePos { x: 0, y: 0 };
+	///   placer
+	///     .scatter(flower, origin, UVec2::new(32, 32), options, 0u16, 1)
+	///     .unwrap();
+	/// }
+	/// ```
+	pub fn scatter<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		origin: Pos,
+		size: UVec2,
+		options: ScatterOptions,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let id = tile_id.into();
+		let origin = origin.into();
+		self.get_tile_index(&id)?;
+
+		let density = options.density.clamp(0.0, 1.0);
+		let grid = self.grids.get(map_id.into(), layer_id);
+		let mut rng = StdRng::seed_from_u64(options.seed);
+		let mut stamp = TileStamp::new(size.x, size.y);
+		let mut scattered = HashSet::default();
+		for y in 0..size.y {
+			for x in 0..size.x {
+				// Always roll so the same cells are picked regardless of what's skipped
+				if rng.gen::<f32>() >= density {
+					continue;
+				}
+
+				let pos = TilePos {
+					x: origin.x + x,
+					y: origin.y + y,
+				};
+				if self.occupancy.is_occupied(pos, map_id, layer_id) {
+					continue;
+				}
+				if options.avoid_adjacent
+					&& grid
+						.neighbors(&pos)
+						.iter()
+						.flatten()
+						.any(|neighbor| scattered.contains(neighbor))
+				{
+					continue;
+				}
+
+				scattered.insert(pos);
+				stamp.set(TilePos { x, y }, 0, Some(id));
+			}
+		}
+
+		self.place_stamp(&stamp, origin, map_id, layer_id)
+	}
+}
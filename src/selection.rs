@@ -0,0 +1,351 @@
+//! Tools for selecting tiles and acting on the selection
+
+use bevy::input::Input;
+use bevy::math::UVec2;
+use bevy::prelude::{Entity, KeyCode, MouseButton, Query, Res, ResMut, Resource};
+use bevy::utils::HashSet;
+use bevy_ecs_tilemap::prelude::TilePos;
+
+use crate::coord::TileCoord;
+use crate::manager::TilemapLayer;
+use crate::picker::TilePicker;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// An opt-in resource containing the selected cells of each map and layer
+///
+/// Cells can be selected manually or by dragging a rectangle (a marquee) over the
+/// [`layer`](Self::layer) with the [`button`](Self::button) held. Releasing the button replaces
+/// the selection with the cells within the marquee, or adds them to it while shift is held.
+///
+/// Use [`TilePlacer::delete_selection`], [`TilePlacer::move_selection`], and
+/// [`TilePlacer::copy_selection`] to act on the selected tiles.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{App, MouseButton};
+/// # use bevy_tileset_map::prelude::TileSelection;
+/// # let mut app = App::new();
+/// app.insert_resource(TileSelection::new(0u16, 1).with_button(MouseButton::Right));
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct TileSelection {
+	/// The map and layer selected by the marquee (`None` to disable marquee selection)
+	pub layer: Option<(u16, u16)>,
+	/// The mouse button used to drag the marquee
+	pub button: MouseButton,
+	/// The selected cells
+	cells: HashSet<TileCoord>,
+	/// The cell the marquee started at, while it's being dragged
+	anchor: Option<TileCoord>,
+	/// The cell under the cursor, while the marquee is being dragged
+	cursor: Option<TilePos>,
+}
+
+impl Default for TileSelection {
+	fn default() -> Self {
+		Self {
+			layer: None,
+			button: MouseButton::Left,
+			cells: HashSet::default(),
+			anchor: None,
+			cursor: None,
+		}
+	}
+}
+
+impl TileSelection {
+	/// Create an empty selection, with the marquee selecting cells of the given layer
+	pub fn new<MId: MapId>(map_id: MId, layer_id: u16) -> Self {
+		Self {
+			layer: Some((map_id.into(), layer_id)),
+			..Default::default()
+		}
+	}
+
+	/// Set the mouse button used to drag the marquee
+	pub fn with_button(mut self, button: MouseButton) -> Self {
+		self.button = button;
+		self
+	}
+
+	/// Returns true if no cell is selected
+	pub fn is_empty(&self) -> bool {
+		self.cells.is_empty()
+	}
+
+	/// Returns true if the given cell is selected
+	pub fn contains<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> bool {
+		self.cells.contains(&TileCoord {
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+		})
+	}
+
+	/// Select the given cell
+	pub fn select<Pos: Into<TilePos>, MId: MapId>(&mut self, pos: Pos, map_id: MId, layer_id: u16) {
+		self.cells.insert(TileCoord {
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+		});
+	}
+
+	/// Deselect the given cell
+	pub fn deselect<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) {
+		self.cells.remove(&TileCoord {
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+		});
+	}
+
+	/// Select every cell within a region
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles.
+	pub fn select_rect<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) {
+		let origin = origin.into();
+		let map_id = map_id.into();
+		for y in origin.y..origin.y + size.y {
+			for x in origin.x..origin.x + size.x {
+				self.select(TilePos { x, y }, map_id, layer_id);
+			}
+		}
+	}
+
+	/// Deselect every cell
+	pub fn clear(&mut self) {
+		self.cells.clear();
+	}
+
+	/// Iterate over the selected cells of every map and layer
+	pub fn iter(&self) -> impl Iterator<Item = &TileCoord> + '_ {
+		self.cells.iter()
+	}
+
+	/// Iterate over the selected cells of the given layer
+	pub fn iter_layer<MId: MapId>(
+		&self,
+		map_id: MId,
+		layer_id: u16,
+	) -> impl Iterator<Item = TilePos> + '_ {
+		let map_id = map_id.into();
+		self.cells
+			.iter()
+			.filter(move |coord| coord.map_id == map_id && coord.layer_id == layer_id)
+			.map(|coord| coord.pos)
+	}
+
+	/// Get the smallest region containing the selected cells of the given layer, as its
+	/// bottom-left corner and size
+	///
+	/// Returns `None` if no cell of the layer is selected.
+	pub fn bounds<MId: MapId>(&self, map_id: MId, layer_id: u16) -> Option<(TilePos, UVec2)> {
+		let mut cells = self.iter_layer(map_id, layer_id);
+		let first = cells.next()?;
+		let (min, max) = cells.fold((first, first), |(min, max), pos| {
+			(
+				TilePos {
+					x: min.x.min(pos.x),
+					y: min.y.min(pos.y),
+				},
+				TilePos {
+					x: max.x.max(pos.x),
+					y: max.y.max(pos.y),
+				},
+			)
+		});
+		Some((min, UVec2::new(max.x - min.x + 1, max.y - min.y + 1)))
+	}
+
+	/// Get the region covered by the marquee, as its bottom-left corner and size
+	///
+	/// Returns `None` if the marquee isn't being dragged.
+	pub fn marquee(&self) -> Option<(TilePos, UVec2)> {
+		let (anchor, cursor) = (self.anchor?.pos, self.cursor?);
+		let origin = TilePos {
+			x: anchor.x.min(cursor.x),
+			y: anchor.y.min(cursor.y),
+		};
+		let size = UVec2::new(
+			anchor.x.max(cursor.x) - origin.x + 1,
+			anchor.y.max(cursor.y) - origin.y + 1,
+		);
+		Some((origin, size))
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Remove every selected tile, then clear the selection
+	pub fn delete_selection(
+		&mut self,
+		selection: &mut TileSelection,
+	) -> Result<(), TilePlacementError> {
+		for coord in selection.cells.drain() {
+			if self
+				.occupancy
+				.is_occupied(coord.pos, coord.map_id, coord.layer_id)
+			{
+				self.remove(coord.pos, coord.map_id, coord.layer_id)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Copy the selected tiles of the given layer into a [`TileStamp`]
+	///
+	/// The stamp covers the [`bounds`](TileSelection::bounds) of the selection, leaving every
+	/// unselected cell empty. Returns the bottom-left corner of the bounds along with the stamp,
+	/// or `None` if no cell of the layer is selected.
+	pub fn copy_selection<MId: MapId>(
+		&self,
+		selection: &TileSelection,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<(TilePos, TileStamp)> {
+		let map_id = map_id.into();
+		let (origin, size) = selection.bounds(map_id, layer_id)?;
+		let mut stamp = self.copy_region(origin, size, map_id, layer_id);
+		for y in 0..size.y {
+			for x in 0..size.x {
+				let pos = TilePos {
+					x: origin.x + x,
+					y: origin.y + y,
+				};
+				if !selection.contains(pos, map_id, layer_id) {
+					stamp.set(TilePos { x, y }, 0, None);
+				}
+			}
+		}
+		Some((origin, stamp))
+	}
+
+	/// Move the selected tiles of the given layer so the bottom-left corner of their
+	/// [`bounds`](TileSelection::bounds) lies at `to`
+	///
+	/// The selection moves along with its tiles. Nothing happens if no cell of the layer is
+	/// selected.
+	///
+	/// Returns the results of each placed tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::prelude::ResMut;
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TilePlacer, TileSelection};
+	/// fn nudge_right(mut placer: TilePlacer, mut selection: ResMut<TileSelection>) {
+	///   if let Some((origin, _)) = selection.bounds(0u16, 1) {
+	///     let to = TilePos {
+	///       x: origin.x + 1,
+	///       y: origin.y,
+	///     };
+	///     placer.move_selection(&mut selection, to, 0u16, 1).unwrap();
+	///   }
+	/// }
+	/// ```
+	pub fn move_selection<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		selection: &mut TileSelection,
+		to: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let to = to.into();
+		let map_id = map_id.into();
+		let (origin, stamp) = match self.copy_selection(selection, map_id, layer_id) {
+			Some(copied) => copied,
+			None => return Ok(Vec::new()),
+		};
+
+		let cells = selection.iter_layer(map_id, layer_id).collect::<Vec<_>>();
+		for pos in &cells {
+			selection.deselect(*pos, map_id, layer_id);
+			if self.occupancy.is_occupied(*pos, map_id, layer_id) {
+				self.remove(*pos, map_id, layer_id)?;
+			}
+		}
+
+		let placed = self.place_stamp(&stamp, to, map_id, layer_id)?;
+		for pos in cells {
+			let pos = TilePos {
+				x: to.x + pos.x - origin.x,
+				y: to.y + pos.y - origin.y,
+			};
+			selection.select(pos, map_id, layer_id);
+		}
+		Ok(placed)
+	}
+}
+
+/// __\[SYSTEM\]__ Drags the marquee of the [`TileSelection`] and selects the cells within it
+pub(crate) fn update_tile_selection(
+	selection: Option<ResMut<TileSelection>>,
+	mouse: Option<Res<Input<MouseButton>>>,
+	keys: Option<Res<Input<KeyCode>>>,
+	picker: TilePicker,
+	tilemaps: Query<(Entity, &TilemapLayer)>,
+) {
+	let (mut selection, mouse) = match (selection, mouse) {
+		(Some(selection), Some(mouse)) => (selection, mouse),
+		_ => return,
+	};
+	let (map_id, layer_id) = match selection.layer {
+		Some(layer) => layer,
+		None => return,
+	};
+
+	let pos = tilemaps
+		.iter()
+		.find(|(_, layer)| layer.map_id == map_id && layer.layer_id == layer_id)
+		.and_then(|(entity, _)| picker.cursor_to_tile(entity));
+
+	if mouse.just_pressed(selection.button) {
+		selection.anchor = pos.map(|pos| TileCoord {
+			pos,
+			map_id,
+			layer_id,
+		});
+	}
+
+	if selection.anchor.is_none() {
+		return;
+	}
+	if let Some(pos) = pos {
+		if selection.cursor != Some(pos) {
+			selection.cursor = Some(pos);
+		}
+	}
+
+	if mouse.just_released(selection.button) {
+		let additive = keys.map_or(false, |keys| {
+			keys.any_pressed([KeyCode::LShift, KeyCode::RShift])
+		});
+		if !additive {
+			selection.clear();
+		}
+		if let Some((origin, size)) = selection.marquee() {
+			selection.select_rect(origin, size, map_id, layer_id);
+		}
+		selection.anchor = None;
+		selection.cursor = None;
+	}
+}
@@ -0,0 +1,260 @@
+//! Tools for periodically saving maps to disk
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::prelude::{
+	Added, App, Changed, CoreStage, Entity, EventReader, EventWriter, Or, Plugin, Query,
+	RemovedComponents, Res, ResMut, Resource, Time, Timer, TimerMode,
+};
+use bevy_ecs_tilemap::prelude::{TileParent, TileTextureIndex};
+
+use crate::changes::{TileChange, TileChangeLog};
+
+use super::{ChunkCulling, TileDelta, TilemapSerializationError, TilemapSerializer};
+
+/// Determines what the [`AutosavePlugin`] writes to disk
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AutosaveMode {
+	/// Overwrite the file with a snapshot of every map
+	///
+	/// The snapshot is saved as JSON if the path ends in `.json` and as RON otherwise. It's first
+	/// written to a temporary file and then moved into place, so a crash mid-save never leaves a
+	/// corrupted file behind.
+	Snapshot,
+	/// Append the edits recorded in the [`TileChangeLog`] since the last save
+	///
+	/// Each save appends a single [`TileDelta`] (as one line of JSON), which can later be loaded
+	/// with [`TilePlacer::apply_deltas`](crate::prelude::TilePlacer::apply_deltas). This is much
+	/// cheaper than saving a snapshot of a large map, but requires the [`TileChangeLog`] resource.
+	/// The log itself is left untouched.
+	ChangeLog,
+}
+
+/// A resource configuring the [`AutosavePlugin`]
+///
+/// This can be modified at runtime to change where and how often maps are saved.
+#[derive(Resource, Debug, Clone)]
+pub struct AutosaveSettings {
+	/// The file the maps are saved to
+	pub path: PathBuf,
+	/// What gets written to the file
+	pub mode: AutosaveMode,
+	/// How often the maps are saved (`None` to only save on exit)
+	pub interval: Option<Duration>,
+	/// Whether to save the maps when an [`AppExit`] event is sent
+	pub save_on_exit: bool,
+}
+
+impl AutosaveSettings {
+	/// Create settings saving a snapshot to the given path every minute and on exit
+	pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+		Self {
+			path: path.into(),
+			mode: AutosaveMode::Snapshot,
+			interval: Some(Duration::from_secs(60)),
+			save_on_exit: true,
+		}
+	}
+
+	/// Set what gets written to the file
+	pub fn with_mode(mut self, mode: AutosaveMode) -> Self {
+		self.mode = mode;
+		self
+	}
+
+	/// Set how often the maps are saved (`None` to only save on exit)
+	pub fn with_interval(mut self, interval: Option<Duration>) -> Self {
+		self.interval = interval;
+		self
+	}
+
+	/// Set whether to save the maps when an [`AppExit`] event is sent
+	pub fn with_save_on_exit(mut self, save_on_exit: bool) -> Self {
+		self.save_on_exit = save_on_exit;
+		self
+	}
+}
+
+/// An event sent after every autosave attempt
+#[derive(Debug)]
+pub struct AutosaveEvent {
+	/// The file the maps were saved to
+	pub path: PathBuf,
+	/// Whether the save succeeded
+	pub result: Result<(), TilemapSerializationError>,
+}
+
+/// Adds automatic saving of maps to disk, configured by its [`AutosaveSettings`]
+///
+/// Maps are only saved if they changed since the last save. The outcome of each save is sent
+/// as an [`AutosaveEvent`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use bevy::prelude::App;
+/// # use bevy_tileset_map::prelude::{AutosavePlugin, AutosaveSettings, TilesetMapPlugin};
+/// App::new()
+///   .add_plugin(TilesetMapPlugin::default())
+///   .add_plugin(AutosavePlugin(
+///     AutosaveSettings::new("saves/world.ron").with_interval(Some(Duration::from_secs(30))),
+///   ));
+/// ```
+pub struct AutosavePlugin(pub AutosaveSettings);
+
+impl Plugin for AutosavePlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(self.0.clone())
+			.init_resource::<AutosaveState>()
+			.add_event::<AutosaveEvent>()
+			.add_system_to_stage(CoreStage::Last, autosave);
+	}
+}
+
+/// The progress of the [`AutosavePlugin`] between saves
+#[derive(Resource, Default)]
+struct AutosaveState {
+	timer: Option<Timer>,
+	/// Whether any tile changed since the last snapshot
+	dirty: bool,
+	/// The number of changes in the [`TileChangeLog`] already saved
+	saved_changes: usize,
+	/// The [generation](TileChangeLog::generation) of the log the saved changes belong to
+	saved_generation: u64,
+	/// The sequence number of the next saved [`TileDelta`]
+	sequence: u64,
+}
+
+/// __\[SYSTEM\]__ Saves the maps once the autosave interval elapses or the app exits
+#[allow(clippy::too_many_arguments)]
+fn autosave(
+	settings: Res<AutosaveSettings>,
+	mut state: ResMut<AutosaveState>,
+	time: Res<Time>,
+	mut exits: EventReader<AppExit>,
+	changed: Query<Entity, Or<(Added<TileParent>, Changed<TileTextureIndex>)>>,
+	mut removed: RemovedComponents<TileParent>,
+	culling: Option<Res<ChunkCulling>>,
+	serializer: TilemapSerializer,
+	log: Option<Res<TileChangeLog>>,
+	mut events: EventWriter<AutosaveEvent>,
+) {
+	// Tiles despawned or respawned by culling aren't edits
+	let is_edit = |entity: Entity| {
+		culling
+			.as_ref()
+			.map_or(true, |culling| !culling.is_culled_entity(&entity))
+	};
+	if changed.iter().any(is_edit) || removed.iter().any(is_edit) {
+		state.dirty = true;
+	}
+
+	// Restart the timer whenever the interval is changed
+	let interval = settings.interval;
+	if state.timer.as_ref().map(Timer::duration) != interval {
+		state.timer = interval.map(|interval| Timer::new(interval, TimerMode::Repeating));
+	}
+	let due = match state.timer.as_mut() {
+		Some(timer) => timer.tick(time.delta()).just_finished(),
+		None => false,
+	};
+	let exiting = exits.iter().next().is_some() && settings.save_on_exit;
+	if !due && !exiting {
+		return;
+	}
+
+	let result = match settings.mode {
+		AutosaveMode::Snapshot => {
+			if !state.dirty {
+				return;
+			}
+
+			let result = save_snapshot(&serializer, culling.as_deref(), &settings.path);
+			if result.is_ok() {
+				state.dirty = false;
+			}
+			result
+		},
+		AutosaveMode::ChangeLog => {
+			let log = match log {
+				Some(log) => log,
+				None => return,
+			};
+
+			// The log was drained or cleared since the last save
+			if log.generation() != state.saved_generation {
+				state.saved_changes = 0;
+				state.saved_generation = log.generation();
+			}
+			let changes = log
+				.iter()
+				.skip(state.saved_changes)
+				.copied()
+				.collect::<Vec<_>>();
+			if changes.is_empty() {
+				return;
+			}
+
+			let result = append_changes(&changes, state.sequence, &settings.path);
+			if result.is_ok() {
+				state.saved_changes = log.len();
+				state.sequence += 1;
+			}
+			result
+		},
+	};
+
+	events.send(AutosaveEvent {
+		path: settings.path.clone(),
+		result,
+	});
+}
+
+/// Save a snapshot of every map to the given path
+///
+/// The tiles of culled chunks are saved from their snapshots.
+fn save_snapshot(
+	serializer: &TilemapSerializer,
+	culling: Option<&ChunkCulling>,
+	path: &Path,
+) -> Result<(), TilemapSerializationError> {
+	let mut tilemap = match serializer.save_maps() {
+		Some(tilemap) => tilemap,
+		None => return Ok(()),
+	};
+	if let Some(culling) = culling {
+		for snapshot in culling.snapshots() {
+			tilemap.extend(snapshot);
+		}
+	}
+	let data = match path.extension() {
+		Some(ext) if ext == "json" => tilemap.to_json()?,
+		_ => tilemap.to_ron()?,
+	};
+
+	let temp = path.with_extension("tmp");
+	fs::write(&temp, data)?;
+	fs::rename(&temp, path)?;
+	Ok(())
+}
+
+/// Append the given changes to the given path as a single [`TileDelta`]
+fn append_changes(
+	changes: &[TileChange],
+	sequence: u64,
+	path: &Path,
+) -> Result<(), TilemapSerializationError> {
+	let delta = TileDelta::from_changes(sequence, changes);
+	let mut line = serde_json::to_string(&delta)?;
+	line.push('\n');
+
+	let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+	file.write_all(line.as_bytes())?;
+	file.sync_data()?;
+	Ok(())
+}
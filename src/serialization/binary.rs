@@ -0,0 +1,108 @@
+//! A compact binary format for [`SerializableTilemap`]
+//!
+//! The format consists of a small header followed by the bincode-encoded tilemap:
+//!
+//! | Bytes | Contents                                 |
+//! |-------|------------------------------------------|
+//! | 0..4  | The magic bytes ([`BINARY_MAGIC`])       |
+//! | 4     | The format version                       |
+//! | 5     | The [`BinaryCompression`] method         |
+//! | 6..   | The (optionally compressed) tilemap data |
+
+use super::{SerializableTilemap, TilemapSerializationError, BINARY_MAGIC};
+
+/// The current version of the binary format
+///
+/// Bincode isn't self-describing, so this must be bumped whenever [`SerializableTilemap`] changes.
+/// Data saved with any other version is rejected rather than misread.
+const BINARY_VERSION: u8 = 5;
+/// The size of the binary header (in bytes)
+const HEADER_SIZE: usize = BINARY_MAGIC.len() + 2;
+
+/// The compression method used by the binary format
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BinaryCompression {
+	/// The data is not compressed
+	None,
+	/// The data is compressed using LZ4
+	///
+	/// Requires the `binary-lz4` feature
+	#[cfg(feature = "binary-lz4")]
+	Lz4,
+}
+
+impl Default for BinaryCompression {
+	fn default() -> Self {
+		#[cfg(feature = "binary-lz4")]
+		return Self::Lz4;
+		#[cfg(not(feature = "binary-lz4"))]
+		return Self::None;
+	}
+}
+
+impl BinaryCompression {
+	fn id(&self) -> u8 {
+		match self {
+			Self::None => 0,
+			#[cfg(feature = "binary-lz4")]
+			Self::Lz4 => 1,
+		}
+	}
+
+	fn from_id(id: u8) -> Result<Self, TilemapSerializationError> {
+		match id {
+			0 => Ok(Self::None),
+			#[cfg(feature = "binary-lz4")]
+			1 => Ok(Self::Lz4),
+			_ => Err(TilemapSerializationError::UnsupportedCompression(id)),
+		}
+	}
+}
+
+impl SerializableTilemap {
+	/// Serialize this tilemap to the binary format
+	pub fn to_binary(
+		&self,
+		compression: BinaryCompression,
+	) -> Result<Vec<u8>, TilemapSerializationError> {
+		let data = bincode::serialize(self)?;
+		let data = match compression {
+			BinaryCompression::None => data,
+			#[cfg(feature = "binary-lz4")]
+			BinaryCompression::Lz4 => lz4_flex::compress_prepend_size(&data),
+		};
+
+		let mut bytes = Vec::with_capacity(HEADER_SIZE + data.len());
+		bytes.extend_from_slice(BINARY_MAGIC);
+		bytes.push(BINARY_VERSION);
+		bytes.push(compression.id());
+		bytes.extend_from_slice(&data);
+		Ok(bytes)
+	}
+
+	/// Deserialize a tilemap from the binary format
+	pub fn from_binary(data: &[u8]) -> Result<Self, TilemapSerializationError> {
+		if data.len() < HEADER_SIZE || !data.starts_with(BINARY_MAGIC) {
+			return Err(TilemapSerializationError::InvalidHeader);
+		}
+
+		let version = data[BINARY_MAGIC.len()];
+		if version != BINARY_VERSION {
+			return Err(TilemapSerializationError::IncompatibleBinaryVersion {
+				found: version,
+				current: BINARY_VERSION,
+			});
+		}
+
+		let compression = BinaryCompression::from_id(data[BINARY_MAGIC.len() + 1])?;
+		let data = &data[HEADER_SIZE..];
+		match compression {
+			BinaryCompression::None => Ok(bincode::deserialize(data)?),
+			#[cfg(feature = "binary-lz4")]
+			BinaryCompression::Lz4 => {
+				let data = lz4_flex::decompress_size_prepended(data)?;
+				Ok(bincode::deserialize(&data)?)
+			},
+		}
+	}
+}
@@ -0,0 +1,506 @@
+//! Tools for saving tilemaps in chunks and streaming them in around a camera
+
+use bevy::log::warn;
+use bevy::math::{IVec2, UVec2, Vec2};
+use bevy::prelude::{
+	Component, DespawnRecursiveExt, Entity, GlobalTransform, ParamSet, Query, ResMut, Resource,
+	With,
+};
+use bevy::reflect::{FromReflect, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::TileId;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::TileTint;
+
+use super::{
+	SerializableFlip, SerializableTile, SerializableTilemap, TilemapDeserializer,
+	TilemapSerializationError, TilemapSerializer,
+};
+
+/// The coordinates of a chunk (in chunks, not tiles)
+#[derive(
+	Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, Reflect, FromReflect,
+)]
+#[reflect(Debug, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ChunkCoord {
+	pub x: u32,
+	pub y: u32,
+}
+
+impl ChunkCoord {
+	/// Get the coordinate of the chunk containing the given tile position
+	pub fn from_tile_pos(pos: &TilePos, chunk_size: UVec2) -> Self {
+		Self {
+			x: pos.x / chunk_size.x,
+			y: pos.y / chunk_size.y,
+		}
+	}
+}
+
+/// Contains serializable tilemap data, split into chunks
+#[derive(Debug, Clone, Deserialize, Serialize, Reflect)]
+#[reflect_value(Debug, Serialize, Deserialize)]
+pub struct SerializableChunkedTilemap {
+	/// The size of each chunk (in tiles)
+	pub chunk_size: UVec2,
+	/// The tilemap data of each chunk
+	#[serde(with = "chunk_entries")]
+	pub chunks: HashMap<ChunkCoord, SerializableTilemap>,
+}
+
+/// (De)serializes the chunk map as a list of entries, since not all formats support struct keys
+mod chunk_entries {
+	use super::{ChunkCoord, SerializableTilemap};
+	use bevy::utils::HashMap;
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(
+		chunks: &HashMap<ChunkCoord, SerializableTilemap>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(chunks.iter())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<HashMap<ChunkCoord, SerializableTilemap>, D::Error> {
+		let entries = Vec::<(ChunkCoord, SerializableTilemap)>::deserialize(deserializer)?;
+		Ok(entries.into_iter().collect())
+	}
+}
+
+impl SerializableChunkedTilemap {
+	/// Create an empty chunked tilemap with the given chunk size
+	pub fn new(chunk_size: UVec2) -> Self {
+		Self {
+			chunk_size,
+			chunks: HashMap::default(),
+		}
+	}
+
+	/// Get the tilemap data of the given chunk
+	pub fn get_chunk(&self, coord: &ChunkCoord) -> Option<&SerializableTilemap> {
+		self.chunks.get(coord)
+	}
+
+	/// Merge all chunks back into a single tilemap
+	pub fn merge(&self) -> SerializableTilemap {
+		let mut tilemap = SerializableTilemap {
+			version: self
+				.chunks
+				.values()
+				.map(|chunk| chunk.version)
+				.max()
+				.unwrap_or_default(),
+			data: HashMap::default(),
+			revealed: HashMap::default(),
+		};
+		for chunk in self.chunks.values() {
+			tilemap.extend(chunk);
+		}
+		tilemap
+	}
+}
+
+impl SerializableTilemap {
+	/// Split this tilemap into chunks of the given size
+	pub fn to_chunks(&self, chunk_size: UVec2) -> SerializableChunkedTilemap {
+		let mut chunked = SerializableChunkedTilemap::new(chunk_size);
+		for (map_id, layers) in &self.data {
+			for (layer_id, tiles) in layers {
+				for tile in tiles {
+					let coord = ChunkCoord::from_tile_pos(&tile.pos, chunk_size);
+					chunked
+						.chunks
+						.entry(coord)
+						.or_insert_with(|| SerializableTilemap {
+							version: self.version,
+							data: HashMap::default(),
+							revealed: HashMap::default(),
+						})
+						.push(*map_id, *layer_id, tile.clone());
+				}
+			}
+		}
+		for (map_id, revealed) in &self.revealed {
+			for (x, y) in revealed {
+				let coord = ChunkCoord::from_tile_pos(&TilePos { x: *x, y: *y }, chunk_size);
+				chunked
+					.chunks
+					.entry(coord)
+					.or_insert_with(|| SerializableTilemap {
+						version: self.version,
+						data: HashMap::default(),
+						revealed: HashMap::default(),
+					})
+					.revealed
+					.entry(*map_id)
+					.or_insert_with(Vec::default)
+					.push((*x, *y));
+			}
+		}
+		chunked
+	}
+
+	/// Add a tile to the given map and layer
+	pub(crate) fn push(&mut self, map_id: u16, layer_id: u16, tile: SerializableTile) {
+		self.data
+			.entry(map_id)
+			.or_insert_with(HashMap::default)
+			.entry(layer_id)
+			.or_insert_with(Vec::default)
+			.push(tile);
+	}
+
+	/// Add all tiles from the given tilemap to this one
+	pub(crate) fn extend(&mut self, other: &SerializableTilemap) {
+		for (map_id, layers) in &other.data {
+			for (layer_id, tiles) in layers {
+				for tile in tiles {
+					self.push(*map_id, *layer_id, tile.clone());
+				}
+			}
+		}
+		for (map_id, revealed) in &other.revealed {
+			self.revealed
+				.entry(*map_id)
+				.or_insert_with(Vec::default)
+				.extend(revealed);
+		}
+	}
+}
+
+impl<'w, 's> TilemapSerializer<'w, 's> {
+	/// Save all current maps, split into chunks of the given size
+	pub fn save_maps_chunked(&self, chunk_size: UVec2) -> Option<SerializableChunkedTilemap> {
+		Some(self.save_maps()?.to_chunks(chunk_size))
+	}
+
+	/// Save the tiles of the given map contained within the given chunk
+	///
+	/// Only the cells within the chunk are visited, so saving a chunk doesn't depend on the size
+	/// of the rest of the map.
+	pub fn save_chunk(
+		&self,
+		map_id: u16,
+		coord: ChunkCoord,
+		chunk_size: UVec2,
+	) -> Option<SerializableTilemap> {
+		let min = UVec2::new(coord.x, coord.y) * chunk_size;
+		let max = min + chunk_size;
+
+		let mut tiles_map = HashMap::default();
+		for (layer, storage) in self.storages.iter() {
+			if layer.map_id != map_id {
+				continue;
+			}
+
+			for y in min.y..max.y.min(storage.size.y) {
+				for x in min.x..max.x.min(storage.size.x) {
+					let entity = match storage.get(&TilePos { x, y }) {
+						Some(entity) => entity,
+						None => continue,
+					};
+					if let Ok((tile, parent, pos, tileset, flip, anim, color)) =
+						self.tiles.get(entity)
+					{
+						save_tiles!(self, tile, parent, pos, tileset, flip, anim, color, tiles_map);
+					}
+				}
+			}
+		}
+
+		let mut revealed = self.save_revealed(Some(map_id));
+		for positions in revealed.values_mut() {
+			positions.retain(|(x, y)| {
+				ChunkCoord::from_tile_pos(&TilePos { x: *x, y: *y }, chunk_size) == coord
+			});
+		}
+
+		Some(SerializableTilemap {
+			version: self.migrations.current_version(),
+			data: tiles_map,
+			revealed,
+		})
+	}
+}
+
+impl<'w, 's> TilemapDeserializer<'w, 's> {
+	/// Despawn the tiles of the given map within the given chunk
+	///
+	/// Unlike [`unload_maps`](Self::unload_maps), the tiles are despawned directly rather than
+	/// removed through the [`TilePlacer`](crate::prelude::TilePlacer): their neighbors keep their
+	/// auto tile textures and nothing is recorded in the
+	/// [`TileChangeLog`](crate::prelude::TileChangeLog). Returns the despawned entities.
+	pub(crate) fn despawn_chunk(
+		&mut self,
+		map_id: u16,
+		coord: ChunkCoord,
+		chunk_size: UVec2,
+	) -> Vec<Entity> {
+		let min = UVec2::new(coord.x, coord.y) * chunk_size;
+		let max = min + chunk_size;
+		let tilemaps = self
+			.tile_placer
+			.tilemaps
+			.iter()
+			.filter(|(_, layer)| layer.map_id == map_id)
+			.map(|(tilemap, _)| tilemap)
+			.collect::<Vec<_>>();
+
+		let mut despawned = Vec::new();
+		for tilemap in tilemaps {
+			let mut storage = match self.tile_placer.map_query.get_mut(tilemap) {
+				Ok(storage) => storage,
+				Err(_) => continue,
+			};
+			for y in min.y..max.y.min(storage.size.y) {
+				for x in min.x..max.x.min(storage.size.x) {
+					let pos = TilePos { x, y };
+					if let Some(entity) = storage.get(&pos) {
+						storage.remove(&pos);
+						despawned.push(entity);
+					}
+				}
+			}
+		}
+
+		for entity in &despawned {
+			self.commands.entity(*entity).despawn_recursive();
+		}
+		despawned
+	}
+
+	/// Spawn the tiles of a chunk saved with [`TilemapSerializer::save_chunk`] directly
+	///
+	/// This is the inverse of [`despawn_chunk`](Self::despawn_chunk): the tiles are spawned
+	/// without going through the [`TilePlacer`](crate::prelude::TilePlacer), keeping their saved
+	/// textures, and any tile already at a saved position is despawned. The chunk is first
+	/// upgraded to the current version using the registered
+	/// [`MapMigration`](crate::prelude::MapMigration)s.
+	///
+	/// Returns the spawned entities, along with the number of tiles whose tileset, tile, or layer
+	/// could not be found.
+	pub(crate) fn respawn_chunk(
+		&mut self,
+		chunk: &SerializableTilemap,
+	) -> Result<(Vec<Entity>, usize), TilemapSerializationError> {
+		let chunk = self.migrations.migrated(chunk)?;
+
+		let mut spawned = Vec::new();
+		let mut missing = 0;
+		for (map_id, layers) in &chunk.data {
+			for (layer_id, tiles) in layers {
+				let tilemap = self
+					.tile_placer
+					.tilemaps
+					.iter()
+					.find(|(_, layer)| layer.map_id == *map_id && layer.layer_id == *layer_id)
+					.map(|(tilemap, _)| tilemap);
+				let storage =
+					tilemap.and_then(|tilemap| self.tile_placer.map_query.get_mut(tilemap).ok());
+				let (tilemap, mut storage) = match (tilemap, storage) {
+					(Some(tilemap), Some(storage)) => (tilemap, storage),
+					_ => {
+						missing += tiles.len();
+						continue;
+					},
+				};
+
+				for tile in tiles {
+					let tileset = match tile.tileset.as_ref() {
+						Some(name) => self.tilesets.get_by_name(name),
+						None => self.tilesets.get_by_id(&tile.id.tileset_id),
+					};
+					let tileset = match tileset {
+						Some(tileset) if tile.pos.within_map_bounds(&storage.size) => tileset,
+						_ => {
+							missing += 1;
+							continue;
+						},
+					};
+					let id = TileId {
+						tileset_id: *tileset.id(),
+						..tile.id
+					};
+					let index = match tileset.get_tile_index_by_id(id) {
+						Some(index) => index,
+						None => {
+							missing += 1;
+							continue;
+						},
+					};
+
+					if let Some(existing) = storage.get(&tile.pos) {
+						self.commands.entity(existing).despawn_recursive();
+					}
+					let entity = crate::builder::spawn_tile(
+						&mut self.commands,
+						tileset,
+						tilemap,
+						tile.pos,
+						id,
+						index,
+					);
+					storage.set(&tile.pos, entity);
+					spawned.push(entity);
+
+					let mut cmds = self.commands.entity(entity);
+					cmds.insert(TileParent {
+						map_id: *map_id,
+						layer_id: *layer_id,
+					});
+					if tile.flip != SerializableFlip::default() {
+						cmds.insert(TileFlip::from(tile.flip));
+					}
+					if let Some(anim) = tile.animation {
+						cmds.insert(AnimatedTile::from(anim));
+					}
+					if let Some(color) = tile.color {
+						cmds.insert(TileTint::from(color));
+					}
+					#[cfg(feature = "auto-tile")]
+					if let Some(index) = tile.resolved_index {
+						cmds.insert((TileTextureIndex(index), crate::auto::ResolvedAutoTile));
+					}
+				}
+			}
+		}
+
+		self.load_revealed(&chunk, None);
+		Ok((spawned, missing))
+	}
+}
+
+/// Marks the camera used to decide which chunks are streamed in by the [`TilemapStreamer`]
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub struct StreamingCamera;
+
+/// A resource used to stream in chunks of a map around the [`StreamingCamera`]
+///
+/// Chunks within `radius` chunks of the camera are loaded from the chunked tilemap, while loaded
+/// chunks outside that radius are saved back to it and despawned.
+///
+/// This assumes the map is positioned with its bottom-left tile at `origin`.
+#[derive(Resource, Debug, Clone)]
+pub struct TilemapStreamer {
+	/// The map to stream into
+	pub map_id: u16,
+	/// The radius (in chunks) around the camera to keep loaded
+	pub radius: u32,
+	/// The size of a tile (in world units)
+	pub tile_size: Vec2,
+	/// The world position of the bottom-left corner of the map
+	pub origin: Vec2,
+	/// The stored chunks
+	pub tilemap: SerializableChunkedTilemap,
+	/// The chunks currently loaded into the world
+	loaded: HashSet<ChunkCoord>,
+}
+
+impl TilemapStreamer {
+	/// Create a new streamer for the given map and chunked tilemap
+	pub fn new(map_id: u16, tile_size: Vec2, tilemap: SerializableChunkedTilemap) -> Self {
+		Self {
+			map_id,
+			radius: 2,
+			tile_size,
+			origin: Vec2::ZERO,
+			tilemap,
+			loaded: HashSet::default(),
+		}
+	}
+
+	/// Set the radius (in chunks) around the camera to keep loaded
+	pub fn with_radius(mut self, radius: u32) -> Self {
+		self.radius = radius;
+		self
+	}
+
+	/// Set the world position of the bottom-left corner of the map
+	pub fn with_origin(mut self, origin: Vec2) -> Self {
+		self.origin = origin;
+		self
+	}
+
+	/// Returns true if the given chunk is currently loaded
+	pub fn is_loaded(&self, coord: &ChunkCoord) -> bool {
+		self.loaded.contains(coord)
+	}
+
+	/// Get the coordinate of the chunk containing the given world position
+	fn chunk_at(&self, world_pos: Vec2) -> IVec2 {
+		let chunk_size = self.tilemap.chunk_size.as_vec2() * self.tile_size;
+		((world_pos - self.origin) / chunk_size).floor().as_ivec2()
+	}
+}
+
+/// __\[SYSTEM\]__ Streams chunks in and out around the [`StreamingCamera`]
+///
+/// Does nothing unless a [`TilemapStreamer`] resource exists.
+pub(crate) fn stream_chunks(
+	streamer: Option<ResMut<TilemapStreamer>>,
+	cameras: Query<&GlobalTransform, With<StreamingCamera>>,
+	// The deserializer writes to what the serializer reads, so only one can be used at a time
+	mut serialization: ParamSet<(TilemapSerializer, TilemapDeserializer)>,
+) {
+	let mut streamer = match streamer {
+		Some(streamer) => streamer,
+		None => return,
+	};
+
+	let radius = streamer.radius as i32;
+	let mut wanted = HashSet::default();
+	for transform in cameras.iter() {
+		let center = streamer.chunk_at(transform.translation().truncate());
+		for y in (center.y - radius)..=(center.y + radius) {
+			for x in (center.x - radius)..=(center.x + radius) {
+				if x >= 0 && y >= 0 {
+					wanted.insert(ChunkCoord {
+						x: x as u32,
+						y: y as u32,
+					});
+				}
+			}
+		}
+	}
+
+	let map_id = streamer.map_id;
+	let chunk_size = streamer.tilemap.chunk_size;
+
+	// === Unload === //
+	let unload = streamer
+		.loaded
+		.difference(&wanted)
+		.copied()
+		.collect::<Vec<_>>();
+	for coord in unload {
+		let saved = serialization.p0().save_chunk(map_id, coord, chunk_size);
+		if let Some(chunk) = saved {
+			serialization.p1().despawn_chunk(map_id, coord, chunk_size);
+			streamer.tilemap.chunks.insert(coord, chunk);
+		}
+		streamer.loaded.remove(&coord);
+	}
+
+	// === Load === //
+	let load = wanted
+		.difference(&streamer.loaded)
+		.copied()
+		.collect::<Vec<_>>();
+	for coord in load {
+		if let Some(chunk) = streamer.tilemap.get_chunk(&coord) {
+			match serialization.p1().respawn_chunk(chunk) {
+				Ok((_, 0)) => {},
+				Ok((_, missing)) => warn!(
+					"{} tile(s) of streamed chunk {:?} could not be found",
+					missing, coord
+				),
+				Err(err) => warn!("Failed to stream in chunk {:?}: {}", coord, err),
+			}
+		}
+		streamer.loaded.insert(coord);
+	}
+}
@@ -0,0 +1,215 @@
+//! Tools for despawning the tiles of chunks far outside the view of the camera
+
+use bevy::log::warn;
+use bevy::math::{Rect, UVec2, Vec2};
+use bevy::prelude::{
+	Camera, Entity, GlobalTransform, OrthographicProjection, ParamSet, Query, ResMut, Resource,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::manager::TilemapLayer;
+
+use super::{ChunkCoord, SerializableTilemap, TilemapDeserializer, TilemapSerializer};
+
+/// A resource used to despawn the tiles of chunks far outside the view of the camera
+///
+/// Each frame, chunks of the map more than `margin` chunks outside the projection of every active
+/// orthographic camera are saved to a snapshot and their tile entities are despawned. Once the
+/// camera approaches them again, they are respawned from that snapshot.
+///
+/// Unlike the [`TilemapStreamer`](super::TilemapStreamer), which loads chunks from a saved map,
+/// this works on maps that already exist in the world, keeping huge maps from holding on to
+/// every tile entity forever.
+///
+/// Culling isn't an edit: tiles are despawned and respawned directly, so neighboring auto tiles
+/// aren't updated, nothing is recorded in the [`TileChangeLog`], and the chunks aren't marked in
+/// the [`DirtyChunks`] or picked up by the autosave.
+///
+/// This assumes the map is positioned with its bottom-left tile at `origin`. Tiles placed in a
+/// culled chunk are replaced by the snapshot once the chunk is respawned.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{App, UVec2, Vec2};
+/// # use bevy_tileset_map::prelude::ChunkCulling;
+/// # let mut app = App::new();
+/// app.insert_resource(ChunkCulling::new(0, UVec2::splat(32), Vec2::splat(16.0)).with_margin(2));
+/// ```
+///
+/// [`TileChangeLog`]: crate::prelude::TileChangeLog
+/// [`DirtyChunks`]: super::DirtyChunks
+#[derive(Resource, Debug, Clone)]
+pub struct ChunkCulling {
+	/// The map to cull
+	pub map_id: u16,
+	/// The size of each chunk (in tiles)
+	pub chunk_size: UVec2,
+	/// The size of a tile (in world units)
+	pub tile_size: Vec2,
+	/// The world position of the bottom-left corner of the map
+	pub origin: Vec2,
+	/// The number of chunks outside the camera's view to keep spawned
+	pub margin: u32,
+	/// The snapshots of the currently culled chunks
+	culled: HashMap<ChunkCoord, SerializableTilemap>,
+	/// The tile entities despawned or spawned by the last two runs, which aren't treated as
+	/// edits by the systems reacting to placed and removed tiles
+	culled_entities: [HashSet<Entity>; 2],
+}
+
+impl ChunkCulling {
+	/// Create a new culler for the given map
+	pub fn new(map_id: u16, chunk_size: UVec2, tile_size: Vec2) -> Self {
+		Self {
+			map_id,
+			chunk_size,
+			tile_size,
+			origin: Vec2::ZERO,
+			margin: 1,
+			culled: HashMap::default(),
+			culled_entities: Default::default(),
+		}
+	}
+
+	/// Set the number of chunks outside the camera's view to keep spawned
+	pub fn with_margin(mut self, margin: u32) -> Self {
+		self.margin = margin;
+		self
+	}
+
+	/// Set the world position of the bottom-left corner of the map
+	pub fn with_origin(mut self, origin: Vec2) -> Self {
+		self.origin = origin;
+		self
+	}
+
+	/// Returns true if the given chunk is currently culled
+	pub fn is_culled(&self, coord: &ChunkCoord) -> bool {
+		self.culled.contains_key(coord)
+	}
+
+	/// Get the snapshot of a culled chunk
+	pub fn get_snapshot(&self, coord: &ChunkCoord) -> Option<&SerializableTilemap> {
+		self.culled.get(coord)
+	}
+
+	/// Iterate over the snapshots of every culled chunk
+	pub(crate) fn snapshots(&self) -> impl Iterator<Item = &SerializableTilemap> {
+		self.culled.values()
+	}
+
+	/// Returns true if the given tile entity was despawned or spawned by culling
+	///
+	/// Removals are seen up to a frame after they happen, so the entities of the previous run
+	/// are kept as well.
+	pub(crate) fn is_culled_entity(&self, entity: &Entity) -> bool {
+		self.culled_entities.iter().any(|set| set.contains(entity))
+	}
+
+	/// Get the world-space bounds of the given chunk
+	fn chunk_rect(&self, coord: ChunkCoord) -> Rect {
+		let size = self.chunk_size.as_vec2() * self.tile_size;
+		let min = self.origin + Vec2::new(coord.x as f32, coord.y as f32) * size;
+		Rect::from_corners(min, min + size)
+	}
+}
+
+/// __\[SYSTEM\]__ Culls and respawns chunks of the map around the active cameras
+///
+/// Does nothing unless a [`ChunkCulling`] resource exists.
+pub(crate) fn cull_chunks(
+	culling: Option<ResMut<ChunkCulling>>,
+	cameras: Query<(&Camera, &GlobalTransform, &OrthographicProjection)>,
+	tilemaps: Query<(&TilemapLayer, &TilemapSize)>,
+	// The deserializer writes to what the serializer reads, so only one can be used at a time
+	mut serialization: ParamSet<(TilemapSerializer, TilemapDeserializer)>,
+) {
+	let mut culling = match culling {
+		Some(culling) => culling,
+		None => return,
+	};
+
+	let margin = culling.chunk_size.as_vec2() * culling.tile_size * culling.margin as f32;
+	let views = cameras
+		.iter()
+		.filter(|(camera, ..)| camera.is_active)
+		.map(|(_, transform, projection)| {
+			let center = transform.translation().truncate();
+			Rect::from_corners(
+				center + projection.area.min - margin,
+				center + projection.area.max + margin,
+			)
+		})
+		.collect::<Vec<_>>();
+	if views.is_empty() {
+		return;
+	}
+
+	let map_size = tilemaps
+		.iter()
+		.filter(|(layer, _)| layer.map_id == culling.map_id)
+		.fold(UVec2::ZERO, |size, (_, map_size)| {
+			size.max(UVec2::new(map_size.x, map_size.y))
+		});
+	let chunk_size = culling.chunk_size.max(UVec2::ONE);
+	let chunks = (map_size + chunk_size - UVec2::ONE) / chunk_size;
+
+	let mut cull = Vec::new();
+	let mut respawn = Vec::new();
+	for y in 0..chunks.y {
+		for x in 0..chunks.x {
+			let coord = ChunkCoord { x, y };
+			let rect = culling.chunk_rect(coord);
+			let visible = views.iter().any(|view| !view.intersect(rect).is_empty());
+			match (visible, culling.is_culled(&coord)) {
+				(true, true) => respawn.push(coord),
+				(false, false) => cull.push(coord),
+				_ => {},
+			}
+		}
+	}
+
+	let map_id = culling.map_id;
+	let culling = &mut *culling;
+	culling.culled_entities.swap(0, 1);
+	culling.culled_entities[0].clear();
+
+	// === Cull === //
+	for coord in cull {
+		let snapshot = match serialization.p0().save_chunk(map_id, coord, chunk_size) {
+			Some(snapshot) => snapshot,
+			None => {
+				warn!("Failed to save culled chunk {:?} of map {}", coord, map_id);
+				continue;
+			},
+		};
+		let despawned = serialization.p1().despawn_chunk(map_id, coord, chunk_size);
+		culling.culled_entities[0].extend(despawned);
+		culling.culled.insert(coord, snapshot);
+	}
+
+	// === Respawn === //
+	for coord in respawn {
+		let snapshot = match culling.culled.remove(&coord) {
+			Some(snapshot) => snapshot,
+			None => continue,
+		};
+		match serialization.p1().respawn_chunk(&snapshot) {
+			Ok((spawned, missing)) => {
+				culling.culled_entities[0].extend(spawned);
+				if missing > 0 {
+					warn!(
+						"{} tile(s) of culled chunk {:?} could not be respawned",
+						missing, coord
+					);
+				}
+			},
+			Err(err) => {
+				warn!("Failed to respawn culled chunk {:?}: {}", coord, err);
+				culling.culled.insert(coord, snapshot);
+			},
+		}
+	}
+}
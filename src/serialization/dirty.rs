@@ -0,0 +1,220 @@
+//! Tools for tracking which chunks changed since they were last saved
+
+use bevy::math::UVec2;
+use bevy::prelude::{
+	Changed, Entity, EventReader, Local, Or, Query, RemovedComponents, Res, ResMut, Resource,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{ChunkCoord, ChunkCulling, SerializableTilemap, TilemapSerializer};
+
+/// A resource tracking the chunks of each map modified since they were last saved
+///
+/// A chunk becomes dirty whenever one of its tiles is placed, removed, moved, flipped, or given a
+/// new texture. Changes to a tile's color (such as from lighting or fog of war) are ignored. This
+/// allows incremental persistence backends (like databases or cloud saves) to only write the
+/// chunks that changed, rather than the whole map.
+///
+/// Once a chunk is written, send a [`ChunkSavedEvent`] to mark it as clean again.
+///
+/// Tracking only happens while this resource exists, so insert it to start tracking.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{EventWriter, Res};
+/// # use bevy_tileset_map::prelude::{ChunkSavedEvent, DirtyChunks, TilemapSerializer};
+/// # fn write_to_database<T>(_: T) {}
+/// fn save_dirty_chunks(
+///   serializer: TilemapSerializer,
+///   dirty: Res<DirtyChunks>,
+///   mut saved: EventWriter<ChunkSavedEvent>,
+/// ) {
+///   for (map_id, coord, chunk) in serializer.save_dirty_chunks(&dirty) {
+///     write_to_database(chunk);
+///     saved.send(ChunkSavedEvent { map_id, coord });
+///   }
+/// }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct DirtyChunks {
+	chunk_size: UVec2,
+	maps: HashMap<u16, HashSet<ChunkCoord>>,
+}
+
+impl DirtyChunks {
+	/// Track the modified chunks of the given size (in tiles)
+	pub fn new(chunk_size: UVec2) -> Self {
+		Self {
+			chunk_size,
+			maps: HashMap::default(),
+		}
+	}
+
+	/// The size of each chunk (in tiles)
+	pub fn chunk_size(&self) -> UVec2 {
+		self.chunk_size
+	}
+
+	/// Mark the given chunk as modified
+	pub fn mark_dirty(&mut self, map_id: u16, coord: ChunkCoord) {
+		self.maps.entry(map_id).or_default().insert(coord);
+	}
+
+	/// Mark the given chunk as saved
+	///
+	/// Returns true if the chunk was dirty.
+	pub fn mark_saved(&mut self, map_id: u16, coord: &ChunkCoord) -> bool {
+		let chunks = match self.maps.get_mut(&map_id) {
+			Some(chunks) => chunks,
+			None => return false,
+		};
+
+		let was_dirty = chunks.remove(coord);
+		if chunks.is_empty() {
+			self.maps.remove(&map_id);
+		}
+		was_dirty
+	}
+
+	/// Returns true if the given chunk was modified since it was last saved
+	pub fn is_dirty(&self, map_id: u16, coord: &ChunkCoord) -> bool {
+		self.maps
+			.get(&map_id)
+			.map_or(false, |chunks| chunks.contains(coord))
+	}
+
+	/// Iterate over the modified chunks of the given map
+	pub fn iter(&self, map_id: u16) -> impl Iterator<Item = &ChunkCoord> {
+		self.maps.get(&map_id).into_iter().flatten()
+	}
+
+	/// Iterate over the maps with modified chunks
+	pub fn maps(&self) -> impl Iterator<Item = u16> + '_ {
+		self.maps.keys().copied()
+	}
+
+	/// The total number of modified chunks across all maps
+	pub fn len(&self) -> usize {
+		self.maps.values().map(HashSet::len).sum()
+	}
+
+	/// Returns true if no chunks were modified
+	pub fn is_empty(&self) -> bool {
+		self.maps.is_empty()
+	}
+
+	/// Mark every chunk as saved
+	pub fn clear(&mut self) {
+		self.maps.clear();
+	}
+}
+
+/// An event marking a chunk as saved, removing it from the [`DirtyChunks`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChunkSavedEvent {
+	/// The map the chunk belongs to
+	pub map_id: u16,
+	/// The coordinate of the saved chunk
+	pub coord: ChunkCoord,
+}
+
+impl<'w, 's> TilemapSerializer<'w, 's> {
+	/// Save every chunk marked as modified in the given [`DirtyChunks`]
+	///
+	/// Chunks whose tiles were all removed are returned empty, so they can be cleared from
+	/// storage. Returns the map, coordinate, and tiles of each chunk.
+	pub fn save_dirty_chunks(
+		&self,
+		dirty: &DirtyChunks,
+	) -> Vec<(u16, ChunkCoord, SerializableTilemap)> {
+		let mut saved = Vec::with_capacity(dirty.len());
+		for map_id in dirty.maps() {
+			let tilemap = match self.save_map(map_id) {
+				Some(tilemap) => tilemap,
+				None => continue,
+			};
+
+			let version = tilemap.version;
+			let mut chunked = tilemap.to_chunks(dirty.chunk_size);
+			for coord in dirty.iter(map_id) {
+				let chunk = chunked
+					.chunks
+					.remove(coord)
+					.unwrap_or_else(|| SerializableTilemap {
+						version,
+						data: HashMap::default(),
+						revealed: HashMap::default(),
+					});
+				saved.push((map_id, *coord, chunk));
+			}
+		}
+		saved
+	}
+}
+
+/// __\[SYSTEM\]__ Marks the chunks of placed, removed, and modified tiles as dirty
+pub(crate) fn track_dirty_chunks(
+	dirty: Option<ResMut<DirtyChunks>>,
+	changed: Query<
+		(Entity, &TilePos, &TileParent),
+		Or<(
+			Changed<TileParent>,
+			Changed<TilePos>,
+			Changed<TileTextureIndex>,
+			Changed<TileFlip>,
+		)>,
+	>,
+	mut removed: RemovedComponents<TileParent>,
+	culling: Option<Res<ChunkCulling>>,
+	mut tracked: Local<HashMap<Entity, (u16, ChunkCoord)>>,
+) {
+	let mut dirty = match dirty {
+		Some(dirty) => dirty,
+		None => return,
+	};
+
+	// Tiles despawned or respawned by culling are still tracked, but don't dirty their chunk
+	let is_culled = |entity: &Entity| {
+		culling
+			.as_ref()
+			.map_or(false, |culling| culling.is_culled_entity(entity))
+	};
+
+	let chunk_size = dirty.chunk_size;
+	for entity in removed.iter() {
+		if let Some((map_id, coord)) = tracked.remove(&entity) {
+			if !is_culled(&entity) {
+				dirty.mark_dirty(map_id, coord);
+			}
+		}
+	}
+
+	for (entity, pos, parent) in changed.iter() {
+		let key = (parent.map_id, ChunkCoord::from_tile_pos(pos, chunk_size));
+		let previous = tracked.insert(entity, key);
+		if is_culled(&entity) {
+			continue;
+		}
+		if let Some((map_id, coord)) = previous {
+			dirty.mark_dirty(map_id, coord);
+		}
+		dirty.mark_dirty(key.0, key.1);
+	}
+}
+
+/// __\[SYSTEM\]__ Marks the chunks of each [`ChunkSavedEvent`] as saved
+pub(crate) fn clear_saved_chunks(
+	mut events: EventReader<ChunkSavedEvent>,
+	dirty: Option<ResMut<DirtyChunks>>,
+) {
+	let mut dirty = match dirty {
+		Some(dirty) => dirty,
+		None => return,
+	};
+
+	for event in events.iter() {
+		dirty.mark_saved(event.map_id, &event.coord);
+	}
+}
@@ -0,0 +1,135 @@
+//! Tools for upgrading tilemaps saved with older versions of a game's save format
+
+use std::borrow::Cow;
+
+use bevy::prelude::{App, Resource};
+
+use super::{SerializableTilemap, TilemapSerializationError};
+
+/// A migration used to upgrade a [`SerializableTilemap`] from one version to the next
+///
+/// Migrations are registered with the [`MapMigrations`] resource (or via
+/// [`AddMapMigration::add_map_migration`]). When a tilemap is loaded, every registered migration
+/// with a version greater than the tilemap's version is applied in order.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{MapMigration, SerializableTilemap};
+/// /// Moves all tiles from layer 1 to layer 2
+/// struct MoveDecorLayer;
+///
+/// impl MapMigration for MoveDecorLayer {
+///   fn version(&self) -> u32 {
+///     1
+///   }
+///
+///   fn migrate(&self, tilemap: &mut SerializableTilemap) {
+///     for layers in tilemap.data.values_mut() {
+///       if let Some(tiles) = layers.remove(&1) {
+///         layers.insert(2, tiles);
+///       }
+///     }
+///   }
+/// }
+/// ```
+pub trait MapMigration: Send + Sync + 'static {
+	/// The version this migration upgrades a tilemap _to_
+	///
+	/// This should be greater than zero, since tilemaps saved without a version are
+	/// treated as version `0`.
+	fn version(&self) -> u32;
+
+	/// Upgrade the given tilemap from the previous version
+	fn migrate(&self, tilemap: &mut SerializableTilemap);
+}
+
+impl<F: Fn(&mut SerializableTilemap) + Send + Sync + 'static> MapMigration for (u32, F) {
+	fn version(&self) -> u32 {
+		self.0
+	}
+
+	fn migrate(&self, tilemap: &mut SerializableTilemap) {
+		(self.1)(tilemap)
+	}
+}
+
+/// A resource containing all registered [`MapMigration`]s
+///
+/// The current save version is the highest version of all registered migrations (or `0` if
+/// there are none). Tilemaps are saved with this version by the `TilemapSerializer` and
+/// are upgraded to it by the `TilemapDeserializer`.
+#[derive(Resource, Default)]
+pub struct MapMigrations {
+	migrations: Vec<Box<dyn MapMigration>>,
+}
+
+impl MapMigrations {
+	/// Register a migration
+	pub fn add<M: MapMigration>(&mut self, migration: M) -> &mut Self {
+		self.migrations.push(Box::new(migration));
+		self.migrations.sort_by_key(|migration| migration.version());
+		self
+	}
+
+	/// Get the current save version
+	pub fn current_version(&self) -> u32 {
+		self.migrations
+			.last()
+			.map(|migration| migration.version())
+			.unwrap_or_default()
+	}
+
+	/// Upgrade the given tilemap to the current version
+	///
+	/// Returns an error if the tilemap was saved with a newer version than the current one.
+	pub fn migrate(
+		&self,
+		tilemap: &mut SerializableTilemap,
+	) -> Result<(), TilemapSerializationError> {
+		let current = self.current_version();
+		if tilemap.version > current {
+			return Err(TilemapSerializationError::UnsupportedVersion {
+				found: tilemap.version,
+				current,
+			});
+		}
+
+		for migration in &self.migrations {
+			if migration.version() > tilemap.version {
+				migration.migrate(tilemap);
+				tilemap.version = migration.version();
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Get the given tilemap upgraded to the current version, only cloning it if needed
+	pub(crate) fn migrated<'a>(
+		&self,
+		tilemap: &'a SerializableTilemap,
+	) -> Result<Cow<'a, SerializableTilemap>, TilemapSerializationError> {
+		if tilemap.version == self.current_version() {
+			return Ok(Cow::Borrowed(tilemap));
+		}
+
+		let mut tilemap = tilemap.clone();
+		self.migrate(&mut tilemap)?;
+		Ok(Cow::Owned(tilemap))
+	}
+}
+
+/// An extension trait for registering [`MapMigration`]s on an [`App`]
+pub trait AddMapMigration {
+	/// Register a migration with the [`MapMigrations`] resource
+	fn add_map_migration<M: MapMigration>(&mut self, migration: M) -> &mut Self;
+}
+
+impl AddMapMigration for App {
+	fn add_map_migration<M: MapMigration>(&mut self, migration: M) -> &mut Self {
+		self.init_resource::<MapMigrations>();
+		self.world.resource_mut::<MapMigrations>().add(migration);
+		self
+	}
+}
@@ -0,0 +1,559 @@
+//! Tools for serializing and deserializing entire tilemaps with one or more tilesets
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Color, Commands, Query, Res, ResMut};
+use bevy::reflect::{FromReflect, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::prelude::{FogOfWar, PlacedTile, TilePlacer, TileTint, TilemapLayer};
+use bevy_tileset::prelude::{TileId, TilesetParent, Tilesets};
+
+pub use autosave::{AutosaveEvent, AutosaveMode, AutosavePlugin, AutosaveSettings};
+#[cfg(feature = "binary")]
+pub use binary::BinaryCompression;
+pub(crate) use chunked::stream_chunks;
+pub use chunked::{ChunkCoord, SerializableChunkedTilemap, StreamingCamera, TilemapStreamer};
+pub(crate) use culling::cull_chunks;
+pub use culling::ChunkCulling;
+pub(crate) use dirty::{clear_saved_chunks, track_dirty_chunks};
+pub use dirty::{ChunkSavedEvent, DirtyChunks};
+pub use migration::{AddMapMigration, MapMigration, MapMigrations};
+pub use prefab::{TilePrefab, TilePrefabLoader};
+pub use replication::{TileDelta, TileEdit};
+#[cfg(feature = "auto-tile")]
+pub use resolved::AutoTileSerialization;
+
+mod autosave;
+#[cfg(feature = "binary")]
+mod binary;
+mod chunked;
+mod culling;
+mod dirty;
+mod migration;
+mod prefab;
+mod replication;
+#[cfg(feature = "auto-tile")]
+mod resolved;
+
+/// Errors related to the serialization of tilemaps
+#[derive(Error, Debug)]
+pub enum TilemapSerializationError {
+	/// An error generated while reading or writing a file
+	#[error("IO error: {0}")]
+	Io(#[from] std::io::Error),
+	/// An error generated while reading or writing RON
+	#[error("RON error: {0}")]
+	Ron(#[from] ron::Error),
+	/// An error generated while reading RON
+	#[error("RON error: {0}")]
+	RonSpanned(#[from] ron::error::SpannedError),
+	/// An error generated while reading or writing JSON
+	#[error("JSON error: {0}")]
+	Json(#[from] serde_json::Error),
+	/// An error generated while reading or writing the binary format
+	#[cfg(feature = "binary")]
+	#[error("Binary error: {0}")]
+	Binary(#[from] bincode::Error),
+	/// An error generated while decompressing the binary format
+	#[cfg(feature = "binary-lz4")]
+	#[error("Decompression error: {0}")]
+	Decompression(#[from] lz4_flex::block::DecompressError),
+	/// The data is in the binary format but its header is invalid or unsupported
+	#[error("Invalid binary header")]
+	InvalidHeader,
+	/// The data was saved with a different version of the binary format
+	///
+	/// Bincode isn't self-describing, so binary data can only be read by the version of the
+	/// format it was written with.
+	#[error("Binary format version {found} is incompatible with the current version {current}")]
+	IncompatibleBinaryVersion {
+		/// The version of the binary format the data was saved with
+		found: u8,
+		/// The current version of the binary format
+		current: u8,
+	},
+	/// The tilemap was saved with a newer version than any registered [`MapMigration`]
+	#[error("Tilemap version {found} is newer than the current version {current}")]
+	UnsupportedVersion {
+		/// The version of the tilemap
+		found: u32,
+		/// The current version
+		current: u32,
+	},
+	/// The data is compressed with a method that is not enabled
+	///
+	/// Contains the ID of the compression method in question
+	#[error("Unsupported compression method {0}")]
+	UnsupportedCompression(u8),
+}
+
+/// Contains the serializable tint of a tile
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize, Reflect, FromReflect)]
+#[reflect(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SerializableColor {
+	pub r: f32,
+	pub g: f32,
+	pub b: f32,
+	pub a: f32,
+}
+
+/// Contains serializable tilemap data
+#[derive(Debug, Clone, Deserialize, Serialize, Reflect)]
+#[reflect_value(Debug, Serialize, Deserialize)]
+pub struct SerializableTile {
+	pub id: TileId,
+	#[serde(with = "crate::coord::TilePosRef")]
+	pub pos: TilePos,
+	/// The name of the tileset this tile belongs to
+	///
+	/// If present, this is used to find the tileset on load rather than the tileset ID
+	/// contained in `id`.
+	#[serde(default)]
+	pub tileset: Option<String>,
+	/// The flip state of the tile
+	#[serde(default)]
+	pub flip: SerializableFlip,
+	/// The animation state of the tile (if animated)
+	#[serde(default)]
+	pub animation: Option<SerializableAnimation>,
+	/// The tint of the tile (if tinted)
+	#[serde(default)]
+	pub color: Option<SerializableColor>,
+	/// The resolved texture index of the tile (if it's an auto tile saved with
+	/// [`AutoTileSerialization::Store`])
+	#[serde(default)]
+	pub resolved_index: Option<u32>,
+}
+
+/// Contains the serializable flip state of a tile
+///
+/// A diagonal flip combined with an x or y flip can be used to represent a rotation, so this
+/// also preserves tiles placed with a [`TileTransform`](crate::prelude::TileTransform).
+#[derive(
+	Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Reflect, FromReflect,
+)]
+#[reflect(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SerializableFlip {
+	#[serde(default)]
+	pub x: bool,
+	#[serde(default)]
+	pub y: bool,
+	#[serde(default)]
+	pub d: bool,
+}
+
+/// Contains the serializable animation state of a tile
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize, Reflect, FromReflect)]
+#[reflect(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SerializableAnimation {
+	pub start: u32,
+	pub end: u32,
+	pub speed: f32,
+}
+
+/// Contains serializable tilemap data
+#[derive(Debug, Clone, Deserialize, Serialize, Reflect)]
+#[reflect_value(Debug, Serialize, Deserialize)]
+pub struct SerializableTilemap {
+	/// The version this tilemap was saved with
+	///
+	/// Tilemaps saved before versioning was introduced are treated as version `0`.
+	/// See [`MapMigration`] for details.
+	#[serde(default)]
+	pub version: u32,
+	pub data: HashMap<u16, HashMap<u16, Vec<SerializableTile>>>,
+	/// The positions revealed by the [`FogOfWar`] of each map, as `(x, y)` pairs
+	#[serde(default)]
+	pub revealed: HashMap<u16, Vec<(u32, u32)>>,
+}
+
+impl From<TileFlip> for SerializableFlip {
+	fn from(flip: TileFlip) -> Self {
+		Self {
+			x: flip.x,
+			y: flip.y,
+			d: flip.d,
+		}
+	}
+}
+
+impl From<SerializableFlip> for TileFlip {
+	fn from(flip: SerializableFlip) -> Self {
+		Self {
+			x: flip.x,
+			y: flip.y,
+			d: flip.d,
+		}
+	}
+}
+
+impl From<TileColor> for SerializableColor {
+	fn from(color: TileColor) -> Self {
+		let [r, g, b, a] = color.0.as_rgba_f32();
+		Self { r, g, b, a }
+	}
+}
+
+impl From<SerializableColor> for TileColor {
+	fn from(color: SerializableColor) -> Self {
+		Self(Color::rgba(color.r, color.g, color.b, color.a))
+	}
+}
+
+impl From<TileTint> for SerializableColor {
+	fn from(tint: TileTint) -> Self {
+		let [r, g, b, a] = tint.0.as_rgba_f32();
+		Self { r, g, b, a }
+	}
+}
+
+impl From<SerializableColor> for TileTint {
+	fn from(color: SerializableColor) -> Self {
+		Self(Color::rgba(color.r, color.g, color.b, color.a))
+	}
+}
+
+impl From<AnimatedTile> for SerializableAnimation {
+	fn from(anim: AnimatedTile) -> Self {
+		Self {
+			start: anim.start,
+			end: anim.end,
+			speed: anim.speed,
+		}
+	}
+}
+
+impl From<SerializableAnimation> for AnimatedTile {
+	fn from(anim: SerializableAnimation) -> Self {
+		Self {
+			start: anim.start,
+			end: anim.end,
+			speed: anim.speed,
+		}
+	}
+}
+
+impl SerializableTilemap {
+	/// Serialize this tilemap to a RON string
+	pub fn to_ron(&self) -> Result<String, TilemapSerializationError> {
+		Ok(ron::ser::to_string_pretty(
+			self,
+			ron::ser::PrettyConfig::default(),
+		)?)
+	}
+
+	/// Deserialize a tilemap from a RON string
+	pub fn from_ron(data: &str) -> Result<Self, TilemapSerializationError> {
+		Ok(ron::from_str(data)?)
+	}
+
+	/// Serialize this tilemap to a JSON string
+	pub fn to_json(&self) -> Result<String, TilemapSerializationError> {
+		Ok(serde_json::to_string_pretty(self)?)
+	}
+
+	/// Deserialize a tilemap from a JSON string
+	pub fn from_json(data: &str) -> Result<Self, TilemapSerializationError> {
+		Ok(serde_json::from_str(data)?)
+	}
+
+	/// Deserialize a tilemap from bytes, automatically detecting its format
+	///
+	/// Data starting with the binary header is read using the binary format (requires the `binary`
+	/// feature). Otherwise, it is read as JSON if it starts with `{` and as RON if not.
+	pub fn from_bytes(data: &[u8]) -> Result<Self, TilemapSerializationError> {
+		if data.starts_with(BINARY_MAGIC) {
+			#[cfg(feature = "binary")]
+			return Self::from_binary(data);
+			#[cfg(not(feature = "binary"))]
+			return Err(TilemapSerializationError::InvalidHeader);
+		}
+
+		let text = std::str::from_utf8(data).map_err(|_| TilemapSerializationError::InvalidHeader)?;
+		if text.trim_start().starts_with('{') {
+			Self::from_json(text)
+		} else {
+			Self::from_ron(text)
+		}
+	}
+}
+
+/// The magic bytes at the start of every tilemap saved in the binary format
+pub const BINARY_MAGIC: &[u8; 4] = b"BTSM";
+
+/// Get the serializable tint of a tile, skipping the default (untinted) color
+///
+/// Only the [`TileTint`] is saved, since the rest of the tile's [`TileColor`] comes from
+/// lighting, fog of war, and the opacity of its layer.
+pub(crate) fn serialize_color(tint: TileTint) -> Option<SerializableColor> {
+	if tint.0 == Color::WHITE {
+		None
+	} else {
+		Some(SerializableColor::from(tint))
+	}
+}
+
+/// A system parameter that can be used to handle tilemap serialization
+///
+/// This reads every [`TileStorage`], so it conflicts with the [`TilePlacer`] and the
+/// [`TilemapDeserializer`], which modify them. Systems needing both should put them in a
+/// [`ParamSet`](bevy::ecs::system::ParamSet).
+#[derive(SystemParam)]
+pub struct TilemapSerializer<'w, 's> {
+	tiles: Query<
+		'w,
+		's,
+		(
+			&'static TileTextureIndex,
+			&'static TileParent,
+			&'static TilePos,
+			&'static TilesetParent,
+			Option<&'static TileFlip>,
+			Option<&'static AnimatedTile>,
+			Option<&'static TileTint>,
+		),
+	>,
+	/// Query used to find the tiles of each layer by position
+	storages: Query<'w, 's, (&'static TilemapLayer, &'static TileStorage)>,
+	tilesets: Tilesets<'w, 's>,
+	migrations: Res<'w, MapMigrations>,
+	fog: Option<Res<'w, FogOfWar>>,
+	#[cfg(feature = "auto-tile")]
+	auto_tile_serialization: Res<'w, AutoTileSerialization>,
+}
+
+macro_rules! save_tiles {
+	($self: ident, $tile: ident, $parent: ident, $pos: ident, $tileset: ident, $flip: ident, $anim: ident, $color: ident, $tiles_map: ident) => {
+		let tileset = $self.tilesets.get_by_id(&$tileset.0)?;
+		let index = $tile.0 as usize;
+		let tile_id = tileset.get_tile_id(&index)?;
+		let map = $tiles_map
+			.entry($parent.map_id)
+			.or_insert_with(HashMap::default);
+		let layer = map.entry($parent.layer_id).or_insert_with(Vec::default);
+		let tile = SerializableTile {
+			id: *tile_id,
+			pos: *$pos,
+			tileset: Some(tileset.name().to_string()),
+			flip: $flip.copied().map(SerializableFlip::from).unwrap_or_default(),
+			animation: $anim.copied().map(SerializableAnimation::from),
+			color: $color.copied().and_then(serialize_color),
+			#[cfg(feature = "auto-tile")]
+			resolved_index: $self.resolved_index(tile_id, $tile),
+			#[cfg(not(feature = "auto-tile"))]
+			resolved_index: None,
+		};
+		layer.push(tile);
+	};
+}
+
+impl<'w, 's> TilemapSerializer<'w, 's> {
+	/// Save all current maps
+	pub fn save_maps(&self) -> Option<SerializableTilemap> {
+		let capacity = self.tiles.iter().count();
+		let mut tiles_map = HashMap::with_capacity(capacity);
+		for (tile, parent, pos, tileset, flip, anim, color) in self.tiles.iter() {
+			save_tiles!(self, tile, parent, pos, tileset, flip, anim, color, tiles_map);
+		}
+		Some(SerializableTilemap {
+			version: self.migrations.current_version(),
+			data: tiles_map,
+			revealed: self.save_revealed(None),
+		})
+	}
+
+	/// Save the given map
+	pub fn save_map(&self, map_id: u16) -> Option<SerializableTilemap> {
+		let mut tiles_map = HashMap::default();
+		for (tile, parent, pos, tileset, flip, anim, color) in self.tiles.iter() {
+			if parent.map_id != map_id {
+				continue;
+			}
+
+			save_tiles!(self, tile, parent, pos, tileset, flip, anim, color, tiles_map);
+		}
+		Some(SerializableTilemap {
+			version: self.migrations.current_version(),
+			data: tiles_map,
+			revealed: self.save_revealed(Some(map_id)),
+		})
+	}
+
+	/// Save the given layer for the given map
+	pub fn save_layer(&self, map_id: u16, layer_id: u16) -> Option<SerializableTilemap> {
+		let mut tiles_map = HashMap::default();
+		for (tile, parent, pos, tileset, flip, anim, color) in self.tiles.iter() {
+			if parent.map_id != map_id || parent.layer_id != layer_id {
+				continue;
+			}
+
+			save_tiles!(self, tile, parent, pos, tileset, flip, anim, color, tiles_map);
+		}
+		Some(SerializableTilemap {
+			version: self.migrations.current_version(),
+			data: tiles_map,
+			revealed: self.save_revealed(Some(map_id)),
+		})
+	}
+
+	/// Get the positions revealed by the [`FogOfWar`], if it covers the given map (or any map if
+	/// `None`)
+	fn save_revealed(&self, map_id: Option<u16>) -> HashMap<u16, Vec<(u32, u32)>> {
+		let mut revealed = HashMap::default();
+		if let Some(fog) = &self.fog {
+			if map_id.map(|map_id| map_id == fog.map_id).unwrap_or(true) {
+				let positions = fog.revealed().map(|pos| (pos.x, pos.y)).collect();
+				revealed.insert(fog.map_id, positions);
+			}
+		}
+		revealed
+	}
+}
+
+/// A system parameter that can be used to handle tilemap deserialization
+#[derive(SystemParam)]
+pub struct TilemapDeserializer<'w, 's> {
+	tilesets: Tilesets<'w, 's>,
+	tile_placer: TilePlacer<'w, 's>,
+	commands: Commands<'w, 's>,
+	migrations: Res<'w, MapMigrations>,
+	fog: Option<ResMut<'w, FogOfWar>>,
+	/// Query used to find the auto tiles to verify
+	#[cfg(feature = "auto-tile")]
+	auto_tiles: Query<
+		'w,
+		's,
+		(
+			bevy::prelude::Entity,
+			&'static TileParent,
+			&'static bevy_tileset::auto::AutoTileId,
+		),
+	>,
+}
+
+impl<'w, 's> TilemapDeserializer<'w, 's> {
+	/// Load all maps contained in the given tilemap
+	///
+	/// The tilemap is first upgraded to the current version using the registered [`MapMigration`]s.
+	pub fn load_maps(
+		&mut self,
+		tilemap: &SerializableTilemap,
+	) -> Result<(), TilemapSerializationError> {
+		let tilemap = self.migrations.migrated(tilemap)?;
+		for (map_id, layers) in &tilemap.data {
+			for (layer_id, tiles) in layers.iter() {
+				self.load_tiles(tiles, *map_id, *layer_id);
+			}
+		}
+		self.load_revealed(&tilemap, None);
+		Ok(())
+	}
+
+	/// Load the given map from the given tilemap
+	///
+	/// The tilemap is first upgraded to the current version using the registered [`MapMigration`]s.
+	pub fn load_map(
+		&mut self,
+		tilemap: &SerializableTilemap,
+		map_id: u16,
+	) -> Result<(), TilemapSerializationError> {
+		let tilemap = self.migrations.migrated(tilemap)?;
+		if let Some(layers) = tilemap.data.get(&map_id) {
+			for (layer_id, tiles) in layers.iter() {
+				self.load_tiles(tiles, map_id, *layer_id);
+			}
+		}
+		self.load_revealed(&tilemap, Some(map_id));
+		Ok(())
+	}
+
+	/// Load the given layer of the given map from the given tilemap
+	///
+	/// The tilemap is first upgraded to the current version using the registered [`MapMigration`]s.
+	pub fn load_layer(
+		&mut self,
+		tilemap: &SerializableTilemap,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<(), TilemapSerializationError> {
+		let tilemap = self.migrations.migrated(tilemap)?;
+		if let Some(tiles) = tilemap
+			.data
+			.get(&map_id)
+			.and_then(|layers| layers.get(&layer_id))
+		{
+			self.load_tiles(tiles, map_id, layer_id);
+		}
+		Ok(())
+	}
+
+	/// Remove every tile contained in the given tilemap from the world
+	///
+	/// This is the inverse of [`load_maps`](Self::load_maps) and removes whatever tile currently
+	/// occupies each saved position.
+	pub fn unload_maps(&mut self, tilemap: &SerializableTilemap) {
+		for (map_id, layers) in &tilemap.data {
+			for (layer_id, tiles) in layers.iter() {
+				for tile in tiles {
+					self.tile_placer.remove(tile.pos, *map_id, *layer_id).ok();
+				}
+			}
+		}
+	}
+
+	/// Reveal the saved positions in the [`FogOfWar`], if it covers the given map (or any map if
+	/// `None`)
+	///
+	/// Positions are only ever revealed, so loading one chunk of a map at a time keeps the
+	/// positions revealed in the others.
+	fn load_revealed(&mut self, tilemap: &SerializableTilemap, map_id: Option<u16>) {
+		if let Some(fog) = &mut self.fog {
+			if map_id.map(|map_id| map_id != fog.map_id).unwrap_or(false) {
+				return;
+			}
+
+			if let Some(revealed) = tilemap.revealed.get(&fog.map_id) {
+				for (x, y) in revealed {
+					fog.reveal(TilePos { x: *x, y: *y }, 0);
+				}
+			}
+		}
+	}
+
+	fn load_tiles(&mut self, tiles: &[SerializableTile], map_id: u16, layer_id: u16) {
+		for tile in tiles {
+			let mut id = tile.id;
+			if let Some(tileset) = tile
+				.tileset
+				.as_ref()
+				.and_then(|name| self.tilesets.get_by_name(name))
+			{
+				id.tileset_id = *tileset.id();
+			}
+
+			if let Ok(PlacedTile::Added {
+				new_tile: (entity, ..),
+				..
+			}) = self.tile_placer.place(id, tile.pos, map_id, layer_id)
+			{
+				let mut cmds = self.commands.entity(entity);
+				if tile.flip != SerializableFlip::default() {
+					cmds.insert(TileFlip::from(tile.flip));
+				}
+				if let Some(anim) = tile.animation {
+					cmds.insert(AnimatedTile::from(anim));
+				}
+				if let Some(color) = tile.color {
+					cmds.insert(TileTint::from(color));
+				}
+				#[cfg(feature = "auto-tile")]
+				if let Some(index) = tile.resolved_index {
+					cmds.insert((TileTextureIndex(index), crate::auto::ResolvedAutoTile));
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,188 @@
+//! Tools for saving regions of a map as reusable prefabs
+
+use std::collections::BTreeMap;
+
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::math::UVec2;
+use bevy::prelude::Handle;
+use bevy::reflect::{Reflect, ReflectDeserialize, ReflectSerialize, TypeUuid};
+use bevy_ecs_tilemap::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{MapId, PlacedTile, TilePlacementError, TilePlacer, TileTint};
+use crate::stamp::region_pos;
+
+use super::{
+	serialize_color, SerializableAnimation, SerializableFlip, SerializableTile,
+	TilemapSerializationError, TilemapSerializer,
+};
+
+/// A multi-layer region of a map that can be saved to a `.prefab.ron` file and placed anywhere
+///
+/// Prefabs are created with [`TilemapSerializer::save_prefab`], loaded as assets using the
+/// [`TilePrefabLoader`], and placed with [`TilePlacer::place_prefab`].
+///
+/// Tile positions are relative to the bottom-left corner of the prefab, while layers are kept
+/// as-is, so a prefab always fills the same layers it was saved from.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Reflect, TypeUuid)]
+#[reflect_value(Debug, Serialize, Deserialize)]
+#[uuid = "5b0e3f6a-8d21-4c7e-b3a9-1f64d2e07c58"]
+pub struct TilePrefab {
+	/// The size of the prefab (in tiles)
+	pub size: UVec2,
+	/// The tiles of each layer
+	pub layers: BTreeMap<u16, Vec<SerializableTile>>,
+}
+
+impl TilePrefab {
+	/// Serialize this prefab to a RON string
+	pub fn to_ron(&self) -> Result<String, TilemapSerializationError> {
+		Ok(ron::ser::to_string_pretty(
+			self,
+			ron::ser::PrettyConfig::default(),
+		)?)
+	}
+
+	/// Deserialize a prefab from a RON string
+	pub fn from_ron(data: &str) -> Result<Self, TilemapSerializationError> {
+		Ok(ron::from_str(data)?)
+	}
+
+	/// Returns true if this prefab contains no tiles
+	pub fn is_empty(&self) -> bool {
+		self.layers.values().all(Vec::is_empty)
+	}
+}
+
+/// Asset loader for tile prefabs (`.prefab.ron` files)
+#[derive(Default)]
+pub struct TilePrefabLoader;
+
+impl AssetLoader for TilePrefabLoader {
+	fn load<'a>(
+		&'a self,
+		bytes: &'a [u8],
+		load_context: &'a mut LoadContext,
+	) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+		Box::pin(async move {
+			let prefab = ron::de::from_bytes::<TilePrefab>(bytes)?;
+			load_context.set_default_asset(LoadedAsset::new(prefab));
+			Ok(())
+		})
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&["prefab.ron"]
+	}
+}
+
+impl<'w, 's> TilemapSerializer<'w, 's> {
+	/// Save every layer of the given map within a region as a [`TilePrefab`]
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles.
+	pub fn save_prefab(&self, map_id: u16, origin: TilePos, size: UVec2) -> TilePrefab {
+		let mut layers = BTreeMap::<u16, Vec<SerializableTile>>::new();
+		for (index, parent, pos, tileset, flip, anim, color) in self.tiles.iter() {
+			if parent.map_id != map_id {
+				continue;
+			}
+
+			let pos = match region_pos(pos, &origin, size) {
+				Some(pos) => pos,
+				None => continue,
+			};
+			let tileset = match self.tilesets.get_by_id(&tileset.0) {
+				Some(tileset) => tileset,
+				None => continue,
+			};
+			if let Some(tile_id) = tileset.get_tile_id(&(index.0 as usize)) {
+				layers
+					.entry(parent.layer_id)
+					.or_default()
+					.push(SerializableTile {
+						id: *tile_id,
+						pos,
+						tileset: Some(tileset.name().to_string()),
+						flip: flip
+							.copied()
+							.map(SerializableFlip::from)
+							.unwrap_or_default(),
+						animation: anim.copied().map(SerializableAnimation::from),
+						color: color.copied().and_then(serialize_color),
+						resolved_index: None,
+					});
+			}
+		}
+
+		TilePrefab { size, layers }
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Place a loaded [`TilePrefab`] with its bottom-left corner at the given origin
+	///
+	/// Like [`place_stamp`](Self::place_stamp), every tile is validated before any are placed, so
+	/// an invalid tile results in an error without modifying the map. Saved flips, animations,
+	/// and tints are restored on the placed tiles.
+	///
+	/// Returns the results of each placed tile.
+	pub fn place_prefab<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		prefab: &Handle<TilePrefab>,
+		origin: Pos,
+		map_id: MId,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let origin = origin.into();
+		let prefab = self
+			.prefabs
+			.get(prefab)
+			.ok_or(TilePlacementError::UnloadedPrefab)?;
+
+		// === Resolve === //
+		let mut tiles = Vec::new();
+		for (layer_id, layer) in &prefab.layers {
+			for tile in layer {
+				let mut id = tile.id;
+				if let Some(tileset) = tile
+					.tileset
+					.as_ref()
+					.and_then(|name| self.tilesets.get_by_name(name))
+				{
+					id.tileset_id = *tileset.id();
+				}
+
+				let pos = TilePos {
+					x: origin.x + tile.pos.x,
+					y: origin.y + tile.pos.y,
+				};
+				self.can_place(&id, pos, map_id.into(), *layer_id, None)?;
+				tiles.push((id, pos, *layer_id, tile.flip, tile.animation, tile.color));
+			}
+		}
+
+		// === Place === //
+		let mut placed = Vec::with_capacity(tiles.len());
+		for (id, pos, layer_id, flip, animation, color) in tiles {
+			let result = self.place_unchecked(id, pos, map_id, layer_id)?;
+			if let PlacedTile::Added {
+				new_tile: (entity, ..),
+				..
+			} = result
+			{
+				let mut cmds = self.commands.entity(entity);
+				if flip != SerializableFlip::default() {
+					cmds.insert(TileFlip::from(flip));
+				}
+				if let Some(anim) = animation {
+					cmds.insert(AnimatedTile::from(anim));
+				}
+				if let Some(color) = color {
+					cmds.insert(TileTint::from(color));
+				}
+			}
+			placed.push(result);
+		}
+
+		Ok(placed)
+	}
+}
@@ -0,0 +1,138 @@
+//! Tools for replicating tile edits over the network
+
+use bevy::reflect::{Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy::utils::HashMap;
+use bevy_tileset::prelude::TileId;
+use serde::{Deserialize, Serialize};
+
+use crate::coord::TileCoord;
+use crate::prelude::{PlacedTile, TileChange, TilePlacementError, TilePlacer};
+
+/// A single edit contained in a [`TileDelta`]
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize, Reflect)]
+#[reflect_value(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TileEdit {
+	/// The coordinate of the edited tile
+	pub coord: TileCoord,
+	/// The tile placed at the coordinate (`None` if the tile was removed)
+	pub tile: Option<TileId>,
+}
+
+/// A serializable batch of tile edits, meant to be broadcast by a server to its clients
+///
+/// Deltas are usually created from the changes recorded in a
+/// [`TileChangeLog`](crate::prelude::TileChangeLog) and applied on the receiving side with
+/// [`TilePlacer::apply_deltas`].
+///
+/// Only the final state of each edited coordinate is kept. Auto tiles are sent without their
+/// resolved texture so that each receiver resolves them against its own copy of the map, while
+/// variants are kept so every receiver ends up with the same textures.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::ResMut;
+/// # use bevy_tileset_map::prelude::{TileChangeLog, TileDelta};
+/// fn broadcast_edits(mut log: ResMut<TileChangeLog>, mut sequence: bevy::prelude::Local<u64>) {
+///   if log.is_empty() {
+///     return;
+///   }
+///
+///   let delta = TileDelta::from_changes(*sequence, &log.drain());
+///   *sequence += 1;
+///   let message = serde_json::to_string(&delta).unwrap();
+///   // Send `message` to every client...
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Reflect)]
+#[reflect_value(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TileDelta {
+	/// The position of this delta in the stream of deltas
+	///
+	/// Deltas are applied in order of their sequence number.
+	pub sequence: u64,
+	/// The edits contained in this delta
+	pub edits: Vec<TileEdit>,
+}
+
+impl TileDelta {
+	/// Create a delta from the given changes
+	///
+	/// Changes are expected to be in the order they were made (as recorded by the
+	/// [`TileChangeLog`](crate::prelude::TileChangeLog)).
+	pub fn from_changes(sequence: u64, changes: &[TileChange]) -> Self {
+		let mut order = Vec::new();
+		let mut latest = HashMap::default();
+		for change in changes {
+			if latest.insert(change.coord, change.new).is_none() {
+				order.push(change.coord);
+			}
+		}
+
+		let edits = order
+			.into_iter()
+			.map(|coord| TileEdit {
+				coord,
+				tile: latest[&coord].map(strip_auto_index),
+			})
+			.collect();
+
+		Self { sequence, edits }
+	}
+
+	/// Returns true if this delta contains no edits
+	pub fn is_empty(&self) -> bool {
+		self.edits.is_empty()
+	}
+}
+
+/// Remove the resolved auto tile texture from a tile so it's resolved by the receiver
+fn strip_auto_index(tile_id: TileId) -> TileId {
+	#[cfg(feature = "auto-tile")]
+	{
+		let mut tile_id = tile_id;
+		tile_id.auto_index = None;
+		tile_id
+	}
+
+	#[cfg(not(feature = "auto-tile"))]
+	tile_id
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Apply the given deltas in order of their sequence number
+	///
+	/// Since every edit of a batch is applied within the same frame, auto tiles are resolved
+	/// once after all deltas have been applied.
+	///
+	/// Returns the results of each applied edit.
+	pub fn apply_deltas(
+		&mut self,
+		deltas: &[TileDelta],
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let mut deltas = deltas.iter().collect::<Vec<_>>();
+		deltas.sort_by_key(|delta| delta.sequence);
+
+		let mut placed = Vec::new();
+		for edit in deltas.into_iter().flat_map(|delta| delta.edits.iter()) {
+			let TileCoord {
+				pos,
+				map_id,
+				layer_id,
+			} = edit.coord;
+			match edit.tile {
+				Some(tile_id) => placed.push(self.place(tile_id, pos, map_id, layer_id)?),
+				None => {
+					if let Some(entity) = self.find_entity(pos, map_id, layer_id) {
+						self.remove(pos, map_id, layer_id)?;
+						placed.push(PlacedTile::Removed {
+							old_tile: Some((entity, None)),
+						});
+					}
+				},
+			}
+		}
+
+		Ok(placed)
+	}
+}
@@ -0,0 +1,68 @@
+//! Tools for saving the resolved state of auto tiles
+
+use bevy::prelude::Resource;
+use bevy_ecs_tilemap::prelude::TileTextureIndex;
+use bevy_tileset::prelude::TileId;
+
+use crate::auto::ResolvedAutoTile;
+
+use super::{TilemapDeserializer, TilemapSerializer};
+
+/// A resource determining how auto tiles are saved by the [`TilemapSerializer`]
+///
+/// By default, only the identity of each auto tile is saved and every auto tile is resolved again
+/// once loaded. For large maps, this can be skipped by storing the resolved texture index of each
+/// auto tile instead. Loaded tiles then keep their saved texture until one of their neighbors
+/// changes, or until they're checked with [`TilemapDeserializer::verify_auto_tiles`].
+///
+/// Prefabs never store resolved indices, since they need to blend in with their surroundings.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy_tileset_map::prelude::AutoTileSerialization;
+/// # let mut app = App::new();
+/// app.insert_resource(AutoTileSerialization::Store);
+/// ```
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AutoTileSerialization {
+	/// Resolve auto tiles again when loaded
+	Recompute,
+	/// Save the resolved texture index of auto tiles and reuse it when loaded
+	Store,
+}
+
+impl Default for AutoTileSerialization {
+	fn default() -> Self {
+		Self::Recompute
+	}
+}
+
+impl<'w, 's> TilemapSerializer<'w, 's> {
+	/// Get the resolved texture index to save for the given tile (if any)
+	pub(super) fn resolved_index(&self, tile_id: &TileId, index: &TileTextureIndex) -> Option<u32> {
+		match *self.auto_tile_serialization {
+			AutoTileSerialization::Store if tile_id.auto_index.is_some() => Some(index.0),
+			_ => None,
+		}
+	}
+}
+
+impl<'w, 's> TilemapDeserializer<'w, 's> {
+	/// Resolve every auto tile in the given map again
+	///
+	/// This is useful for checking maps loaded with their resolved indices (see
+	/// [`AutoTileSerialization::Store`]), such as after the tileset's rules have changed. The
+	/// tiles are resolved the next time the auto tile systems run.
+	pub fn verify_auto_tiles(&mut self, map_id: u16) {
+		for (entity, parent, auto_id) in self.auto_tiles.iter() {
+			if parent.map_id == map_id {
+				self.commands
+					.entity(entity)
+					.insert(*auto_id)
+					.remove::<ResolvedAutoTile>();
+			}
+		}
+	}
+}
@@ -0,0 +1,193 @@
+//! Tools for configuring the z-order and parallax of map layers
+
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::{Camera, Entity, GlobalTransform, Local, Query, Res, Resource, Transform};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilemapGridSize;
+
+use crate::elevation::{ElevationSettings, LayerElevation};
+use crate::manager::TilemapLayer;
+use crate::placement::MapId;
+
+/// The z-offset and parallax applied to a layer
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LayerSettings {
+	/// The offset added to the layer's z-position
+	pub z_offset: f32,
+	/// How much the layer moves relative to the camera
+	///
+	/// A factor of `1.0` moves the layer with the world (no parallax), while a factor of `0.0`
+	/// keeps it fixed to the camera. Values in between make the layer appear further away, and
+	/// values above `1.0` make it appear closer.
+	pub parallax: Vec2,
+}
+
+impl Default for LayerSettings {
+	fn default() -> Self {
+		Self {
+			z_offset: 0.0,
+			parallax: Vec2::ONE,
+		}
+	}
+}
+
+impl LayerSettings {
+	/// Create settings with the given z-offset and no parallax
+	pub fn with_z_offset(z_offset: f32) -> Self {
+		Self {
+			z_offset,
+			..Default::default()
+		}
+	}
+
+	/// Create settings with the given parallax factor and no z-offset
+	pub fn with_parallax(parallax: Vec2) -> Self {
+		Self {
+			parallax,
+			..Default::default()
+		}
+	}
+}
+
+/// A resource configuring the z-order and parallax of map layers
+///
+/// Settings are applied to every [`TilemapLayer`] each frame, so background and foreground layers
+/// don't need to have their transforms adjusted by hand. Settings for a specific map take
+/// priority over those for every map.
+///
+/// The layer's spawned transform is used as its origin: the z-offset is added to it and the
+/// parallax is computed from the first active camera.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::{App, Vec2};
+/// # use bevy_tileset_map::prelude::{LayerSettings, MapSettings};
+/// # let mut app = App::new();
+/// let mut settings = MapSettings::default();
+/// settings
+///   .insert_layer(0, LayerSettings::with_parallax(Vec2::splat(0.5)))
+///   .insert_layer(3, LayerSettings::with_z_offset(10.0));
+/// app.insert_resource(settings);
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MapSettings {
+	layers: HashMap<u16, LayerSettings>,
+	map_layers: HashMap<(u16, u16), LayerSettings>,
+}
+
+impl MapSettings {
+	/// Set the settings of the given layer in every map
+	pub fn insert_layer(&mut self, layer_id: u16, settings: LayerSettings) -> &mut Self {
+		self.layers.insert(layer_id, settings);
+		self
+	}
+
+	/// Set the settings of the given layer in a specific map
+	pub fn insert_map_layer<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+		settings: LayerSettings,
+	) -> &mut Self {
+		self.map_layers.insert((map_id.into(), layer_id), settings);
+		self
+	}
+
+	/// Remove the settings of the given layer in every map
+	pub fn remove_layer(&mut self, layer_id: u16) -> Option<LayerSettings> {
+		self.layers.remove(&layer_id)
+	}
+
+	/// Remove the settings of the given layer in a specific map
+	pub fn remove_map_layer<MId: MapId>(
+		&mut self,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<LayerSettings> {
+		self.map_layers.remove(&(map_id.into(), layer_id))
+	}
+
+	/// Get the settings applied to the given layer (if any)
+	pub fn get<MId: MapId>(&self, map_id: MId, layer_id: u16) -> Option<&LayerSettings> {
+		self.map_layers
+			.get(&(map_id.into(), layer_id))
+			.or_else(|| self.layers.get(&layer_id))
+	}
+}
+
+/// The position of a layer before its settings (and elevation) were applied
+struct LayerOrigin {
+	origin: Vec3,
+	/// The position last written by [`apply_layer_transforms`]
+	applied: Vec3,
+}
+
+/// __\[SYSTEM\]__ Positions each layer according to the [`MapSettings`] and its
+/// [`LayerElevation`]
+///
+/// Both are applied by this one system so they don't overwrite each other: elevated layers are
+/// placed relative to the origin of their base layer, after which the layer's own z-offset and
+/// parallax are added. If something else moves a layer (such as
+/// [`TilemapManager::swap_layers`]), its new position becomes its origin.
+///
+/// [`TilemapManager::swap_layers`]: crate::prelude::TilemapManager::swap_layers
+pub(crate) fn apply_layer_transforms(
+	settings: Res<MapSettings>,
+	elevation: Res<ElevationSettings>,
+	mut tilemaps: Query<(
+		Entity,
+		&TilemapLayer,
+		&TilemapGridSize,
+		&mut Transform,
+		Option<&LayerElevation>,
+	)>,
+	cameras: Query<(&Camera, &GlobalTransform)>,
+	mut origins: Local<HashMap<Entity, LayerOrigin>>,
+) {
+	let camera = cameras
+		.iter()
+		.find(|(camera, _)| camera.is_active)
+		.map(|(_, transform)| transform.translation().truncate())
+		.unwrap_or_default();
+
+	// === Origins === //
+	origins.retain(|entity, _| tilemaps.contains(*entity));
+	let mut bases = HashMap::<(u16, u16), Vec3>::default();
+	for (entity, layer, _, transform, _) in tilemaps.iter() {
+		let origin = origins.entry(entity).or_insert(LayerOrigin {
+			origin: transform.translation,
+			applied: transform.translation,
+		});
+		if transform.translation != origin.applied {
+			origin.origin = transform.translation;
+		}
+		bases.insert((layer.map_id, layer.layer_id), origin.origin);
+	}
+
+	// === Apply === //
+	for (entity, layer, grid_size, mut transform, elevated) in tilemaps.iter_mut() {
+		let origin = match origins.get_mut(&entity) {
+			Some(origin) => origin,
+			None => continue,
+		};
+
+		let mut translation = origin.origin;
+		if let Some(LayerElevation(height)) = elevated {
+			let base_layer = layer.layer_id.saturating_sub(*height);
+			if let Some(base) = bases.get(&(layer.map_id, base_layer)) {
+				let height = *height as f32;
+				translation = *base + Vec3::new(0.0, height * elevation.step * grid_size.y, height);
+			}
+		}
+		if let Some(layer_settings) = settings.get(layer.map_id, layer.layer_id) {
+			let offset = camera * (Vec2::ONE - layer_settings.parallax);
+			translation += offset.extend(layer_settings.z_offset);
+		}
+
+		if transform.translation != translation {
+			transform.translation = translation;
+		}
+		origin.applied = translation;
+	}
+}
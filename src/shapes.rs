@@ -0,0 +1,113 @@
+//! Tools for placing simple shapes of tiles
+
+use bevy::math::UVec2;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Fill a region of the given layer with a single tile
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. Like
+	/// [`place_stamp`](Self::place_stamp), the tile is validated before any are placed and auto
+	/// tiles are only updated once after the whole region is placed.
+	///
+	/// Returns the results of each placed tile.
+	pub fn place_rect<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let id = tile_id.into();
+		let stamp = rect_stamp(size, |_| Some(id));
+		self.place_stamp(&stamp, origin, map_id, layer_id)
+	}
+
+	/// Place a tile along the edges of a region, leaving its interior untouched
+	///
+	/// Works just like [`place_rect`](Self::place_rect) otherwise.
+	///
+	/// Returns the results of each placed tile.
+	pub fn place_rect_outline<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let id = tile_id.into();
+		let stamp = rect_stamp(size, |pos| is_edge(pos, size).then_some(id));
+		self.place_stamp(&stamp, origin, map_id, layer_id)
+	}
+
+	/// Place a hollow box, with `wall_id` along the edges of a region and `floor_id` filling
+	/// its interior
+	///
+	/// Both tiles are validated before any are placed, and auto tiles are only updated once
+	/// after the whole box is placed.
+	///
+	/// Returns the results of each placed tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy::math::UVec2;
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+	/// fn build_room(mut placer: TilePlacer) {
+	///   # let (wall, floor) = (TileId::new(0, 0), TileId::new(1, 0));
+	///   let origin = TilePos { x: 4, y: 4 };
+	///   placer
+	///     .place_box(wall, floor, origin, UVec2::new(8, 6), 0u16, 0)
+	///     .unwrap();
+	/// }
+	/// ```
+	pub fn place_box<
+		WallId: Into<TileId>,
+		FloorId: Into<TileId>,
+		Pos: Into<TilePos>,
+		MId: MapId,
+	>(
+		&mut self,
+		wall_id: WallId,
+		floor_id: FloorId,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let wall_id = wall_id.into();
+		let floor_id = floor_id.into();
+		let stamp = rect_stamp(size, |pos| {
+			Some(if is_edge(pos, size) {
+				wall_id
+			} else {
+				floor_id
+			})
+		});
+		self.place_stamp(&stamp, origin, map_id, layer_id)
+	}
+}
+
+/// Create a single-layer stamp of the given size, using the given function to pick each tile
+fn rect_stamp<F: Fn(TilePos) -> Option<TileId>>(size: UVec2, tile_at: F) -> TileStamp {
+	let mut stamp = TileStamp::new(size.x, size.y);
+	for y in 0..size.y {
+		for x in 0..size.x {
+			let pos = TilePos { x, y };
+			stamp.set(pos, 0, tile_at(pos));
+		}
+	}
+	stamp
+}
+
+/// Returns true if the given position lies on the edge of a region of the given size
+fn is_edge(pos: TilePos, size: UVec2) -> bool {
+	pos.x == 0 || pos.y == 0 || pos.x + 1 == size.x || pos.y + 1 == size.y
+}
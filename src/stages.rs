@@ -0,0 +1,220 @@
+//! Tools for tiles that progress through several stages, such as destructible terrain
+
+use bevy::prelude::{App, Resource};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::{TileGroupId, TileId, TilesetId};
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacementResult, TilePlacer};
+
+/// An ordered list of tiles a placed tile progresses through
+///
+/// For example, a wall could go from intact, to cracked, to crumbling. Tiles are matched by
+/// group, so every variant of a tile belongs to the same stage.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{TileId, TileStage};
+/// # let (wall, cracked, crumbling) = (TileId::new(0, 0), TileId::new(1, 0), TileId::new(2, 0));
+/// let stage = TileStage::new([wall, cracked, crumbling]).removed_after_last();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileStage {
+	tiles: Vec<TileId>,
+	remove_after_last: bool,
+}
+
+impl TileStage {
+	/// Create a stage from the given tiles, in order
+	pub fn new<I: IntoIterator<Item = TileId>>(tiles: I) -> Self {
+		Self {
+			tiles: tiles.into_iter().collect(),
+			remove_after_last: false,
+		}
+	}
+
+	/// Remove the tile when it advances past its last stage
+	///
+	/// By default, tiles stay at their last stage.
+	pub fn removed_after_last(mut self) -> Self {
+		self.remove_after_last = true;
+		self
+	}
+
+	/// The number of stages
+	pub fn len(&self) -> usize {
+		self.tiles.len()
+	}
+
+	/// Returns true if there are no stages
+	pub fn is_empty(&self) -> bool {
+		self.tiles.is_empty()
+	}
+
+	/// Get the tile of the given stage
+	pub fn get(&self, stage: usize) -> Option<TileId> {
+		self.tiles.get(stage).copied()
+	}
+
+	/// Get the stage of the given tile (if it belongs to this one)
+	pub fn stage_of(&self, tile_id: &TileId) -> Option<usize> {
+		self.tiles
+			.iter()
+			.position(|tile| tile.eq_tile_group(tile_id))
+	}
+}
+
+/// A resource containing every registered [`TileStage`]
+///
+/// A tile can only belong to a single stage. Registering a stage containing a tile that already
+/// belongs to another one moves it to the new stage.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileStages {
+	stages: Vec<TileStage>,
+	lookup: HashMap<(TilesetId, TileGroupId), usize>,
+}
+
+impl TileStages {
+	/// Register a stage
+	pub fn add(&mut self, stage: TileStage) -> &mut Self {
+		let index = self.stages.len();
+		for tile in &stage.tiles {
+			self.lookup.insert((tile.tileset_id, tile.group_id), index);
+		}
+		self.stages.push(stage);
+		self
+	}
+
+	/// Get the stage the given tile belongs to, along with its current stage index
+	pub fn get(&self, tile_id: &TileId) -> Option<(&TileStage, usize)> {
+		let index = self.lookup.get(&(tile_id.tileset_id, tile_id.group_id))?;
+		let stage = self.stages.get(*index)?;
+		Some((stage, stage.stage_of(tile_id)?))
+	}
+}
+
+/// An extension trait for registering [`TileStage`]s on an [`App`]
+pub trait AddTileStage {
+	/// Register a stage with the [`TileStages`] resource
+	fn add_tile_stage(&mut self, stage: TileStage) -> &mut Self;
+}
+
+impl AddTileStage for App {
+	fn add_tile_stage(&mut self, stage: TileStage) -> &mut Self {
+		self.init_resource::<TileStages>();
+		self.world.resource_mut::<TileStages>().add(stage);
+		self
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Get the current stage of the tile at the given position
+	///
+	/// Returns `None` if there is no tile or it doesn't belong to a [`TileStage`].
+	pub fn get_stage<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<usize> {
+		let tile_id = self.staged_tile(pos.into(), map_id.into(), layer_id).ok()?;
+		self.stages.get(&tile_id).map(|(_, stage)| stage)
+	}
+
+	/// Set the stage of the tile at the given position
+	///
+	/// The tile is replaced by the tile of the given stage, keeping its variant. Fails if there
+	/// is no tile belonging to a [`TileStage`] at the given position, or the stage doesn't exist.
+	pub fn set_stage<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		stage: usize,
+	) -> TilePlacementResult {
+		let pos = pos.into();
+		let tile_id = self.staged_tile(pos, map_id.into(), layer_id)?;
+		let (tile_stage, _) = self
+			.stages
+			.get(&tile_id)
+			.ok_or(TilePlacementError::NoStagedTile(pos))?;
+		let new_id = tile_stage
+			.get(stage)
+			.ok_or(TilePlacementError::InvalidStage(stage))?;
+
+		self.place_stage(tile_id, new_id, pos, map_id, layer_id)
+	}
+
+	/// Advance the tile at the given position to its next stage
+	///
+	/// If the tile is already at its last stage, it's removed if its [`TileStage`] was created
+	/// with [`removed_after_last`](TileStage::removed_after_last). Otherwise, nothing happens and
+	/// `None` is returned.
+	pub fn advance_stage<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Option<PlacedTile>, TilePlacementError> {
+		let pos = pos.into();
+		let tile_id = self.staged_tile(pos, map_id.into(), layer_id)?;
+		let (tile_stage, stage) = self
+			.stages
+			.get(&tile_id)
+			.ok_or(TilePlacementError::NoStagedTile(pos))?;
+
+		match tile_stage.get(stage + 1) {
+			Some(new_id) => self
+				.place_stage(tile_id, new_id, pos, map_id, layer_id)
+				.map(Some),
+			None if tile_stage.remove_after_last => {
+				let entity = self.find_entity(pos, map_id, layer_id);
+				self.remove(pos, map_id, layer_id)?;
+				Ok(Some(PlacedTile::Removed {
+					old_tile: entity.map(|entity| (entity, Some(tile_id))),
+				}))
+			},
+			None => Ok(None),
+		}
+	}
+
+	/// Replace a staged tile with the tile of another stage, keeping its variant
+	fn place_stage<MId: MapId>(
+		&mut self,
+		old_id: TileId,
+		new_id: TileId,
+		pos: TilePos,
+		map_id: MId,
+		layer_id: u16,
+	) -> TilePlacementResult {
+		#[cfg(feature = "variants")]
+		let new_id = {
+			let kept = TileId {
+				variant_index: old_id.variant_index,
+				..new_id
+			};
+			if self.get_tile_index(&kept).is_ok() {
+				kept
+			} else {
+				new_id
+			}
+		};
+		#[cfg(not(feature = "variants"))]
+		let _ = old_id;
+
+		self.place(new_id, pos, map_id, layer_id)
+	}
+
+	/// Get the ID of the tile at the given position
+	fn staged_tile(
+		&self,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Result<TileId, TilePlacementError> {
+		self.find_entity(pos, map_id, layer_id)
+			.and_then(|entity| self.tile_id_of(entity))
+			.ok_or(TilePlacementError::NoStagedTile(pos))
+	}
+}
@@ -0,0 +1,273 @@
+//! Tools for placing multi-tile structures
+
+use std::collections::BTreeMap;
+
+use bevy::math::UVec2;
+use bevy::reflect::Reflect;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+
+/// A small grid of tiles that can be placed all at once
+///
+/// Stamps can span multiple layers. Each layer of a stamp is relative to the layer it's placed
+/// on, so a stamp with tiles on layers `0` and `1` placed on layer `2` will fill layers `2` and
+/// `3`. Empty cells are left untouched when the stamp is placed.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{TileId, TileStamp};
+/// # let trunk = TileId::new(0, 0);
+/// # let leaves = TileId::new(1, 0);
+/// let tree = TileStamp::new(1, 2)
+///   .with_tile(trunk, TilePos { x: 0, y: 0 }, 0)
+///   .with_tile(leaves, TilePos { x: 0, y: 1 }, 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect_value(Debug, PartialEq)]
+#[cfg_attr(
+	feature = "serialization",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TileStamp {
+	size: UVec2,
+	layers: BTreeMap<u16, Vec<Option<TileId>>>,
+}
+
+impl TileStamp {
+	/// Create an empty stamp with the given size (in tiles)
+	pub fn new(width: u32, height: u32) -> Self {
+		Self {
+			size: UVec2::new(width, height),
+			layers: BTreeMap::default(),
+		}
+	}
+
+	/// The size of this stamp (in tiles)
+	pub fn size(&self) -> UVec2 {
+		self.size
+	}
+
+	/// Returns true if this stamp contains no tiles
+	pub fn is_empty(&self) -> bool {
+		self.iter().next().is_none()
+	}
+
+	/// Get the tile at the given position and relative layer
+	pub fn get<Pos: Into<TilePos>>(&self, pos: Pos, layer_id: u16) -> Option<&TileId> {
+		let index = self.index(pos.into())?;
+		self.layers.get(&layer_id)?.get(index)?.as_ref()
+	}
+
+	/// Set (or clear) the tile at the given position and relative layer
+	///
+	/// Returns the previous tile, or `None` if there was none or the position is out of bounds.
+	pub fn set<Pos: Into<TilePos>>(
+		&mut self,
+		pos: Pos,
+		layer_id: u16,
+		tile_id: Option<TileId>,
+	) -> Option<TileId> {
+		let index = self.index(pos.into())?;
+		let len = (self.size.x * self.size.y) as usize;
+		let layer = self
+			.layers
+			.entry(layer_id)
+			.or_insert_with(|| vec![None; len]);
+		std::mem::replace(&mut layer[index], tile_id)
+	}
+
+	/// Set the tile at the given position and relative layer
+	pub fn with_tile<Id: Into<TileId>, Pos: Into<TilePos>>(
+		mut self,
+		tile_id: Id,
+		pos: Pos,
+		layer_id: u16,
+	) -> Self {
+		self.set(pos, layer_id, Some(tile_id.into()));
+		self
+	}
+
+	/// Iterate over all tiles in this stamp, along with their position and relative layer
+	pub fn iter(&self) -> impl Iterator<Item = (TilePos, u16, &TileId)> + '_ {
+		let width = self.size.x.max(1);
+		self.layers.iter().flat_map(move |(layer_id, tiles)| {
+			tiles.iter().enumerate().filter_map(move |(index, tile)| {
+				let pos = TilePos {
+					x: index as u32 % width,
+					y: index as u32 / width,
+				};
+				tile.as_ref().map(|tile| (pos, *layer_id, tile))
+			})
+		})
+	}
+
+	/// Create the smallest stamp containing the given tiles (on layer `0`), along with the
+	/// position of its bottom-left corner
+	///
+	/// Returns `None` if no tiles are given.
+	pub(crate) fn fit<I: IntoIterator<Item = (TilePos, TileId)>>(
+		tiles: I,
+	) -> Option<(TilePos, Self)> {
+		let tiles = tiles.into_iter().collect::<Vec<_>>();
+		let min_x = tiles.iter().map(|(pos, _)| pos.x).min()?;
+		let min_y = tiles.iter().map(|(pos, _)| pos.y).min()?;
+		let max_x = tiles.iter().map(|(pos, _)| pos.x).max()?;
+		let max_y = tiles.iter().map(|(pos, _)| pos.y).max()?;
+
+		let mut stamp = Self::new(max_x - min_x + 1, max_y - min_y + 1);
+		for (pos, tile_id) in tiles {
+			let local = TilePos {
+				x: pos.x - min_x,
+				y: pos.y - min_y,
+			};
+			stamp.set(local, 0, Some(tile_id));
+		}
+		Some((TilePos { x: min_x, y: min_y }, stamp))
+	}
+
+	/// Get the index of the given position within a layer
+	fn index(&self, pos: TilePos) -> Option<usize> {
+		if pos.x < self.size.x && pos.y < self.size.y {
+			Some((pos.y * self.size.x + pos.x) as usize)
+		} else {
+			None
+		}
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Place a [`TileStamp`] with its bottom-left corner at the given origin
+	///
+	/// The stamp is placed atomically: every tile is validated before any are placed, so an
+	/// invalid tile results in an error without modifying the map. Since all tiles are placed
+	/// within the same frame, auto tiles are only updated once after the whole stamp is placed.
+	///
+	/// Returns the results of each placed tile.
+	pub fn place_stamp<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		stamp: &TileStamp,
+		origin: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let origin = origin.into();
+
+		// === Validate === //
+		for (pos, layer, tile_id) in stamp.iter() {
+			let pos = TilePos {
+				x: origin.x + pos.x,
+				y: origin.y + pos.y,
+			};
+			self.can_place(tile_id, pos, map_id.into(), layer_id + layer, None)?;
+		}
+
+		// === Place === //
+		let mut placed = Vec::new();
+		for (pos, layer, tile_id) in stamp.iter() {
+			let pos = TilePos {
+				x: origin.x + pos.x,
+				y: origin.y + pos.y,
+			};
+			placed.push(self.place_unchecked(*tile_id, pos, map_id, layer_id + layer)?);
+		}
+
+		Ok(placed)
+	}
+
+	/// Copy the tiles of the given layer within a region into a [`TileStamp`]
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. Tiles are
+	/// copied by their [`TileId`], so animated tiles and auto tiles keep their animation and auto
+	/// tile group when pasted.
+	pub fn copy_region<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		origin: Pos,
+		size: UVec2,
+		map_id: MId,
+		layer_id: u16,
+	) -> TileStamp {
+		let origin = origin.into();
+		let map_id = map_id.into();
+		let mut stamp = TileStamp::new(size.x, size.y);
+
+		for (_, pos, parent, index, tileset) in self.region_query.iter() {
+			if parent.map_id != map_id || parent.layer_id != layer_id {
+				continue;
+			}
+
+			if let Some(pos) = region_pos(pos, &origin, size) {
+				if let Some(tile_id) = self.resolve_tile_id(index, tileset) {
+					stamp.set(pos, 0, Some(tile_id));
+				}
+			}
+		}
+
+		stamp
+	}
+
+	/// Paste a [`TileStamp`] (usually created by [`copy_region`](Self::copy_region)) with its
+	/// bottom-left corner at the given origin
+	///
+	/// Unlike [`place_stamp`](Self::place_stamp), this replaces the entire region covered by the
+	/// stamp: any existing tile in a cell left empty by the stamp is removed.
+	pub fn paste<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		stamp: &TileStamp,
+		origin: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let origin = origin.into();
+		let size = stamp.size();
+
+		// === Validate === //
+		for (_, layer, tile_id) in stamp.iter() {
+			if !self.skips_placement(tile_id, map_id.into(), layer_id + layer) {
+				self.get_tile_index(tile_id)?;
+			}
+		}
+
+		// === Clear === //
+		let map = map_id.into();
+		let mut cleared = Vec::new();
+		for (entity, pos, parent, index, tileset) in self.region_query.iter() {
+			let layer = match parent.layer_id.checked_sub(layer_id) {
+				Some(layer) if parent.map_id == map && stamp.layers.contains_key(&layer) => layer,
+				_ => continue,
+			};
+
+			if let Some(local) = region_pos(pos, &origin, size) {
+				if stamp.get(local, layer).is_none() {
+					let tile_id = self.resolve_tile_id(index, tileset);
+					cleared.push((*pos, parent.layer_id, entity, tile_id));
+				}
+			}
+		}
+
+		let mut placed = Vec::new();
+		for (pos, layer, entity, tile_id) in cleared {
+			self.remove(pos, map_id, layer)?;
+			placed.push(PlacedTile::Removed {
+				old_tile: Some((entity, tile_id)),
+			});
+		}
+
+		placed.extend(self.place_stamp(stamp, origin, map_id, layer_id)?);
+		Ok(placed)
+	}
+}
+
+/// Get the position of a tile relative to a region, if it's contained within it
+pub(crate) fn region_pos(pos: &TilePos, origin: &TilePos, size: UVec2) -> Option<TilePos> {
+	let x = pos.x.checked_sub(origin.x)?;
+	let y = pos.y.checked_sub(origin.y)?;
+	if x < size.x && y < size.y {
+		Some(TilePos { x, y })
+	} else {
+		None
+	}
+}
@@ -0,0 +1,119 @@
+//! Tools for placing tiles without naming their map and layer
+
+use bevy::prelude::Resource;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, TilePlacementError, TilePlacementResult, TilePlacer};
+
+/// A resource containing the map and layer targeted by the `*_default` methods of the
+/// [`TilePlacer`]
+///
+/// Most projects only use a single map, so these methods spare them from passing the same map
+/// and layer to every call. The initial target can be set using
+/// [`TilesetMapPlugin::with_default_target`].
+///
+/// [`TilesetMapPlugin::with_default_target`]: crate::prelude::TilesetMapPlugin::with_default_target
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::ResMut;
+/// # use bevy_tileset_map::prelude::DefaultMapTarget;
+/// fn edit_props(mut target: ResMut<DefaultMapTarget>) {
+///   target.layer_id = 1;
+/// }
+/// ```
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DefaultMapTarget {
+	/// The targeted map
+	pub map_id: u16,
+	/// The targeted layer
+	pub layer_id: u16,
+}
+
+impl DefaultMapTarget {
+	/// Target the given layer of the given map
+	pub fn new<MId: MapId>(map_id: MId, layer_id: u16) -> Self {
+		Self {
+			map_id: map_id.into(),
+			layer_id,
+		}
+	}
+}
+
+impl Default for DefaultMapTarget {
+	fn default() -> Self {
+		Self {
+			map_id: 0,
+			layer_id: 0,
+		}
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// The map and layer targeted by the `*_default` methods
+	pub fn default_target(&self) -> DefaultMapTarget {
+		*self.default_target
+	}
+
+	/// Like [`place`](Self::place), placing the tile in the [`DefaultMapTarget`]
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+	/// fn place_grass(mut placer: TilePlacer) {
+	///   # let grass = TileId::new(0, 0);
+	///   placer.place_default(grass, TilePos { x: 2, y: 3 }).unwrap();
+	/// }
+	/// ```
+	pub fn place_default<Id: Into<TileId>, Pos: Into<TilePos> + Clone>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+	) -> TilePlacementResult {
+		let DefaultMapTarget { map_id, layer_id } = self.default_target();
+		self.place(tile_id, pos, map_id, layer_id)
+	}
+
+	/// Like [`try_place`](Self::try_place), placing the tile in the [`DefaultMapTarget`]
+	pub fn try_place_default<Id: Into<TileId>, Pos: Into<TilePos> + Clone>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+	) -> TilePlacementResult {
+		let DefaultMapTarget { map_id, layer_id } = self.default_target();
+		self.try_place(tile_id, pos, map_id, layer_id)
+	}
+
+	/// Like [`replace`](Self::replace), placing the tile in the [`DefaultMapTarget`]
+	pub fn replace_default<Id: Into<TileId>, Pos: Into<TilePos> + Clone>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+	) -> TilePlacementResult {
+		let DefaultMapTarget { map_id, layer_id } = self.default_target();
+		self.replace(tile_id, pos, map_id, layer_id)
+	}
+
+	/// Like [`toggle`](Self::toggle), toggling the tile in the [`DefaultMapTarget`]
+	pub fn toggle_default<Id: Into<TileId>, Pos: Into<TilePos> + Clone>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+	) -> TilePlacementResult {
+		let DefaultMapTarget { map_id, layer_id } = self.default_target();
+		self.toggle(tile_id, pos, map_id, layer_id)
+	}
+
+	/// Like [`remove`](Self::remove), removing the tile from the [`DefaultMapTarget`]
+	pub fn remove_default<Pos: Into<TilePos>>(
+		&mut self,
+		pos: Pos,
+	) -> Result<(), TilePlacementError> {
+		let DefaultMapTarget { map_id, layer_id } = self.default_target();
+		self.remove(pos, map_id, layer_id)
+	}
+}
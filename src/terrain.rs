@@ -0,0 +1,196 @@
+//! Tools for raising and lowering terrain
+
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::coord::circle;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// A resource containing the tiles used for each height of the terrain, from lowest to highest
+///
+/// Raising terrain replaces each tile with the tile of the next tier (and lowering it with the
+/// tile of the previous one). Tiers are usually auto tiles, so the transitions between them are
+/// fixed automatically as the terrain changes.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::App;
+/// # use bevy_tileset_map::prelude::{TerrainTiers, TileId};
+/// # let mut app = App::new();
+/// # let (water, sand, grass, cliff) =
+/// #   (TileId::new(0, 0), TileId::new(1, 0), TileId::new(2, 0), TileId::new(3, 0));
+/// app.insert_resource(TerrainTiers::new([water, sand, grass, cliff]));
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TerrainTiers {
+	tiers: Vec<TileId>,
+}
+
+impl TerrainTiers {
+	/// Create the given tiers, from lowest to highest
+	pub fn new<I: IntoIterator<Item = TileId>>(tiers: I) -> Self {
+		Self {
+			tiers: tiers.into_iter().collect(),
+		}
+	}
+
+	/// Add a tier above all existing ones
+	pub fn push<Id: Into<TileId>>(&mut self, tile_id: Id) -> &mut Self {
+		self.tiers.push(tile_id.into());
+		self
+	}
+
+	/// Get the tile of the given tier
+	pub fn get(&self, tier: usize) -> Option<&TileId> {
+		self.tiers.get(tier)
+	}
+
+	/// Get the tier of the given tile
+	///
+	/// Tiles are matched by their group, so any variant of a tier's tile belongs to that tier.
+	pub fn tier_of(&self, tile_id: &TileId) -> Option<usize> {
+		self.tiers.iter().position(|tier| {
+			tier.group_id == tile_id.group_id && tier.tileset_id == tile_id.tileset_id
+		})
+	}
+
+	/// The number of tiers
+	pub fn len(&self) -> usize {
+		self.tiers.len()
+	}
+
+	/// Returns true if no tiers have been added
+	pub fn is_empty(&self) -> bool {
+		self.tiers.is_empty()
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Raise the terrain within `radius` tiles of the given position by one [tier](TerrainTiers)
+	///
+	/// Empty cells are filled with the lowest tier, while tiles that aren't part of any tier are
+	/// left untouched. To keep each transition between neighboring tiers, surrounding tiles more
+	/// than one tier below a raised tile are raised as well, cascading outwards as needed.
+	///
+	/// All changes are placed at once (see [`place_stamp`](Self::place_stamp)), so auto tiles
+	/// are only updated once. Returns the results of each placed tile.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::TilePlacer;
+	/// fn raise_island(mut placer: TilePlacer) {
+	///   placer.raise_terrain(TilePos { x: 16, y: 16 }, 3, 0u16, 0).unwrap();
+	/// }
+	/// ```
+	pub fn raise_terrain<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		radius: u32,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		self.terraform(pos.into(), radius, map_id.into(), layer_id, true)
+	}
+
+	/// Lower the terrain within `radius` tiles of the given position by one [tier](TerrainTiers)
+	///
+	/// Tiles already at the lowest tier, empty cells, and tiles that aren't part of any tier are
+	/// left untouched. Like [`raise_terrain`](Self::raise_terrain), surrounding tiles more than
+	/// one tier above a lowered tile are lowered as well.
+	pub fn lower_terrain<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		radius: u32,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		self.terraform(pos.into(), radius, map_id.into(), layer_id, false)
+	}
+
+	/// Raise or lower the terrain around the given position, cascading to its surroundings
+	fn terraform(
+		&mut self,
+		center: TilePos,
+		radius: u32,
+		map_id: u16,
+		layer_id: u16,
+		raise: bool,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		if self.terrain_tiers.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let top = self.terrain_tiers.len() - 1;
+		let grid = self.grids.get(map_id, layer_id);
+		let mut tiers: HashMap<TilePos, Option<usize>> = HashMap::default();
+		let mut changed: HashMap<TilePos, usize> = HashMap::default();
+		let mut open = Vec::new();
+
+		let area = circle(center, radius).filter(|pos| pos.within_map_bounds(&grid.size));
+		for pos in area {
+			let tier = self.terrain_tier(&mut tiers, pos, map_id, layer_id);
+			let next = match (tier, raise) {
+				(None, true) if !self.occupancy.is_occupied(pos, map_id, layer_id) => 0,
+				(Some(tier), true) if tier < top => tier + 1,
+				(Some(tier), false) if tier > 0 => tier - 1,
+				_ => continue,
+			};
+			tiers.insert(pos, Some(next));
+			changed.insert(pos, next);
+			open.push(pos);
+		}
+
+		// === Cascade === //
+		while let Some(pos) = open.pop() {
+			let tier = changed[&pos];
+			for neighbor in grid.neighbors(&pos).into_iter().flatten() {
+				let other = match self.terrain_tier(&mut tiers, neighbor, map_id, layer_id) {
+					Some(other) => other,
+					None => continue,
+				};
+
+				let next = if raise && other + 1 < tier {
+					tier - 1
+				} else if !raise && other > tier + 1 {
+					tier + 1
+				} else {
+					continue;
+				};
+				tiers.insert(neighbor, Some(next));
+				changed.insert(neighbor, next);
+				open.push(neighbor);
+			}
+		}
+
+		// === Place === //
+		let tiles = changed
+			.into_iter()
+			.filter_map(|(pos, tier)| Some((pos, *self.terrain_tiers.get(tier)?)));
+		match TileStamp::fit(tiles) {
+			Some((origin, stamp)) => self.place_stamp(&stamp, origin, map_id, layer_id),
+			None => Ok(Vec::new()),
+		}
+	}
+
+	/// Get the terrain tier of the tile at the given position, caching it in `tiers`
+	fn terrain_tier(
+		&self,
+		tiers: &mut HashMap<TilePos, Option<usize>>,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Option<usize> {
+		*tiers.entry(pos).or_insert_with(|| {
+			let id = self
+				.find_entity(pos, map_id, layer_id)
+				.and_then(|entity| self.tile_id_of(entity))?;
+			self.terrain_tiers.tier_of(&id)
+		})
+	}
+}
@@ -0,0 +1,164 @@
+//! Tools for making follow-up edits to just-placed tiles
+
+use bevy::prelude::{Bundle, Color, Commands, Entity};
+use bevy_ecs_tilemap::prelude::{AnimatedTile, TileFlip, TilePos};
+use bevy_tileset::prelude::TileId;
+
+use crate::color::TileTint;
+use crate::coord::TileCoord;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+
+/// A handle to a tile that was just placed
+///
+/// Returned by [`TilePlacer::place_ref`] (and friends), this knows where the tile was placed and
+/// allows making follow-up edits to it without having to query for it again. Edits are queued as
+/// commands, just like the placement itself.
+///
+/// No handle is returned if the [`PlacementPolicy`](crate::prelude::PlacementPolicy) skipped the
+/// tile, since no tile was placed.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy::prelude::Color;
+/// # use bevy_ecs_tilemap::prelude::TilePos;
+/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+/// fn place_torch(mut placer: TilePlacer) {
+///   # let torch = TileId::new(0, 0);
+///   let placed = placer
+///     .place_ref(torch, TilePos { x: 3, y: 4 }, 0u16, 1)
+///     .unwrap();
+///   if let Some(mut placed) = placed {
+///     placed.set_color(Color::ORANGE).animate(0, 4, 1.0);
+///   }
+/// }
+/// ```
+pub struct PlacedTileRef<'a, 'w, 's> {
+	commands: &'a mut Commands<'w, 's>,
+	entity: Entity,
+	tile_id: TileId,
+	old_tile: Option<(Entity, Option<TileId>)>,
+	coord: TileCoord,
+}
+
+impl<'a, 'w, 's> PlacedTileRef<'a, 'w, 's> {
+	/// The placed tile entity
+	pub fn entity(&self) -> Entity {
+		self.entity
+	}
+
+	/// The ID of the placed tile
+	pub fn tile_id(&self) -> TileId {
+		self.tile_id
+	}
+
+	/// The coordinate the tile was placed at
+	pub fn coord(&self) -> TileCoord {
+		self.coord
+	}
+
+	/// The tile replaced by this one (if any)
+	pub fn old_tile(&self) -> Option<(Entity, Option<TileId>)> {
+		self.old_tile
+	}
+
+	/// Get the [`PlacedTile`] describing this placement
+	pub fn result(&self) -> PlacedTile {
+		PlacedTile::Added {
+			old_tile: self.old_tile,
+			new_tile: (self.entity, self.tile_id),
+		}
+	}
+
+	/// Tint the tile with the given color
+	pub fn set_color(&mut self, color: Color) -> &mut Self {
+		self.insert(TileTint(color))
+	}
+
+	/// Flip the tile
+	pub fn flip(&mut self, flip: TileFlip) -> &mut Self {
+		self.insert(flip)
+	}
+
+	/// Animate the tile through the given range of texture indices
+	pub fn animate(&mut self, start: u32, end: u32, speed: f32) -> &mut Self {
+		self.insert(AnimatedTile { start, end, speed })
+	}
+
+	/// Insert the given bundle onto the tile
+	pub fn insert<B: Bundle>(&mut self, bundle: B) -> &mut Self {
+		self.commands.entity(self.entity).insert(bundle);
+		self
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Same as [`place`](Self::place), but returning a [`PlacedTileRef`] to the placed tile
+	pub fn place_ref<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Option<PlacedTileRef<'_, 'w, 's>>, TilePlacementError> {
+		let result = self.place(tile_id, pos.clone(), map_id, layer_id)?;
+		Ok(self.tile_ref(result, pos.into(), map_id.into(), layer_id))
+	}
+
+	/// Same as [`try_place`](Self::try_place), but returning a [`PlacedTileRef`] to the placed
+	/// tile
+	pub fn try_place_ref<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Option<PlacedTileRef<'_, 'w, 's>>, TilePlacementError> {
+		let result = self.try_place(tile_id, pos.clone(), map_id, layer_id)?;
+		Ok(self.tile_ref(result, pos.into(), map_id.into(), layer_id))
+	}
+
+	/// Same as [`replace`](Self::replace), but returning a [`PlacedTileRef`] to the placed tile
+	pub fn replace_ref<Id: Into<TileId>, Pos: Into<TilePos> + Clone, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Option<PlacedTileRef<'_, 'w, 's>>, TilePlacementError> {
+		let result = self.replace(tile_id, pos.clone(), map_id, layer_id)?;
+		Ok(self.tile_ref(result, pos.into(), map_id.into(), layer_id))
+	}
+
+	/// Create a [`PlacedTileRef`] from the result of a placement, if it placed a tile
+	///
+	/// The position is resolved again, since the [`OutOfBoundsPolicy`] may have clamped the tile
+	/// to a different position than the one requested.
+	///
+	/// [`OutOfBoundsPolicy`]: crate::prelude::OutOfBoundsPolicy
+	fn tile_ref(
+		&mut self,
+		result: PlacedTile,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+	) -> Option<PlacedTileRef<'_, 'w, 's>> {
+		let (old_tile, (entity, tile_id)) = match result {
+			PlacedTile::Added { old_tile, new_tile } => (old_tile, new_tile),
+			PlacedTile::Removed { .. } | PlacedTile::Skipped => return None,
+		};
+		let pos = self.resolve_bounds(pos, map_id, layer_id).unwrap_or(pos);
+
+		Some(PlacedTileRef {
+			commands: &mut self.commands,
+			entity,
+			tile_id,
+			old_tile,
+			coord: TileCoord {
+				pos,
+				map_id,
+				layer_id,
+			},
+		})
+	}
+}
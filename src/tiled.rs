@@ -0,0 +1,328 @@
+//! Tools for exporting tilemaps to the [Tiled](https://www.mapeditor.org/) `.tmx` format
+
+use std::fmt::Write;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{AssetServer, Query, Res};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_tilemap::prelude::*;
+use bevy_tileset::prelude::{TilesetId, TilesetParent, Tilesets};
+
+use crate::manager::TilemapLayer;
+
+/// Tiled flag marking a tile as flipped horizontally
+const FLIPPED_HORIZONTALLY: u32 = 0x80000000;
+/// Tiled flag marking a tile as flipped vertically
+const FLIPPED_VERTICALLY: u32 = 0x40000000;
+/// Tiled flag marking a tile as flipped diagonally
+const FLIPPED_DIAGONALLY: u32 = 0x20000000;
+
+/// How tilesets should be written into an exported `.tmx` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TiledTilesetMode {
+	/// Tilesets are embedded directly in the `.tmx` file
+	Embedded,
+	/// Tilesets are referenced as external `.tsx` files
+	///
+	/// Each tileset is referenced as `<directory>/<tileset name>.tsx`. The contents of these
+	/// files can be generated with [`TilemapExporter::export_tileset`].
+	Referenced {
+		/// The directory containing the `.tsx` files (relative to the `.tmx` file)
+		directory: String,
+	},
+}
+
+impl Default for TiledTilesetMode {
+	fn default() -> Self {
+		Self::Embedded
+	}
+}
+
+/// Settings used to configure the [`TilemapExporter`]
+#[derive(Debug, Clone, Default)]
+pub struct TiledExportSettings {
+	/// How tilesets should be written
+	pub tileset_mode: TiledTilesetMode,
+	/// The prefix prepended to tile image paths
+	///
+	/// Image paths are taken from the `AssetServer` and so are relative to the assets folder. This
+	/// prefix can be used to make them relative to the exported file instead.
+	pub image_prefix: String,
+}
+
+/// A system parameter that can be used to export tilemaps to Tiled's `.tmx` format
+///
+/// Tilesets are exported as Tiled "collection of images" tilesets, using the source image of
+/// each tile in the [`Tileset`](bevy_tileset::prelude::Tileset). This means the exported file
+/// can be opened in Tiled directly, without needing access to the generated texture atlas.
+#[derive(SystemParam)]
+pub struct TilemapExporter<'w, 's> {
+	tiles: Query<
+		'w,
+		's,
+		(
+			&'static TilePos,
+			&'static TileParent,
+			&'static TileTextureIndex,
+			&'static TilesetParent,
+			Option<&'static TileFlip>,
+		),
+	>,
+	tilemaps: Query<'w, 's, (&'static TilemapLayer, &'static TilemapSize)>,
+	tilesets: Tilesets<'w, 's>,
+	asset_server: Res<'w, AssetServer>,
+}
+
+/// A tile gathered for export
+struct ExportTile {
+	pos: TilePos,
+	layer_id: u16,
+	index: u32,
+	tileset_id: TilesetId,
+	flip: TileFlip,
+}
+
+impl<'w, 's> TilemapExporter<'w, 's> {
+	/// Export the given map as the contents of a `.tmx` file
+	///
+	/// Each layer of the map becomes a Tiled tile layer (ordered by layer ID). The exported map is
+	/// as large as the largest [`TilemapSize`] of its layers, so empty rows and columns are kept.
+	///
+	/// Returns `None` if the map contains no tiles or references an unloaded tileset.
+	pub fn export_map(&self, map_id: u16, settings: &TiledExportSettings) -> Option<String> {
+		let tiles = self
+			.tiles
+			.iter()
+			.filter(|(_, parent, ..)| parent.map_id == map_id)
+			.map(|(pos, parent, index, tileset, flip)| ExportTile {
+				pos: *pos,
+				layer_id: parent.layer_id,
+				index: index.0,
+				tileset_id: tileset.0,
+				flip: flip.copied().unwrap_or_default(),
+			})
+			.collect::<Vec<_>>();
+
+		if tiles.is_empty() {
+			return None;
+		}
+
+		// === Map Bounds === //
+		let mut width = 0;
+		let mut height = 0;
+		for (layer, size) in self.tilemaps.iter() {
+			if layer.map_id == map_id {
+				width = width.max(size.x);
+				height = height.max(size.y);
+			}
+		}
+		// Layers without a tilemap are sized to fit their tiles
+		for tile in &tiles {
+			width = width.max(tile.pos.x + 1);
+			height = height.max(tile.pos.y + 1);
+		}
+
+		// === Tilesets === //
+		let mut tileset_ids = tiles
+			.iter()
+			.map(|tile| tile.tileset_id)
+			.collect::<HashSet<_>>()
+			.into_iter()
+			.collect::<Vec<_>>();
+		tileset_ids.sort_unstable();
+
+		let mut first_gids = HashMap::default();
+		let mut next_gid = 1u32;
+		let mut tile_size = None;
+		for tileset_id in &tileset_ids {
+			let tileset = self.tilesets.get_by_id(tileset_id)?;
+			tile_size.get_or_insert(tileset.tile_size());
+			first_gids.insert(*tileset_id, next_gid);
+			next_gid += self.tile_count(tileset_id) as u32;
+		}
+		let tile_size = tile_size?;
+
+		let mut out = String::new();
+		writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).ok()?;
+		writeln!(
+			out,
+			r#"<map version="1.10" orientation="orthogonal" renderorder="right-down" width="{}" height="{}" tilewidth="{}" tileheight="{}" infinite="0" nextlayerid="{}" nextobjectid="1">"#,
+			width,
+			height,
+			tile_size.x,
+			tile_size.y,
+			tiles.iter().map(|tile| tile.layer_id).max()? as u32 + 2,
+		)
+		.ok()?;
+
+		for tileset_id in &tileset_ids {
+			let first_gid = first_gids[tileset_id];
+			match &settings.tileset_mode {
+				TiledTilesetMode::Embedded => {
+					self.write_tileset(&mut out, tileset_id, Some(first_gid), settings)?;
+				},
+				TiledTilesetMode::Referenced { directory } => {
+					let tileset = self.tilesets.get_by_id(tileset_id)?;
+					writeln!(
+						out,
+						r#" <tileset firstgid="{}" source="{}"/>"#,
+						first_gid,
+						escape(&join_path(directory, &format!("{}.tsx", tileset.name()))),
+					)
+					.ok()?;
+				},
+			}
+		}
+
+		// === Layers === //
+		let mut layers: HashMap<u16, Vec<u32>> = HashMap::default();
+		for tile in &tiles {
+			let layer = layers
+				.entry(tile.layer_id)
+				.or_insert_with(|| vec![0; (width * height) as usize]);
+
+			// Tiled maps go top-down while bevy_ecs_tilemap goes bottom-up
+			let row = height - 1 - tile.pos.y;
+			let mut gid = first_gids[&tile.tileset_id] + tile.index;
+			if tile.flip.x {
+				gid |= FLIPPED_HORIZONTALLY;
+			}
+			if tile.flip.y {
+				gid |= FLIPPED_VERTICALLY;
+			}
+			if tile.flip.d {
+				gid |= FLIPPED_DIAGONALLY;
+			}
+			layer[(row * width + tile.pos.x) as usize] = gid;
+		}
+
+		let mut layer_ids = layers.keys().copied().collect::<Vec<_>>();
+		layer_ids.sort_unstable();
+		for layer_id in layer_ids {
+			let data = &layers[&layer_id];
+			writeln!(
+				out,
+				r#" <layer id="{}" name="Layer {}" width="{}" height="{}">"#,
+				layer_id as u32 + 1,
+				layer_id,
+				width,
+				height
+			)
+			.ok()?;
+			writeln!(out, r#"  <data encoding="csv">"#).ok()?;
+			let rows = data
+				.chunks(width as usize)
+				.map(|row| {
+					row.iter()
+						.map(|gid| gid.to_string())
+						.collect::<Vec<_>>()
+						.join(",")
+				})
+				.collect::<Vec<_>>();
+			writeln!(out, "{}", rows.join(",\n")).ok()?;
+			writeln!(out, "  </data>").ok()?;
+			writeln!(out, " </layer>").ok()?;
+		}
+
+		writeln!(out, "</map>").ok()?;
+		Some(out)
+	}
+
+	/// Export the given tileset as the contents of a `.tsx` file
+	///
+	/// This is needed when exporting maps using [`TiledTilesetMode::Referenced`].
+	///
+	/// Returns `None` if the tileset does not exist.
+	pub fn export_tileset(
+		&self,
+		tileset_id: &TilesetId,
+		settings: &TiledExportSettings,
+	) -> Option<String> {
+		let mut out = String::new();
+		writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).ok()?;
+		self.write_tileset(&mut out, tileset_id, None, settings)?;
+		Some(out)
+	}
+
+	/// Write a `<tileset>` element for the given tileset
+	///
+	/// If `first_gid` is given, the tileset is written as an embedded tileset.
+	fn write_tileset(
+		&self,
+		out: &mut String,
+		tileset_id: &TilesetId,
+		first_gid: Option<u32>,
+		settings: &TiledExportSettings,
+	) -> Option<()> {
+		let tileset = self.tilesets.get_by_id(tileset_id)?;
+		let tile_size = tileset.tile_size();
+		let count = self.tile_count(tileset_id);
+
+		let first_gid = first_gid
+			.map(|gid| format!(r#"firstgid="{}" "#, gid))
+			.unwrap_or_default();
+		writeln!(
+			out,
+			r#" <tileset {}name="{}" tilewidth="{}" tileheight="{}" tilecount="{}" columns="0">"#,
+			first_gid,
+			escape(tileset.name()),
+			tile_size.x,
+			tile_size.y,
+			count
+		)
+		.ok()?;
+		writeln!(out, r#"  <grid orientation="orthogonal" width="1" height="1"/>"#).ok()?;
+
+		for index in 0..count {
+			let handle = tileset.get_tile_handle(&index)?;
+			let path = self.asset_server.get_handle_path(handle)?;
+			let source = join_path(
+				&settings.image_prefix,
+				&path.path().to_string_lossy().replace('\\', "/"),
+			);
+
+			writeln!(out, r#"  <tile id="{}">"#, index).ok()?;
+			writeln!(
+				out,
+				r#"   <image width="{}" height="{}" source="{}"/>"#,
+				tile_size.x,
+				tile_size.y,
+				escape(&source)
+			)
+			.ok()?;
+			writeln!(out, "  </tile>").ok()?;
+		}
+
+		writeln!(out, " </tileset>").ok()?;
+		Some(())
+	}
+
+	/// Get the number of tile textures contained in the given tileset
+	fn tile_count(&self, tileset_id: &TilesetId) -> usize {
+		if let Some(tileset) = self.tilesets.get_by_id(tileset_id) {
+			(0..)
+				.take_while(|index| tileset.get_tile_handle(index).is_some())
+				.count()
+		} else {
+			0
+		}
+	}
+}
+
+/// Join a directory prefix and a path using forward slashes
+fn join_path(prefix: &str, path: &str) -> String {
+	if prefix.is_empty() {
+		path.to_string()
+	} else {
+		format!("{}/{}", prefix.trim_end_matches('/'), path)
+	}
+}
+
+/// Escape a string for use within an XML attribute
+fn escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
@@ -0,0 +1,229 @@
+//! Tools for making several tile edits that either all succeed or all fail
+
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::coord::TileCoord;
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::validation::TileOverlay;
+
+/// A set of tile edits that are only applied once all of them have been validated
+///
+/// Created by [`TilePlacer::transaction`]. Each edit goes through the same checks as it would
+/// outside a transaction, against the map as it would be after the previous edits of the
+/// transaction. This means placing a tile and then attempting to [`try_place`](Self::try_place)
+/// another at the same position fails, and [`PlacementValidator`]s see the earlier edits.
+///
+/// Atomicity only covers this validation: once every edit is validated, they are applied one
+/// after the other, and a failure while applying them (such as a [`PlacementValidator`]
+/// rejecting a placement it accepted against the transaction's view of the map) leaves the
+/// edits applied before it in place.
+///
+/// [`PlacementValidator`]: crate::prelude::PlacementValidator
+pub struct TileTransaction<'a, 'w, 's> {
+	placer: &'a TilePlacer<'w, 's>,
+	/// The edited cells, in the order they were first edited
+	edited: Vec<TileCoord>,
+	/// The final state of each cell edited by this transaction
+	overlay: TileOverlay,
+}
+
+impl<'a, 'w, 's> TileTransaction<'a, 'w, 's> {
+	/// Queue a tile to be placed, replacing any existing tile
+	///
+	/// Tiles skipped by the [`PlacementPolicy`](crate::prelude::PlacementPolicy) aren't queued.
+	pub fn place<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<(), TilePlacementError> {
+		let id = tile_id.into();
+		let map_id = map_id.into();
+		let resolved =
+			self.placer
+				.can_place(&id, pos.into(), map_id, layer_id, Some(&self.overlay))?;
+		let pos = match resolved {
+			Some(pos) => pos,
+			None => return Ok(()),
+		};
+
+		self.set(
+			TileCoord {
+				pos,
+				map_id,
+				layer_id,
+			},
+			Some(id),
+		);
+		Ok(())
+	}
+
+	/// Queue a tile to be placed, failing if a tile already exists at the given position
+	pub fn try_place<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<(), TilePlacementError> {
+		let id = tile_id.into();
+		let pos = pos.into();
+
+		if let Some(existing) = self.get(pos, map_id, layer_id) {
+			return Err(TilePlacementError::TileAlreadyExists {
+				new: id,
+				existing: Some(existing),
+				pos,
+			});
+		}
+
+		self.place(id, pos, map_id, layer_id)
+	}
+
+	/// Queue a tile to be placed, failing if a tile of the same group already exists at the
+	/// given position
+	pub fn replace<Id: Into<TileId>, Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<(), TilePlacementError> {
+		let id = tile_id.into();
+		let pos = pos.into();
+
+		if let Some(existing) = self.get(pos, map_id, layer_id) {
+			if existing.eq_tile_group(&id) {
+				return Err(TilePlacementError::TileAlreadyExists {
+					new: id,
+					existing: Some(existing),
+					pos,
+				});
+			}
+		}
+
+		self.place(id, pos, map_id, layer_id)
+	}
+
+	/// Queue the tile at the given position to be removed
+	///
+	/// Removing an empty cell does nothing. Fails if the position lies outside the layer's
+	/// [`PlacementMask`](crate::prelude::PlacementMask).
+	pub fn remove<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<(), TilePlacementError> {
+		let coord = TileCoord {
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+		};
+		if self
+			.placer
+			.check_placement_mask(coord.pos, coord.map_id, layer_id)?
+		{
+			self.set(coord, None);
+		}
+		Ok(())
+	}
+
+	/// Get the tile at the given position, including the edits made by this transaction
+	pub fn get<Pos: Into<TilePos>, MId: MapId>(
+		&self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+	) -> Option<TileId> {
+		let coord = TileCoord {
+			pos: pos.into(),
+			map_id: map_id.into(),
+			layer_id,
+		};
+
+		if let Some(tile_id) = self.overlay.get(&coord) {
+			return *tile_id;
+		}
+
+		let entity = self.placer.find_entity(coord.pos, coord.map_id, layer_id)?;
+		self.placer.tile_id_of(entity)
+	}
+
+	/// Set the final state of the given cell
+	fn set(&mut self, coord: TileCoord, tile_id: Option<TileId>) {
+		if self.overlay.insert(coord, tile_id).is_none() {
+			self.edited.push(coord);
+		}
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Make several edits that are only applied if all of them pass validation
+	///
+	/// The given closure queues its edits on a [`TileTransaction`]. Nothing is applied to the map
+	/// until the closure returns, and if it (or any of its edits) returns an error, none of the
+	/// edits are applied at all. This makes it easy to place structures spanning multiple cells
+	/// without leaving half of them behind when one of them is invalid. Errors while applying the
+	/// validated edits are still returned, but don't undo the edits applied before them (see
+	/// [`TileTransaction`]).
+	///
+	/// Only the final state of each edited cell is applied, so placing and then removing a tile
+	/// within the same transaction leaves the cell as it was.
+	///
+	/// Returns the results of each applied edit.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::{TileId, TilePlacer};
+	/// fn place_door(mut placer: TilePlacer) {
+	///   # let (bottom, top) = (TileId::new(0, 0), TileId::new(1, 0));
+	///   let placed = placer.transaction(|tx| {
+	///     tx.try_place(bottom, TilePos { x: 4, y: 2 }, 0u16, 1)?;
+	///     tx.try_place(top, TilePos { x: 4, y: 3 }, 0u16, 1)
+	///   });
+	/// }
+	/// ```
+	pub fn transaction<F>(&mut self, edits: F) -> Result<Vec<PlacedTile>, TilePlacementError>
+	where
+		F: FnOnce(&mut TileTransaction<'_, 'w, 's>) -> Result<(), TilePlacementError>,
+	{
+		let mut tx = TileTransaction {
+			placer: self,
+			edited: Vec::new(),
+			overlay: HashMap::default(),
+		};
+		edits(&mut tx)?;
+		let TileTransaction {
+			edited, overlay, ..
+		} = tx;
+
+		let mut placed = Vec::with_capacity(edited.len());
+		for coord in edited {
+			let TileCoord {
+				pos,
+				map_id,
+				layer_id,
+			} = coord;
+			match overlay[&coord] {
+				Some(tile_id) => placed.push(self.place_unchecked(tile_id, pos, map_id, layer_id)?),
+				None => {
+					if let Some(entity) = self.find_entity(pos, map_id, layer_id) {
+						let tile_id = self.tile_id_of(entity);
+						self.remove(pos, map_id, layer_id)?;
+						placed.push(PlacedTile::Removed {
+							old_tile: Some((entity, tile_id)),
+						});
+					}
+				},
+			}
+		}
+
+		Ok(placed)
+	}
+}
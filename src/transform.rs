@@ -0,0 +1,184 @@
+//! Tools for placing flipped and rotated tiles
+
+use bevy::reflect::{FromReflect, Reflect};
+use bevy_ecs_tilemap::prelude::{TileFlip, TilePos};
+use bevy_tileset::prelude::TileId;
+
+use crate::placement::{MapId, TilePlacementResult, TilePlacer};
+
+/// A clockwise rotation applied to a tile
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect, FromReflect)]
+#[cfg_attr(
+	feature = "serialization",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum TileRotation {
+	/// No rotation
+	None,
+	/// Rotated 90° clockwise
+	Cw90,
+	/// Rotated 180°
+	Cw180,
+	/// Rotated 270° clockwise (90° counter-clockwise)
+	Cw270,
+}
+
+impl Default for TileRotation {
+	fn default() -> Self {
+		Self::None
+	}
+}
+
+impl TileRotation {
+	/// Get this rotation followed by another one
+	pub fn then(self, other: TileRotation) -> Self {
+		Self::from_steps(self.steps() + other.steps())
+	}
+
+	/// The number of 90° clockwise steps in this rotation
+	fn steps(self) -> u8 {
+		match self {
+			Self::None => 0,
+			Self::Cw90 => 1,
+			Self::Cw180 => 2,
+			Self::Cw270 => 3,
+		}
+	}
+
+	/// Create a rotation from a number of 90° clockwise steps
+	fn from_steps(steps: u8) -> Self {
+		match steps % 4 {
+			0 => Self::None,
+			1 => Self::Cw90,
+			2 => Self::Cw180,
+			_ => Self::Cw270,
+		}
+	}
+}
+
+/// The flip and rotation of a tile
+///
+/// The rotation is applied first, followed by the flips. This is converted to a [`TileFlip`]
+/// using the same conventions as [Tiled](https://www.mapeditor.org/).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect, FromReflect)]
+#[cfg_attr(
+	feature = "serialization",
+	derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TileTransform {
+	/// Mirror the tile horizontally
+	pub flip_x: bool,
+	/// Mirror the tile vertically
+	pub flip_y: bool,
+	/// Rotate the tile clockwise
+	pub rotation: TileRotation,
+}
+
+impl TileTransform {
+	/// A transform that leaves the tile unchanged
+	pub const IDENTITY: Self = Self {
+		flip_x: false,
+		flip_y: false,
+		rotation: TileRotation::None,
+	};
+
+	/// Create a transform with the given rotation
+	pub fn rotated(rotation: TileRotation) -> Self {
+		Self {
+			rotation,
+			..Self::IDENTITY
+		}
+	}
+
+	/// Mirror this transform horizontally
+	pub fn with_flip_x(mut self) -> Self {
+		self.flip_x = !self.flip_x;
+		self
+	}
+
+	/// Mirror this transform vertically
+	pub fn with_flip_y(mut self) -> Self {
+		self.flip_y = !self.flip_y;
+		self
+	}
+}
+
+impl From<TileTransform> for TileFlip {
+	fn from(transform: TileTransform) -> Self {
+		let (x, y, d) = match transform.rotation {
+			TileRotation::None => (false, false, false),
+			TileRotation::Cw90 => (true, false, true),
+			TileRotation::Cw180 => (true, true, false),
+			TileRotation::Cw270 => (false, true, true),
+		};
+		TileFlip {
+			x: x ^ transform.flip_x,
+			y: y ^ transform.flip_y,
+			d,
+		}
+	}
+}
+
+impl From<TileFlip> for TileTransform {
+	fn from(flip: TileFlip) -> Self {
+		let rotation = match (flip.d, flip.x, flip.y) {
+			(false, ..) => TileRotation::None,
+			(true, true, _) => TileRotation::Cw90,
+			(true, false, _) => TileRotation::Cw270,
+		};
+		let base = TileFlip::from(Self::rotated(rotation));
+		Self {
+			flip_x: flip.x != base.x,
+			flip_y: flip.y != base.y,
+			rotation,
+		}
+	}
+}
+
+impl From<TileRotation> for TileTransform {
+	fn from(rotation: TileRotation) -> Self {
+		Self::rotated(rotation)
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Same as [`place`](Self::place), but flipping and rotating the placed tile
+	pub fn place_with_transform<
+		Id: Into<TileId>,
+		Pos: Into<TilePos> + Clone,
+		MId: MapId,
+		T: Into<TileTransform>,
+	>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		transform: T,
+	) -> TilePlacementResult {
+		let result = self.place(tile_id, pos, map_id, layer_id)?;
+		let transform: TileTransform = transform.into();
+		self.insert_on_added(&result, TileFlip::from(transform));
+		Ok(result)
+	}
+
+	/// Same as [`replace`](Self::replace), but flipping and rotating the placed tile
+	pub fn replace_with_transform<
+		Id: Into<TileId>,
+		Pos: Into<TilePos> + Clone,
+		MId: MapId,
+		T: Into<TileTransform>,
+	>(
+		&mut self,
+		tile_id: Id,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		transform: T,
+	) -> TilePlacementResult {
+		let result = self.replace(tile_id, pos, map_id, layer_id)?;
+		let transform: TileTransform = transform.into();
+		self.insert_on_added(&result, TileFlip::from(transform));
+		Ok(result)
+	}
+}
@@ -0,0 +1,211 @@
+//! Tools for enforcing game rules on tile placement
+
+use bevy::prelude::{App, Resource};
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+
+use crate::coord::TileCoord;
+use crate::placement::{TilePlacementError, TilePlacer};
+
+/// The pending state of the cells edited by a [`TileTransaction`](crate::prelude::TileTransaction)
+pub(crate) type TileOverlay = HashMap<TileCoord, Option<TileId>>;
+
+/// A rule consulted by the [`TilePlacer`] before placing a tile
+///
+/// Validators are registered with the [`PlacementValidators`] resource (or via
+/// [`AddPlacementValidator::add_placement_validator`]). If any validator rejects a placement,
+/// the tile is not placed and a [`TilePlacementError::Rejected`] error is returned instead.
+///
+/// Any `Fn(&PlacementContext) -> Result<(), String>` closure can be used as a validator.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{PlacementContext, PlacementValidator};
+/// /// Prevents water from being placed next to lava
+/// struct NoWaterNextToLava;
+///
+/// impl PlacementValidator for NoWaterNextToLava {
+///   fn validate(&self, placement: &PlacementContext) -> Result<(), String> {
+///     if placement.tile_name(&placement.tile_id) != Some("Water") {
+///       return Ok(());
+///     }
+///
+///     let next_to_lava = placement
+///       .neighbor_tiles()
+///       .iter()
+///       .any(|(_, tile_id)| placement.tile_name(tile_id) == Some("Lava"));
+///     if next_to_lava {
+///       Err(String::from("Water cannot be placed next to lava"))
+///     } else {
+///       Ok(())
+///     }
+///   }
+/// }
+/// ```
+pub trait PlacementValidator: Send + Sync + 'static {
+	/// Check whether the given placement is allowed
+	///
+	/// Returns an error containing the reason the placement was rejected.
+	fn validate(&self, placement: &PlacementContext) -> Result<(), String>;
+}
+
+impl<F: Fn(&PlacementContext) -> Result<(), String> + Send + Sync + 'static> PlacementValidator
+	for F
+{
+	fn validate(&self, placement: &PlacementContext) -> Result<(), String> {
+		self(placement)
+	}
+}
+
+/// A resource containing all registered [`PlacementValidator`]s
+#[derive(Resource, Default)]
+pub struct PlacementValidators {
+	validators: Vec<Box<dyn PlacementValidator>>,
+}
+
+impl PlacementValidators {
+	/// Register a validator
+	pub fn add<V: PlacementValidator>(&mut self, validator: V) -> &mut Self {
+		self.validators.push(Box::new(validator));
+		self
+	}
+
+	/// Returns true if no validators are registered
+	pub fn is_empty(&self) -> bool {
+		self.validators.is_empty()
+	}
+
+	/// Check the given placement against every registered validator
+	pub fn validate(&self, placement: &PlacementContext) -> Result<(), TilePlacementError> {
+		for validator in &self.validators {
+			validator
+				.validate(placement)
+				.map_err(TilePlacementError::Rejected)?;
+		}
+		Ok(())
+	}
+}
+
+/// An extension trait for registering [`PlacementValidator`]s on an [`App`]
+pub trait AddPlacementValidator {
+	/// Register a validator with the [`PlacementValidators`] resource
+	fn add_placement_validator<V: PlacementValidator>(&mut self, validator: V) -> &mut Self;
+}
+
+impl AddPlacementValidator for App {
+	fn add_placement_validator<V: PlacementValidator>(&mut self, validator: V) -> &mut Self {
+		self.init_resource::<PlacementValidators>();
+		self.world
+			.resource_mut::<PlacementValidators>()
+			.add(validator);
+		self
+	}
+}
+
+/// A tile placement to be checked by a [`PlacementValidator`]
+///
+/// Along with the placement itself, this gives access to the current state of the map.
+pub struct PlacementContext<'a, 'w, 's> {
+	/// The tile to be placed
+	pub tile_id: TileId,
+	/// The position the tile will be placed at
+	pub pos: TilePos,
+	/// The map the tile will be placed in
+	pub map_id: u16,
+	/// The layer the tile will be placed in
+	pub layer_id: u16,
+	placer: &'a TilePlacer<'w, 's>,
+	/// The edits made so far by the transaction this placement is part of (if any)
+	overlay: Option<&'a TileOverlay>,
+}
+
+impl<'a, 'w, 's> PlacementContext<'a, 'w, 's> {
+	/// Get the tile currently at the given position in the same map
+	///
+	/// Within a [`TileTransaction`](crate::prelude::TileTransaction), this includes the edits
+	/// queued before this placement.
+	pub fn tile_at(&self, pos: TilePos, layer_id: u16) -> Option<TileId> {
+		let coord = TileCoord {
+			pos,
+			map_id: self.map_id,
+			layer_id,
+		};
+		if let Some(tile_id) = self.overlay.and_then(|overlay| overlay.get(&coord)) {
+			return *tile_id;
+		}
+
+		let entity = self.placer.find_entity(pos, self.map_id, layer_id)?;
+		self.placer.tile_id_of(entity)
+	}
+
+	/// Get the tile that will be replaced by this placement (if any)
+	pub fn existing(&self) -> Option<TileId> {
+		self.tile_at(self.pos, self.layer_id)
+	}
+
+	/// Get the positions neighboring this placement
+	///
+	/// Neighbors are returned in the order: north, east, south, west, north-east, north-west,
+	/// south-east, and south-west. Directions that don't exist for the map type or lie outside
+	/// the map are `None`.
+	pub fn neighbors(&self) -> [Option<TilePos>; 8] {
+		self.placer
+			.grids
+			.get(self.map_id, self.layer_id)
+			.neighbors(&self.pos)
+	}
+
+	/// Get the tiles neighboring this placement in the same layer
+	pub fn neighbor_tiles(&self) -> Vec<(TilePos, TileId)> {
+		self.neighbors()
+			.into_iter()
+			.flatten()
+			.filter_map(|pos| Some((pos, self.tile_at(pos, self.layer_id)?)))
+			.collect()
+	}
+
+	/// Get the name of the given tile
+	pub fn tile_name(&self, tile_id: &TileId) -> Option<&str> {
+		self.placer
+			.tilesets
+			.get_by_id(&tile_id.tileset_id)?
+			.get_tile_name(&tile_id.group_id)
+			.map(String::as_str)
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Check the given placement against the layer's [`PlacementMask`] and [`LayerConstraints`],
+	/// followed by the registered [`PlacementValidator`]s
+	///
+	/// Positions skipped by the mask pass, since they're skipped once placed. The given overlay
+	/// (if any) is applied on top of the map when validators inspect it.
+	///
+	/// [`PlacementMask`]: crate::prelude::PlacementMask
+	/// [`LayerConstraints`]: crate::prelude::LayerConstraints
+	pub(crate) fn validate_placement(
+		&self,
+		tile_id: TileId,
+		pos: TilePos,
+		map_id: u16,
+		layer_id: u16,
+		overlay: Option<&TileOverlay>,
+	) -> Result<(), TilePlacementError> {
+		self.check_placement_mask(pos, map_id, layer_id)?;
+		self.check_layer_constraints(&tile_id, map_id, layer_id)?;
+		if self.validators.is_empty() {
+			return Ok(());
+		}
+
+		self.validators.validate(&PlacementContext {
+			tile_id,
+			pos,
+			map_id,
+			layer_id,
+			placer: self,
+			overlay,
+		})
+	}
+}
@@ -0,0 +1,42 @@
+//! Tools for hiding placed tiles
+
+use bevy::prelude::Entity;
+use bevy_ecs_tilemap::prelude::{TilePos, TileVisible};
+
+use crate::placement::{MapId, TilePlacer};
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Show or hide the tile at the given position
+	///
+	/// Unlike removing it, hiding a tile keeps it on the map: it still counts as occupying its
+	/// position, keeps its auto tile connections to its neighbors, and is saved along with the
+	/// rest of the map. This makes it suitable for mechanics like fog of war or hiding the roofs
+	/// of buildings the player is inside.
+	///
+	/// Returns the tile entity, or `None` if no tile exists at that position.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bevy_ecs_tilemap::prelude::TilePos;
+	/// # use bevy_tileset_map::prelude::TilePlacer;
+	/// fn hide_roof(mut placer: TilePlacer) {
+	///   for x in 4..8 {
+	///     for y in 4..8 {
+	///       placer.set_visible(TilePos { x, y }, 0u16, 2, false);
+	///     }
+	///   }
+	/// }
+	/// ```
+	pub fn set_visible<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		pos: Pos,
+		map_id: MId,
+		layer_id: u16,
+		visible: bool,
+	) -> Option<Entity> {
+		let entity = self.find_entity(pos.into(), map_id, layer_id)?;
+		self.commands.entity(entity).insert(TileVisible(visible));
+		Some(entity)
+	}
+}
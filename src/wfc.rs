@@ -0,0 +1,420 @@
+//! Tools for filling regions of a map using Wave Function Collapse
+
+use bevy::math::UVec2;
+use bevy::utils::HashMap;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_tileset::prelude::TileId;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{thread_rng, Rng};
+
+use crate::placement::{MapId, PlacedTile, TilePlacementError, TilePlacer};
+use crate::stamp::TileStamp;
+
+/// The number of times a collapse is restarted after reaching a contradiction
+const MAX_ATTEMPTS: usize = 16;
+
+/// A direction between two adjacent cells
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WfcDirection {
+	North,
+	East,
+	South,
+	West,
+}
+
+impl WfcDirection {
+	/// All directions, in the same order as the bits of a connection mask
+	pub const ALL: [WfcDirection; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+	/// Get the opposite direction
+	pub fn opposite(self) -> Self {
+		match self {
+			Self::North => Self::South,
+			Self::East => Self::West,
+			Self::South => Self::North,
+			Self::West => Self::East,
+		}
+	}
+
+	/// The index of this direction in [`ALL`](Self::ALL)
+	fn index(self) -> usize {
+		self as usize
+	}
+
+	/// Get the position one step in this direction, if it lies within the given size
+	fn step(self, x: u32, y: u32, size: UVec2) -> Option<(u32, u32)> {
+		let (x, y) = match self {
+			Self::North => (x, y.checked_add(1)?),
+			Self::East => (x.checked_add(1)?, y),
+			Self::South => (x, y.checked_sub(1)?),
+			Self::West => (x.checked_sub(1)?, y),
+		};
+		(x < size.x && y < size.y).then(|| (x, y))
+	}
+}
+
+/// The adjacency constraints used by [`TilePlacer::collapse`]
+///
+/// Each tile is given a weight (how likely it is to be chosen) and the set of tiles allowed to
+/// be placed next to it in each direction. Adjacency is always symmetric: allowing `b` north of
+/// `a` also allows `a` south of `b`.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_tileset_map::prelude::{TileId, WfcConstraints, WfcDirection};
+/// # let grass = TileId::new(0, 0);
+/// # let sand = TileId::new(1, 0);
+/// # let water = TileId::new(2, 0);
+/// let mut constraints = WfcConstraints::default();
+/// constraints
+///   .add_tile(grass, 1.0)
+///   .add_tile(sand, 0.5)
+///   .add_tile(water, 1.0)
+///   .allow_all(grass, grass)
+///   .allow_all(grass, sand)
+///   .allow_all(sand, sand)
+///   .allow_all(sand, water)
+///   .allow_all(water, water);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WfcConstraints {
+	tiles: Vec<TileId>,
+	weights: Vec<f32>,
+	/// For each tile and direction, whether each other tile may be placed in that direction
+	allowed: Vec<[Vec<bool>; 4]>,
+}
+
+impl WfcConstraints {
+	/// Create constraints from tiles with the given connection masks
+	///
+	/// Masks use the same bits as the [`CardinalRuleSet`](crate::prelude::CardinalRuleSet):
+	/// `north = 1`, `east = 2`, `south = 4`, and `west = 8`. Two tiles may be adjacent if they
+	/// either both connect or both don't connect across their shared edge, as with pipes and
+	/// roads.
+	pub fn from_connections(tiles: &[(TileId, u8, f32)]) -> Self {
+		let mut constraints = Self::default();
+		for (tile, _, weight) in tiles {
+			constraints.add_tile(*tile, *weight);
+		}
+
+		for (a, mask_a, _) in tiles {
+			for (b, mask_b, _) in tiles {
+				for dir in WfcDirection::ALL {
+					let connects_a = mask_a & (1 << dir.index()) != 0;
+					let connects_b = mask_b & (1 << dir.opposite().index()) != 0;
+					if connects_a == connects_b {
+						constraints.allow(*a, dir, *b);
+					}
+				}
+			}
+		}
+
+		constraints
+	}
+
+	/// Create constraints from the 16 tiles of a [`CardinalRuleSet`](crate::prelude::CardinalRuleSet)
+	///
+	/// The tile at each index connects in the directions given by the bits of that index (see
+	/// [`from_connections`](Self::from_connections)). Every tile is given the same weight.
+	pub fn from_cardinal(tiles: [TileId; 16]) -> Self {
+		let tiles = tiles
+			.iter()
+			.enumerate()
+			.map(|(mask, tile)| (*tile, mask as u8, 1.0))
+			.collect::<Vec<_>>();
+		Self::from_connections(&tiles)
+	}
+
+	/// Add a tile that may be placed, with the given weight
+	///
+	/// Adding a tile that already exists updates its weight.
+	pub fn add_tile(&mut self, tile: TileId, weight: f32) -> &mut Self {
+		match self.index_of(&tile) {
+			Some(index) => self.weights[index] = weight,
+			None => {
+				self.tiles.push(tile);
+				self.weights.push(weight);
+				for allowed in &mut self.allowed {
+					for dir in allowed.iter_mut() {
+						dir.push(false);
+					}
+				}
+				let len = self.tiles.len();
+				self.allowed.push([
+					vec![false; len],
+					vec![false; len],
+					vec![false; len],
+					vec![false; len],
+				]);
+			},
+		}
+		self
+	}
+
+	/// Allow `b` to be placed in the given direction of `a` (and `a` opposite of `b`)
+	///
+	/// Both tiles are added (with a weight of `1.0`) if they haven't been already.
+	pub fn allow(&mut self, a: TileId, dir: WfcDirection, b: TileId) -> &mut Self {
+		let a = self.index_or_add(a);
+		let b = self.index_or_add(b);
+		self.allowed[a][dir.index()][b] = true;
+		self.allowed[b][dir.opposite().index()][a] = true;
+		self
+	}
+
+	/// Allow `a` and `b` to be placed next to each other in every direction
+	pub fn allow_all(&mut self, a: TileId, b: TileId) -> &mut Self {
+		for dir in WfcDirection::ALL {
+			self.allow(a, dir, b);
+		}
+		self
+	}
+
+	/// Returns true if `b` may be placed in the given direction of `a`
+	pub fn is_allowed(&self, a: &TileId, dir: WfcDirection, b: &TileId) -> bool {
+		match (self.index_of(a), self.index_of(b)) {
+			(Some(a), Some(b)) => self.allowed[a][dir.index()][b],
+			_ => false,
+		}
+	}
+
+	/// Find a valid tiling for a region of the given size
+	///
+	/// `border` contains tiles just outside the region (using positions relative to the
+	/// region, so the cell left of the region's bottom-left corner is at `(-1, 0)`) that the
+	/// tiling must be compatible with. Tiles not contained in these constraints are ignored.
+	///
+	/// Returns the tiles in row-major order (starting from the bottom-left), or `None` if no
+	/// valid tiling was found.
+	pub fn solve<R: Rng>(
+		&self,
+		size: UVec2,
+		border: &HashMap<(i64, i64), TileId>,
+		rng: &mut R,
+	) -> Option<Vec<TileId>> {
+		if self.tiles.is_empty() {
+			return None;
+		}
+
+		(0..MAX_ATTEMPTS).find_map(|_| self.try_solve(size, border, rng))
+	}
+
+	/// Attempt a single collapse, returning `None` on a contradiction
+	fn try_solve<R: Rng>(
+		&self,
+		size: UVec2,
+		border: &HashMap<(i64, i64), TileId>,
+		rng: &mut R,
+	) -> Option<Vec<TileId>> {
+		let len = self.tiles.len();
+		let mut cells = vec![vec![true; len]; (size.x * size.y) as usize];
+		let index = |x: u32, y: u32| (y * size.x + x) as usize;
+
+		// === Border === //
+		let mut pending = Vec::new();
+		for y in 0..size.y {
+			for x in 0..size.x {
+				for dir in WfcDirection::ALL {
+					let (nx, ny) = match dir {
+						WfcDirection::North => (x as i64, y as i64 + 1),
+						WfcDirection::East => (x as i64 + 1, y as i64),
+						WfcDirection::South => (x as i64, y as i64 - 1),
+						WfcDirection::West => (x as i64 - 1, y as i64),
+					};
+					let neighbor = border.get(&(nx, ny)).and_then(|tile| self.index_of(tile));
+					if let Some(neighbor) = neighbor {
+						let allowed = &self.allowed[neighbor][dir.opposite().index()];
+						let cell = &mut cells[index(x, y)];
+						for (option, allowed) in cell.iter_mut().zip(allowed) {
+							*option &= *allowed;
+						}
+						pending.push((x, y));
+					}
+				}
+			}
+		}
+		self.propagate(&mut cells, &mut pending, size)?;
+
+		// === Collapse === //
+		loop {
+			// Find the undecided cell with the fewest options
+			let next = cells
+				.iter()
+				.enumerate()
+				.map(|(i, cell)| (i, cell.iter().filter(|option| **option).count()))
+				.filter(|(_, count)| *count > 1)
+				.min_by_key(|(_, count)| *count);
+			let (cell_index, _) = match next {
+				Some(next) => next,
+				None => break,
+			};
+
+			let options = cells[cell_index]
+				.iter()
+				.enumerate()
+				.filter(|(_, option)| **option)
+				.map(|(tile, _)| tile)
+				.collect::<Vec<_>>();
+			let weights = options
+				.iter()
+				.map(|tile| self.weights[*tile].max(f32::EPSILON));
+			let chosen = options[WeightedIndex::new(weights).ok()?.sample(rng)];
+
+			for (tile, option) in cells[cell_index].iter_mut().enumerate() {
+				*option = tile == chosen;
+			}
+			let x = cell_index as u32 % size.x;
+			let y = cell_index as u32 / size.x;
+			pending.push((x, y));
+			self.propagate(&mut cells, &mut pending, size)?;
+		}
+
+		cells
+			.iter()
+			.map(|cell| {
+				let tile = cell.iter().position(|option| *option)?;
+				Some(self.tiles[tile])
+			})
+			.collect()
+	}
+
+	/// Remove the options of neighboring cells that are no longer allowed
+	///
+	/// Returns `None` if a cell is left without any options.
+	fn propagate(
+		&self,
+		cells: &mut [Vec<bool>],
+		pending: &mut Vec<(u32, u32)>,
+		size: UVec2,
+	) -> Option<()> {
+		let len = self.tiles.len();
+		while let Some((x, y)) = pending.pop() {
+			let cell = cells[(y * size.x + x) as usize].clone();
+			if !cell.contains(&true) {
+				return None;
+			}
+
+			for dir in WfcDirection::ALL {
+				let (nx, ny) = match dir.step(x, y, size) {
+					Some(pos) => pos,
+					None => continue,
+				};
+
+				// Collect every option allowed next to any remaining option of this cell
+				let mut allowed = vec![false; len];
+				for tile in (0..len).filter(|tile| cell[*tile]) {
+					for (allowed, is_allowed) in
+						allowed.iter_mut().zip(&self.allowed[tile][dir.index()])
+					{
+						*allowed |= *is_allowed;
+					}
+				}
+
+				let neighbor = &mut cells[(ny * size.x + nx) as usize];
+				let mut changed = false;
+				for (option, allowed) in neighbor.iter_mut().zip(&allowed) {
+					if *option && !*allowed {
+						*option = false;
+						changed = true;
+					}
+				}
+				if changed {
+					pending.push((nx, ny));
+				}
+			}
+		}
+		Some(())
+	}
+
+	/// Get the index of the given tile
+	fn index_of(&self, tile: &TileId) -> Option<usize> {
+		self.tiles.iter().position(|other| other == tile)
+	}
+
+	/// Get the index of the given tile, adding it if needed
+	fn index_or_add(&mut self, tile: TileId) -> usize {
+		match self.index_of(&tile) {
+			Some(index) => index,
+			None => {
+				self.add_tile(tile, 1.0);
+				self.tiles.len() - 1
+			},
+		}
+	}
+}
+
+impl<'w, 's> TilePlacer<'w, 's> {
+	/// Fill a region of the given layer with a valid tiling, using Wave Function Collapse
+	///
+	/// The region starts at `origin` (its bottom-left corner) and spans `size` tiles. Any
+	/// existing tiles directly bordering the region that appear in the constraints are taken
+	/// into account, so the filled region fits in with its surroundings.
+	///
+	/// Like [`place_stamp`](Self::place_stamp), every tile is validated before any are placed.
+	///
+	/// Returns the results of each placed tile.
+	pub fn collapse<Pos: Into<TilePos>, MId: MapId>(
+		&mut self,
+		origin: Pos,
+		size: UVec2,
+		constraints: &WfcConstraints,
+		map_id: MId,
+		layer_id: u16,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		self.collapse_with_rng(
+			origin,
+			size,
+			constraints,
+			map_id,
+			layer_id,
+			&mut thread_rng(),
+		)
+	}
+
+	/// Same as [`collapse`](Self::collapse), but using the given random number generator
+	///
+	/// This can be used with a seeded generator to produce the same tiling every time.
+	pub fn collapse_with_rng<Pos: Into<TilePos>, MId: MapId, R: Rng>(
+		&mut self,
+		origin: Pos,
+		size: UVec2,
+		constraints: &WfcConstraints,
+		map_id: MId,
+		layer_id: u16,
+		rng: &mut R,
+	) -> Result<Vec<PlacedTile>, TilePlacementError> {
+		let origin = origin.into();
+
+		let border = self
+			.layer_tiles(map_id, layer_id)
+			.into_iter()
+			.filter_map(|(_, pos, tile_id)| {
+				let x = pos.x as i64 - origin.x as i64;
+				let y = pos.y as i64 - origin.y as i64;
+				let inside = (0..size.x as i64).contains(&x) && (0..size.y as i64).contains(&y);
+				let adjacent =
+					(-1..=size.x as i64).contains(&x) && (-1..=size.y as i64).contains(&y);
+				if inside || !adjacent {
+					return None;
+				}
+				Some(((x, y), tile_id?))
+			})
+			.collect();
+
+		let tiles = constraints
+			.solve(size, &border, rng)
+			.ok_or(TilePlacementError::NoValidTiling)?;
+
+		let mut stamp = TileStamp::new(size.x, size.y);
+		for (index, tile) in tiles.into_iter().enumerate() {
+			let pos = TilePos {
+				x: index as u32 % size.x,
+				y: index as u32 / size.x,
+			};
+			stamp.set(pos, 0, Some(tile));
+		}
+
+		self.place_stamp(&stamp, origin, map_id, layer_id)
+	}
+}